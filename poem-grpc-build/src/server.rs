@@ -100,7 +100,10 @@ pub(crate) fn generate(config: &GrpcConfig, service: &Service, buf: &mut String)
 
         #[allow(unused_imports)]
         #[derive(Clone)]
-        pub struct #server_ident<T>(::std::sync::Arc<T>);
+        pub struct #server_ident<T> {
+            inner: ::std::sync::Arc<T>,
+            compression: #crate_name::server::CompressionConfig,
+        }
 
         impl<T: #service_ident> #crate_name::Service for #server_ident<T> {
             const NAME: &'static str = #service_name;
@@ -110,7 +113,31 @@ pub(crate) fn generate(config: &GrpcConfig, service: &Service, buf: &mut String)
         #[allow(dead_code)]
         impl<T> #server_ident<T> {
             pub fn new(service: T) -> Self {
-                Self(::std::sync::Arc::new(service))
+                Self {
+                    inner: ::std::sync::Arc::new(service),
+                    compression: ::std::default::Default::default(),
+                }
+            }
+
+            /// Compress the response messages with the specified encoding if the client accepts it.
+            #[must_use]
+            pub fn send_compressed(mut self, encoding: #crate_name::CompressionEncoding) -> Self {
+                self.compression.send_compressed = Some(encoding);
+                self
+            }
+
+            /// Sets the minimum size of the response messages to be compressed, defaults to `0`.
+            #[must_use]
+            pub fn compression_min_size(mut self, size: usize) -> Self {
+                self.compression.min_size = size;
+                self
+            }
+
+            /// Sets the maximum size of the request messages after decompression, defaults to `4MB`.
+            #[must_use]
+            pub fn max_decompressed_size(mut self, size: usize) -> Self {
+                self.compression.max_decompressed_size = size;
+                self
             }
         }
 
@@ -191,7 +218,7 @@ fn generate_unary(codec_list: &[Path], method_info: MethodInfo) -> TokenStream {
         crate_name,
         codec_list,
         quote! {
-            #crate_name::server::GrpcServer::new(codec).unary(#proxy_service_ident(svc.clone()), req).await
+            #crate_name::server::GrpcServer::new(codec).with_compression(compression).unary(#proxy_service_ident(svc.clone()), req).await
         },
     );
 
@@ -211,7 +238,8 @@ fn generate_unary(codec_list: &[Path], method_info: MethodInfo) -> TokenStream {
         }
 
         route = route.at(#path, ::poem::endpoint::make({
-            let svc = self.0.clone();
+            let svc = self.inner.clone();
+            let compression = self.compression;
             move |req| {
                 let svc = svc.clone();
                 async move { #call }
@@ -235,7 +263,7 @@ fn generate_client_streaming(codec_list: &[Path], method_info: MethodInfo) -> To
         crate_name,
         codec_list,
         quote! {
-            #crate_name::server::GrpcServer::new(codec).client_streaming(#proxy_service_ident(svc.clone()), req).await
+            #crate_name::server::GrpcServer::new(codec).with_compression(compression).client_streaming(#proxy_service_ident(svc.clone()), req).await
         },
     );
 
@@ -255,7 +283,8 @@ fn generate_client_streaming(codec_list: &[Path], method_info: MethodInfo) -> To
         }
 
         route = route.at(#path, ::poem::endpoint::make({
-            let svc = self.0.clone();
+            let svc = self.inner.clone();
+            let compression = self.compression;
             move |req| {
                 let svc = svc.clone();
                 async move { #call }
@@ -279,7 +308,7 @@ fn generate_server_streaming(codec_list: &[Path], method_info: MethodInfo) -> To
         crate_name,
         codec_list,
         quote! {
-            #crate_name::server::GrpcServer::new(codec).server_streaming(#proxy_service_ident(svc.clone()), req).await
+            #crate_name::server::GrpcServer::new(codec).with_compression(compression).server_streaming(#proxy_service_ident(svc.clone()), req).await
         },
     );
 
@@ -299,7 +328,8 @@ fn generate_server_streaming(codec_list: &[Path], method_info: MethodInfo) -> To
         }
 
         route = route.at(#path, ::poem::endpoint::make({
-            let svc = self.0.clone();
+            let svc = self.inner.clone();
+            let compression = self.compression;
             move |req| {
                 let svc = svc.clone();
                 async move { #call }
@@ -323,7 +353,7 @@ fn generate_bidirectional_streaming(codec_list: &[Path], method_info: MethodInfo
         crate_name,
         codec_list,
         quote! {
-            #crate_name::server::GrpcServer::new(codec).bidirectional_streaming(#proxy_service_ident(svc.clone()), req).await
+            #crate_name::server::GrpcServer::new(codec).with_compression(compression).bidirectional_streaming(#proxy_service_ident(svc.clone()), req).await
         },
     );
 
@@ -343,7 +373,8 @@ fn generate_bidirectional_streaming(codec_list: &[Path], method_info: MethodInfo
         }
 
         route = route.at(#path, ::poem::endpoint::make({
-            let svc = self.0.clone();
+            let svc = self.inner.clone();
+            let compression = self.compression;
             move |req| {
                 let svc = svc.clone();
                 async move { #call }
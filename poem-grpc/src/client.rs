@@ -18,6 +18,9 @@ use rustls::ClientConfig as TlsClientConfig;
 
 use crate::{
     codec::Codec,
+    compression::{
+        CompressionEncoding, DEFAULT_MAX_DECOMPRESSED_SIZE, GRPC_ACCEPT_ENCODING_HEADER,
+    },
     connector::HttpsConnector,
     encoding::{create_decode_response_body, create_encode_request_body},
     Code, Metadata, Request, Response, Status, Streaming,
@@ -26,12 +29,24 @@ use crate::{
 pub(crate) type BoxBody = http_body_util::combinators::BoxBody<Bytes, IoError>;
 
 /// A configuration for GRPC client
-#[derive(Default)]
 pub struct ClientConfig {
     uris: Vec<Uri>,
     origin: Option<Uri>,
     user_agent: Option<HeaderValue>,
     tls_config: Option<TlsClientConfig>,
+    max_decompressed_size: usize,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self {
+            uris: Vec::new(),
+            origin: None,
+            user_agent: None,
+            tls_config: None,
+            max_decompressed_size: DEFAULT_MAX_DECOMPRESSED_SIZE,
+        }
+    }
 }
 
 impl ClientConfig {
@@ -145,6 +160,15 @@ impl ClientConfigBuilder {
         self
     }
 
+    /// Set the maximum size of the response messages after decompression,
+    /// defaults to `4MB`
+    pub fn max_decompressed_size(mut self, size: usize) -> Self {
+        if let Ok(config) = &mut self.config {
+            config.max_decompressed_size = size;
+        }
+        self
+    }
+
     /// Consumes this builder and returns the `ClientConfig`
     pub fn build(self) -> Result<ClientConfig, ClientBuilderError> {
         self.config
@@ -155,12 +179,14 @@ impl ClientConfigBuilder {
 #[derive(Clone)]
 pub struct GrpcClient {
     ep: Arc<dyn DynEndpoint<Output = HttpResponse> + 'static>,
+    max_decompressed_size: usize,
 }
 
 impl GrpcClient {
     #[inline]
     pub fn new(config: ClientConfig) -> Self {
         Self {
+            max_decompressed_size: config.max_decompressed_size,
             ep: create_client_endpoint(config),
         }
     }
@@ -173,6 +199,7 @@ impl GrpcClient {
     {
         Self {
             ep: Arc::new(ToDynEndpoint(ep.map_to_response())),
+            max_decompressed_size: DEFAULT_MAX_DECOMPRESSED_SIZE,
         }
     }
 
@@ -218,7 +245,12 @@ impl GrpcClient {
         }
 
         let body = resp.take_body();
-        let mut stream = create_decode_response_body(codec.decoder(), resp.headers(), body)?;
+        let mut stream = create_decode_response_body(
+            codec.decoder(),
+            resp.headers(),
+            self.max_decompressed_size,
+            body,
+        )?;
 
         let message = stream
             .try_next()
@@ -260,7 +292,12 @@ impl GrpcClient {
         }
 
         let body = resp.take_body();
-        let mut stream = create_decode_response_body(codec.decoder(), resp.headers(), body)?;
+        let mut stream = create_decode_response_body(
+            codec.decoder(),
+            resp.headers(),
+            self.max_decompressed_size,
+            body,
+        )?;
 
         let message = stream
             .try_next()
@@ -305,7 +342,12 @@ impl GrpcClient {
         }
 
         let body = resp.take_body();
-        let stream = create_decode_response_body(codec.decoder(), resp.headers(), body)?;
+        let stream = create_decode_response_body(
+            codec.decoder(),
+            resp.headers(),
+            self.max_decompressed_size,
+            body,
+        )?;
 
         Ok(Response {
             metadata: Metadata {
@@ -343,7 +385,12 @@ impl GrpcClient {
         }
 
         let body = resp.take_body();
-        let stream = create_decode_response_body(codec.decoder(), resp.headers(), body)?;
+        let stream = create_decode_response_body(
+            codec.decoder(),
+            resp.headers(),
+            self.max_decompressed_size,
+            body,
+        )?;
 
         Ok(Response {
             metadata: Metadata {
@@ -365,6 +412,10 @@ fn create_http_request<T: Codec>(
         .version(Version::HTTP_2)
         .content_type(T::CONTENT_TYPES[0])
         .header(header::TE, "trailers")
+        .header(
+            GRPC_ACCEPT_ENCODING_HEADER,
+            CompressionEncoding::accept_encoding(),
+        )
         .finish();
     http_request.headers_mut().extend(metadata.headers);
     *http_request.extensions_mut() = extensions;
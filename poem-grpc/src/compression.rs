@@ -0,0 +1,134 @@
+use std::io::{Read, Result as IoResult, Write};
+
+use bytes::{BufMut, Bytes, BytesMut};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use http::HeaderValue;
+use hyper::HeaderMap;
+
+use crate::{Code, Status};
+
+pub(crate) const GRPC_ENCODING_HEADER: &str = "grpc-encoding";
+pub(crate) const GRPC_ACCEPT_ENCODING_HEADER: &str = "grpc-accept-encoding";
+
+/// The default maximum size of the decompressed messages, `4MB`.
+pub(crate) const DEFAULT_MAX_DECOMPRESSED_SIZE: usize = 4 * 1024 * 1024;
+
+/// The compression encodings of the gRPC messages.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum CompressionEncoding {
+    /// gzip
+    Gzip,
+}
+
+impl CompressionEncoding {
+    const ALL: &'static [CompressionEncoding] = &[CompressionEncoding::Gzip];
+
+    #[inline]
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            CompressionEncoding::Gzip => "gzip",
+        }
+    }
+
+    /// Returns the value of the `grpc-accept-encoding` header with all the
+    /// supported encodings.
+    pub(crate) fn accept_encoding() -> HeaderValue {
+        let value = Self::ALL
+            .iter()
+            .map(|encoding| encoding.as_str())
+            .collect::<Vec<_>>()
+            .join(",");
+        HeaderValue::from_str(&value).expect("valid header value")
+    }
+
+    /// Returns `true` if the `grpc-accept-encoding` header contains this
+    /// encoding.
+    pub(crate) fn is_accepted(&self, headers: &HeaderMap) -> bool {
+        headers
+            .get_all(GRPC_ACCEPT_ENCODING_HEADER)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .any(|value| value.trim().eq_ignore_ascii_case(self.as_str()))
+    }
+
+    /// Parses the encoding of the messages from the `grpc-encoding` header.
+    // the error is returned as the status of the call, like the other errors
+    // of the decoders
+    #[allow(clippy::result_large_err)]
+    pub(crate) fn from_encoding_header(headers: &HeaderMap) -> Result<Option<Self>, Status> {
+        let Some(value) = headers.get(GRPC_ENCODING_HEADER) else {
+            return Ok(None);
+        };
+        match value.to_str().map(str::trim) {
+            Ok("identity") => Ok(None),
+            Ok(value) => Self::ALL
+                .iter()
+                .copied()
+                .find(|encoding| encoding.as_str() == value)
+                .map(Some)
+                .ok_or_else(|| {
+                    Status::new(Code::Unimplemented)
+                        .with_message(format!("unsupported grpc-encoding: {value}"))
+                }),
+            Err(_) => Err(Status::new(Code::Internal).with_message("invalid grpc-encoding")),
+        }
+    }
+
+    pub(crate) fn compress(&self, data: &[u8], buf: &mut BytesMut) -> IoResult<()> {
+        match self {
+            CompressionEncoding::Gzip => {
+                let mut encoder = GzEncoder::new(buf.writer(), Compression::default());
+                encoder.write_all(data)?;
+                encoder.finish()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Decompresses a message, at most `max_size + 1` bytes are decompressed,
+    /// so that the caller can detect that the message is too large.
+    pub(crate) fn decompress(&self, data: &[u8], max_size: usize) -> IoResult<Bytes> {
+        let limit = (max_size as u64).saturating_add(1);
+        match self {
+            CompressionEncoding::Gzip => {
+                let mut decoder = GzDecoder::new(data).take(limit);
+                let mut writer = BytesMut::new().writer();
+                std::io::copy(&mut decoder, &mut writer)?;
+                Ok(writer.into_inner().freeze())
+            }
+        }
+    }
+}
+
+/// The compression settings of the messages.
+#[doc(hidden)]
+#[derive(Debug, Copy, Clone)]
+pub struct CompressionConfig {
+    /// The encoding used to compress the outgoing messages if the peer
+    /// accepts it.
+    pub send_compressed: Option<CompressionEncoding>,
+    /// The outgoing messages smaller than this size are not compressed.
+    pub min_size: usize,
+    /// The maximum size of the incoming messages after decompression.
+    pub max_decompressed_size: usize,
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self {
+            send_compressed: None,
+            min_size: 0,
+            max_decompressed_size: DEFAULT_MAX_DECOMPRESSED_SIZE,
+        }
+    }
+}
+
+impl CompressionConfig {
+    /// Returns the encoding to compress the response messages.
+    pub(crate) fn negotiate(&self, request_headers: &HeaderMap) -> Option<CompressionEncoding> {
+        self.send_compressed
+            .filter(|encoding| encoding.is_accepted(request_headers))
+    }
+}
@@ -1,7 +1,6 @@
 use std::io::{Error as IoError, Result as IoResult};
 
 use bytes::{Buf, BufMut, Bytes, BytesMut};
-use flate2::read::GzDecoder;
 use futures_util::StreamExt;
 use http_body_util::{BodyExt, StreamBody};
 use hyper::{body::Frame, HeaderMap};
@@ -12,27 +11,45 @@ use tokio_stream::wrappers::ReceiverStream;
 use crate::{
     client::BoxBody,
     codec::{Decoder, Encoder},
+    compression::{CompressionConfig, CompressionEncoding},
     Code, Status, Streaming,
 };
 
 fn encode_data_frame<T: Encoder>(
     encoder: &mut T,
     buf: &mut BytesMut,
+    compression: CompressionConfig,
     message: T::Item,
 ) -> IoResult<Bytes> {
     buf.put_slice(&[0, 0, 0, 0, 0]);
     encoder.encode(message, buf)?;
+    if let Some(encoding) = compression.send_compressed {
+        if buf.len() - 5 >= compression.min_size {
+            let data = buf.split_off(5);
+            encoding.compress(&data, buf)?;
+            buf.as_mut()[0] = 1;
+        }
+    }
     let msg_len = (buf.len() - 5) as u32;
     buf.as_mut()[1..5].copy_from_slice(&msg_len.to_be_bytes());
     Ok(buf.split().freeze())
 }
 
-#[derive(Default)]
 struct DataFrameDecoder {
     buf: BytesMut,
+    encoding: Option<CompressionEncoding>,
+    max_decompressed_size: usize,
 }
 
 impl DataFrameDecoder {
+    fn new(encoding: Option<CompressionEncoding>, max_decompressed_size: usize) -> Self {
+        Self {
+            buf: BytesMut::new(),
+            encoding,
+            max_decompressed_size,
+        }
+    }
+
     fn put_slice(&mut self, data: impl AsRef<[u8]>) {
         self.buf.extend_from_slice(data.as_ref());
     }
@@ -62,11 +79,20 @@ impl DataFrameDecoder {
             let data = self.buf.split_to(len).freeze();
 
             if compressed {
-                let mut decoder = GzDecoder::new(&*data);
-                let raw_data = BytesMut::new();
-                let mut writer = raw_data.writer();
-                std::io::copy(&mut decoder, &mut writer).map_err(Status::from_std_error)?;
-                Ok(Some(writer.into_inner().freeze()))
+                let encoding = self.encoding.ok_or_else(|| {
+                    Status::new(Code::Internal)
+                        .with_message("compressed flag is set but grpc-encoding is not specified")
+                })?;
+                let data = encoding
+                    .decompress(&data, self.max_decompressed_size)
+                    .map_err(Status::from_std_error)?;
+                if data.len() > self.max_decompressed_size {
+                    return Err(Status::new(Code::ResourceExhausted).with_message(format!(
+                        "decompressed message is larger than the limit of {} bytes",
+                        self.max_decompressed_size
+                    )));
+                }
+                Ok(Some(data))
             } else {
                 Ok(Some(data))
             }
@@ -78,12 +104,15 @@ impl DataFrameDecoder {
 
 pub(crate) fn create_decode_request_body<T: Decoder>(
     mut decoder: T,
+    headers: &HeaderMap,
+    max_decompressed_size: usize,
     body: Body,
 ) -> Streaming<T::Item> {
     let mut body: BoxBody = body.into();
+    let encoding = CompressionEncoding::from_encoding_header(headers);
 
     Streaming::new(async_stream::try_stream! {
-        let mut frame_decoder = DataFrameDecoder::default();
+        let mut frame_decoder = DataFrameDecoder::new(encoding?, max_decompressed_size);
 
         loop {
             match body.frame().await.transpose().map_err(Status::from_std_error)? {
@@ -107,6 +136,7 @@ pub(crate) fn create_decode_request_body<T: Decoder>(
 
 pub(crate) fn create_encode_response_body<T: Encoder>(
     mut encoder: T,
+    compression: CompressionConfig,
     mut stream: Streaming<T::Item>,
 ) -> Body {
     let (tx, rx) = mpsc::channel(16);
@@ -117,7 +147,9 @@ pub(crate) fn create_encode_response_body<T: Encoder>(
         while let Some(item) = stream.next().await {
             match item {
                 Ok(message) => {
-                    if let Ok(data) = encode_data_frame(&mut encoder, &mut buf, message) {
+                    if let Ok(data) =
+                        encode_data_frame(&mut encoder, &mut buf, compression, message)
+                    {
                         if tx.send(Frame::data(data)).await.is_err() {
                             return;
                         }
@@ -151,7 +183,12 @@ pub(crate) fn create_encode_request_body<T: Encoder>(
         let mut buf = BytesMut::new();
 
         while let Some(Ok(message)) = stream.next().await {
-            if let Ok(data) = encode_data_frame(&mut encoder, &mut buf, message) {
+            if let Ok(data) = encode_data_frame(
+                &mut encoder,
+                &mut buf,
+                CompressionConfig::default(),
+                message,
+            ) {
                 if tx.send(Frame::data(data)).await.is_err() {
                     return;
                 }
@@ -168,6 +205,7 @@ pub(crate) fn create_encode_request_body<T: Encoder>(
 pub(crate) fn create_decode_response_body<T: Decoder>(
     mut decoder: T,
     headers: &HeaderMap,
+    max_decompressed_size: usize,
     body: Body,
 ) -> Result<Streaming<T::Item>, Status> {
     // check is trailers-only
@@ -180,9 +218,10 @@ pub(crate) fn create_decode_response_body<T: Decoder>(
     }
 
     let mut body: BoxBody = body.into();
+    let encoding = CompressionEncoding::from_encoding_header(headers)?;
 
     Ok(Streaming::new(async_stream::try_stream! {
-        let mut frame_decoder = DataFrameDecoder::default();
+        let mut frame_decoder = DataFrameDecoder::new(encoding, max_decompressed_size);
         let mut status = None;
 
         while let Some(frame) = body.frame().await.transpose().map_err(Status::from_std_error)? {
@@ -254,7 +293,7 @@ mod tests {
 
         let mut codec = ProstCodec::<TestMsg, TestMsg>::default();
         let mut streaming =
-            create_decode_response_body(codec.decoder(), &HeaderMap::default(), body)
+            create_decode_response_body(codec.decoder(), &HeaderMap::default(), usize::MAX, body)
                 .expect("streaming");
 
         let stream_msg = streaming
@@ -20,6 +20,7 @@ pub mod service;
 pub mod codec;
 pub mod metadata;
 
+mod compression;
 mod connector;
 mod encoding;
 mod health;
@@ -33,6 +34,7 @@ mod streaming;
 mod test_harness;
 
 pub use client::{ClientBuilderError, ClientConfig, ClientConfigBuilder};
+pub use compression::CompressionEncoding;
 pub use health::{health_service, HealthReporter, ServingStatus};
 pub use metadata::Metadata;
 pub use reflection::Reflection;
@@ -1,8 +1,13 @@
 use futures_util::StreamExt;
-use poem::{Request, Response};
+use poem::{
+    http::{HeaderMap, HeaderValue},
+    Request, Response,
+};
 
+pub use crate::compression::CompressionConfig;
 use crate::{
     codec::Codec,
+    compression::{CompressionEncoding, GRPC_ACCEPT_ENCODING_HEADER, GRPC_ENCODING_HEADER},
     encoding::{create_decode_request_body, create_encode_response_body},
     service::{
         BidirectionalStreamingService, ClientStreamingService, ServerStreamingService, UnaryService,
@@ -13,12 +18,48 @@ use crate::{
 #[doc(hidden)]
 pub struct GrpcServer<T> {
     codec: T,
+    compression: CompressionConfig,
 }
 
 impl<T: Codec> GrpcServer<T> {
     #[inline]
     pub fn new(codec: T) -> Self {
-        Self { codec }
+        Self {
+            codec,
+            compression: CompressionConfig::default(),
+        }
+    }
+
+    #[inline]
+    pub fn with_compression(self, compression: CompressionConfig) -> Self {
+        Self {
+            compression,
+            ..self
+        }
+    }
+
+    fn create_response(&self, request_headers: &HeaderMap) -> (Response, CompressionConfig) {
+        let mut resp = Response::default().set_content_type(T::CONTENT_TYPES[0]);
+        resp.headers_mut().insert(
+            GRPC_ACCEPT_ENCODING_HEADER,
+            CompressionEncoding::accept_encoding(),
+        );
+
+        let send_compressed = self.compression.negotiate(request_headers);
+        if let Some(encoding) = send_compressed {
+            resp.headers_mut().insert(
+                GRPC_ENCODING_HEADER,
+                HeaderValue::from_static(encoding.as_str()),
+            );
+        }
+
+        (
+            resp,
+            CompressionConfig {
+                send_compressed,
+                ..self.compression
+            },
+        )
     }
 
     pub async fn unary<S>(&mut self, service: S, request: Request) -> Response
@@ -26,7 +67,13 @@ impl<T: Codec> GrpcServer<T> {
         S: UnaryService<T::Decode, Response = T::Encode>,
     {
         let (parts, body) = request.into_parts();
-        let mut stream = create_decode_request_body(self.codec.decoder(), body);
+        let (mut resp, compression) = self.create_response(&parts.headers);
+        let mut stream = create_decode_request_body(
+            self.codec.decoder(),
+            &parts.headers,
+            self.compression.max_decompressed_size,
+            body,
+        );
 
         let res = match stream.next().await {
             Some(Ok(message)) => {
@@ -44,13 +91,12 @@ impl<T: Codec> GrpcServer<T> {
             None => Err(Status::new(Code::Internal).with_message("missing request message")),
         };
 
-        let mut resp = Response::default().set_content_type(T::CONTENT_TYPES[0]);
-
         match res {
             Ok(grpc_resp) => {
                 let GrpcResponse { metadata, message } = grpc_resp;
                 let body = create_encode_response_body(
                     self.codec.encoder(),
+                    compression,
                     Streaming::new(futures_util::stream::once(async move { Ok(message) })),
                 );
                 resp.headers_mut().extend(metadata.headers);
@@ -69,7 +115,13 @@ impl<T: Codec> GrpcServer<T> {
         S: ClientStreamingService<T::Decode, Response = T::Encode>,
     {
         let (parts, body) = request.into_parts();
-        let stream = create_decode_request_body(self.codec.decoder(), body);
+        let (mut resp, compression) = self.create_response(&parts.headers);
+        let stream = create_decode_request_body(
+            self.codec.decoder(),
+            &parts.headers,
+            self.compression.max_decompressed_size,
+            body,
+        );
 
         let res = service
             .call(GrpcRequest {
@@ -81,13 +133,12 @@ impl<T: Codec> GrpcServer<T> {
             })
             .await;
 
-        let mut resp = Response::default().set_content_type(T::CONTENT_TYPES[0]);
-
         match res {
             Ok(grpc_resp) => {
                 let GrpcResponse { metadata, message } = grpc_resp;
                 let body = create_encode_response_body(
                     self.codec.encoder(),
+                    compression,
                     Streaming::new(futures_util::stream::once(async move { Ok(message) })),
                 );
                 resp.headers_mut().extend(metadata.headers);
@@ -106,7 +157,13 @@ impl<T: Codec> GrpcServer<T> {
         S: ServerStreamingService<T::Decode, Response = T::Encode>,
     {
         let (parts, body) = request.into_parts();
-        let mut stream = create_decode_request_body(self.codec.decoder(), body);
+        let (mut resp, compression) = self.create_response(&parts.headers);
+        let mut stream = create_decode_request_body(
+            self.codec.decoder(),
+            &parts.headers,
+            self.compression.max_decompressed_size,
+            body,
+        );
 
         let res = match stream.next().await {
             Some(Ok(message)) => {
@@ -124,12 +181,10 @@ impl<T: Codec> GrpcServer<T> {
             None => Err(Status::new(Code::Internal).with_message("missing request message")),
         };
 
-        let mut resp = Response::default().set_content_type(T::CONTENT_TYPES[0]);
-
         match res {
             Ok(grpc_resp) => {
                 let GrpcResponse { metadata, message } = grpc_resp;
-                let body = create_encode_response_body(self.codec.encoder(), message);
+                let body = create_encode_response_body(self.codec.encoder(), compression, message);
                 resp.headers_mut().extend(metadata.headers);
                 resp.set_body(body);
             }
@@ -146,7 +201,13 @@ impl<T: Codec> GrpcServer<T> {
         S: BidirectionalStreamingService<T::Decode, Response = T::Encode>,
     {
         let (parts, body) = request.into_parts();
-        let stream = create_decode_request_body(self.codec.decoder(), body);
+        let (mut resp, compression) = self.create_response(&parts.headers);
+        let stream = create_decode_request_body(
+            self.codec.decoder(),
+            &parts.headers,
+            self.compression.max_decompressed_size,
+            body,
+        );
 
         let res = service
             .call(GrpcRequest {
@@ -158,12 +219,10 @@ impl<T: Codec> GrpcServer<T> {
             })
             .await;
 
-        let mut resp = Response::default().set_content_type(T::CONTENT_TYPES[0]);
-
         match res {
             Ok(grpc_resp) => {
                 let GrpcResponse { metadata, message } = grpc_resp;
-                let body = create_encode_response_body(self.codec.encoder(), message);
+                let body = create_encode_response_body(self.codec.encoder(), compression, message);
                 resp.headers_mut().extend(metadata.headers);
                 resp.set_body(body);
             }
@@ -156,4 +156,92 @@ mod tests {
         assert_eq!(resp.metadata().get("mydata"), Some("abc"));
         assert_eq!(resp.into_inner(), ValueResponse { value: 30 });
     }
+
+    #[tokio::test]
+    async fn compression() {
+        use bytes::BytesMut;
+        use poem::{
+            http::{header, Version},
+            Endpoint, IntoEndpoint,
+        };
+        use prost::Message;
+
+        use crate::CompressionEncoding;
+
+        let server = TestHarnessServer::new(TestHarnessService)
+            .send_compressed(CompressionEncoding::Gzip)
+            .compression_min_size(1);
+        let ep = RouteGrpc::new().add_service(server).into_endpoint();
+
+        let message = UnaryRequest { a: 10, b: 20 }.encode_to_vec();
+        let mut body = vec![0];
+        body.extend((message.len() as u32).to_be_bytes());
+        body.extend(message);
+
+        let resp = ep
+            .call(
+                poem::Request::builder()
+                    .uri_str("/test_harness.TestHarness/Unary")
+                    .method(poem::http::Method::POST)
+                    .version(Version::HTTP_2)
+                    .content_type("application/grpc")
+                    .header(header::TE, "trailers")
+                    .header("grpc-accept-encoding", "deflate, gzip")
+                    .body(body),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.headers().get("grpc-encoding").unwrap(), "gzip");
+        let data = resp.into_body().into_vec().await.unwrap();
+        assert_eq!(data[0], 1);
+        let message = CompressionEncoding::Gzip
+            .decompress(&data[5..], usize::MAX)
+            .unwrap();
+        assert_eq!(
+            ValueResponse::decode(message).unwrap(),
+            ValueResponse { value: 30 }
+        );
+
+        // the size of the decompressed request messages is limited
+        let mut message = BytesMut::new();
+        CompressionEncoding::Gzip
+            .compress(&vec![0; 1024 * 1024], &mut message)
+            .unwrap();
+        let mut body = vec![1];
+        body.extend((message.len() as u32).to_be_bytes());
+        body.extend(message);
+        let ep = RouteGrpc::new()
+            .add_service(TestHarnessServer::new(TestHarnessService).max_decompressed_size(1024))
+            .into_endpoint();
+        let resp = ep
+            .call(
+                poem::Request::builder()
+                    .uri_str("/test_harness.TestHarness/Unary")
+                    .method(poem::http::Method::POST)
+                    .version(Version::HTTP_2)
+                    .content_type("application/grpc")
+                    .header("grpc-encoding", "gzip")
+                    .body(body),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.headers().get("grpc-status").unwrap(), "8");
+
+        // the client accepts gzip
+        let server =
+            TestHarnessServer::new(TestHarnessService).send_compressed(CompressionEncoding::Gzip);
+        let cli = TestHarnessClient::from_endpoint(RouteGrpc::new().add_service(server));
+        let resp = cli
+            .server_streaming(Request::new(ValueRequest { value: 2 }))
+            .await
+            .unwrap();
+        assert_eq!(
+            resp.into_inner()
+                .map_ok(|resp| resp.value)
+                .try_collect::<Vec<_>>()
+                .await
+                .unwrap(),
+            vec![2, 1, 0]
+        );
+    }
 }
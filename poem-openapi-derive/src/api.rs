@@ -83,7 +83,7 @@ struct APIOperationParam {
 
 struct Context {
     add_routes: Vec<TokenStream>,
-    operations: Vec<(TokenStream, TokenStream)>,
+    operations: Vec<(TokenStream, TokenStream, TokenStream)>,
     register_items: Vec<TokenStream>,
 }
 
@@ -121,9 +121,9 @@ pub(crate) fn generate(args: APIArgs, mut item_impl: ItemImpl) -> GeneratorResul
     let paths = {
         let mut paths = Vec::new();
 
-        for (path, operation) in operations {
+        for (path, route_path, operation) in operations {
             paths.push(quote! {
-                paths_map.entry(#path).or_default().push(#operation);
+                paths_map.entry(#path).or_insert_with(|| (#route_path, ::std::vec::Vec::new())).1.push(#operation);
             });
         }
         paths
@@ -137,10 +137,11 @@ pub(crate) fn generate(args: APIArgs, mut item_impl: ItemImpl) -> GeneratorResul
                 ::std::vec![#crate_name::registry::MetaApi {
                     paths: {
                         use ::std::iter::{IntoIterator, Iterator};
-                        let mut paths_map = #crate_name::__private::indexmap::IndexMap::<::std::string::String, ::std::vec::Vec<#crate_name::registry::MetaOperation>>::new();
+                        let mut paths_map = #crate_name::__private::indexmap::IndexMap::<::std::string::String, (::std::string::String, ::std::vec::Vec<#crate_name::registry::MetaOperation>)>::new();
                         #(#paths)*
-                        paths_map.into_iter().map(|(path, operations)| #crate_name::registry::MetaPath {
+                        paths_map.into_iter().map(|(path, (route_path, operations))| #crate_name::registry::MetaPath {
                             path,
+                            route_path,
                             operations,
                         }).collect()
                     },
@@ -610,7 +611,8 @@ fn generate_operation(
                     code_samples: ::std::vec![#(#code_samples),*],
                 }
             };
-            ctx.operations.push((oai_path.clone(), meta_operation));
+            ctx.operations
+                .push((oai_path.clone(), new_path.clone(), meta_operation));
         }
     }
 
@@ -96,3 +96,12 @@ impl ResponseError for AuthorizationError {
         StatusCode::UNAUTHORIZED
     }
 }
+
+/// An error that occurs when more than one operation has the same operation
+/// id.
+#[derive(Debug, Error)]
+#[error("duplicate operation id: {operation_id}")]
+pub struct DuplicateOperationIdError {
+    /// The duplicate operation id.
+    pub operation_id: &'static str,
+}
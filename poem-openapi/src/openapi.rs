@@ -12,12 +12,14 @@ use poem::{
 
 use crate::{
     base::UrlQuery,
+    error::DuplicateOperationIdError,
+    path_util::join_path,
     registry::{
         Document, MetaContact, MetaExternalDocument, MetaHeader, MetaInfo, MetaLicense,
         MetaOperationParam, MetaParamIn, MetaSchemaRef, MetaServer, Registry,
     },
     types::Type,
-    OpenApi, Webhook,
+    ApiExtractor, ExtractParamOptions, OpenApi, Webhook,
 };
 
 /// An object representing a Server.
@@ -212,7 +214,67 @@ impl ExtraHeader {
     }
 }
 
+/// The security requirement applied to the operations that do not declare
+/// their own.
+#[derive(Clone)]
+struct DefaultSecurity {
+    security_schemes: fn() -> Vec<&'static str>,
+    register: fn(&mut Registry),
+    guard: fn(BoxEndpoint<'static>) -> BoxEndpoint<'static>,
+}
+
+fn security_guard<S>(ep: BoxEndpoint<'static>) -> BoxEndpoint<'static>
+where
+    S: for<'a> ApiExtractor<'a>,
+{
+    ep.before(|req| async move {
+        let (mut req, mut body) = req.split();
+        S::from_request(&req, &mut body, ExtractParamOptions::default()).await?;
+        if let Ok(body) = body.take() {
+            req.set_body(body);
+        }
+        Ok(req)
+    })
+    .boxed()
+}
+
 /// An OpenAPI service for Poem.
+///
+/// Multiple OpenAPI objects can be composed into one service with a tuple,
+/// and [`OpenApiService::path_prefix`] and [`OpenApiService::security`] are
+/// applied to all of their operations.
+///
+/// # Example
+///
+/// ```
+/// use poem_openapi::{auth::ApiKey, OpenApi, OpenApiService, SecurityScheme};
+///
+/// #[derive(SecurityScheme)]
+/// #[oai(ty = "api_key", key_name = "X-API-Key", key_in = "header")]
+/// struct MyApiKey(ApiKey);
+///
+/// struct UserApi;
+///
+/// #[OpenApi]
+/// impl UserApi {
+///     #[oai(path = "/users", method = "get")]
+///     async fn users(&self) {}
+/// }
+///
+/// struct PublicApi;
+///
+/// #[OpenApi]
+/// impl PublicApi {
+///     /// Overrides the default security requirement.
+///     #[oai(path = "/login", method = "post")]
+///     async fn login(&self, _auth: MyApiKey) {}
+/// }
+///
+/// let api_service = OpenApiService::new((UserApi, PublicApi), "API", "1.0")
+///     .path_prefix("/v1")
+///     .security::<MyApiKey>();
+/// let route = api_service.try_into_endpoint().unwrap();
+/// ```
 #[derive(Clone)]
 pub struct OpenApiService<T, W> {
     api: T,
@@ -224,6 +286,8 @@ pub struct OpenApiService<T, W> {
     extra_response_headers: Vec<(ExtraHeader, MetaSchemaRef, bool)>,
     extra_request_headers: Vec<(ExtraHeader, MetaSchemaRef, bool)>,
     url_prefix: Option<String>,
+    path_prefix: Option<String>,
+    default_security: Option<DefaultSecurity>,
 }
 
 impl<T> OpenApiService<T, ()> {
@@ -248,6 +312,8 @@ impl<T> OpenApiService<T, ()> {
             extra_response_headers: vec![],
             extra_request_headers: vec![],
             url_prefix: None,
+            path_prefix: None,
+            default_security: None,
         }
     }
 }
@@ -265,6 +331,8 @@ impl<T, W> OpenApiService<T, W> {
             extra_response_headers: self.extra_response_headers,
             extra_request_headers: self.extra_request_headers,
             url_prefix: None,
+            path_prefix: self.path_prefix,
+            default_security: self.default_security,
         }
     }
 
@@ -386,6 +454,38 @@ impl<T, W> OpenApiService<T, W> {
         }
     }
 
+    /// Sets the prefix of the paths of all operations.
+    ///
+    /// Unlike [`OpenApiService::url_prefix`], the prefix is also added to the
+    /// routes of the endpoint.
+    #[must_use]
+    pub fn path_prefix(self, path_prefix: impl Into<String>) -> Self {
+        Self {
+            path_prefix: Some(path_prefix.into()),
+            ..self
+        }
+    }
+
+    /// Sets the security scheme that is required by all operations that do
+    /// not declare their own security scheme.
+    ///
+    /// The requirement is added to the specification as the default security
+    /// requirement, and is checked before calling these operations.
+    #[must_use]
+    pub fn security<S>(self) -> Self
+    where
+        S: for<'a> ApiExtractor<'a>,
+    {
+        Self {
+            default_security: Some(DefaultSecurity {
+                security_schemes: S::security_schemes,
+                register: S::register,
+                guard: security_guard::<S>,
+            }),
+            ..self
+        }
+    }
+
     /// Create the OpenAPI Explorer endpoint.
     #[must_use]
     #[cfg(feature = "openapi-explorer")]
@@ -508,6 +608,15 @@ impl<T, W> OpenApiService<T, W> {
         let mut registry = Registry::new();
         let mut apis = T::meta();
 
+        if let Some(path_prefix) = &self.path_prefix {
+            for path in apis
+                .iter_mut()
+                .flat_map(|meta_api| meta_api.paths.iter_mut())
+            {
+                path.path = join_path(path_prefix, &path.path);
+            }
+        }
+
         // update extra request headers
         for operation in apis
             .iter_mut()
@@ -558,6 +667,14 @@ impl<T, W> OpenApiService<T, W> {
         T::register(&mut registry);
         W::register(&mut registry);
 
+        let mut security = Vec::new();
+        if let Some(default_security) = &self.default_security {
+            (default_security.register)(&mut registry);
+            for name in (default_security.security_schemes)() {
+                security.push(HashMap::from([(name, Vec::new())]));
+            }
+        }
+
         let webhooks = W::meta();
 
         let mut doc = Document {
@@ -568,6 +685,7 @@ impl<T, W> OpenApiService<T, W> {
             registry,
             external_document: self.external_document.as_ref(),
             url_prefix: self.url_prefix.as_deref(),
+            security,
         };
         doc.remove_unused_schemas();
 
@@ -595,10 +713,10 @@ impl<T, W> OpenApiService<T, W> {
     }
 }
 
-impl<T: OpenApi, W: Webhook> IntoEndpoint for OpenApiService<T, W> {
-    type Endpoint = BoxEndpoint<'static>;
-
-    fn into_endpoint(self) -> Self::Endpoint {
+impl<T: OpenApi, W: Webhook> OpenApiService<T, W> {
+    /// Consumes this service and returns the endpoint, or an error if more
+    /// than one operation has the same operation id.
+    pub fn try_into_endpoint(self) -> Result<BoxEndpoint<'static>, DuplicateOperationIdError> {
         async fn extract_query(mut req: Request) -> Result<Request> {
             let url_query: Vec<(String, String)> = req.params().unwrap_or_default();
             req.extensions_mut().insert(UrlQuery(url_query));
@@ -612,14 +730,16 @@ impl<T: OpenApi, W: Webhook> IntoEndpoint for OpenApiService<T, W> {
 
         // check duplicate operation id
         let mut operation_ids = HashSet::new();
-        for operation in T::meta()
-            .into_iter()
-            .flat_map(|api| api.paths.into_iter())
-            .flat_map(|path| path.operations.into_iter())
-        {
-            if let Some(operation_id) = operation.operation_id {
-                if !operation_ids.insert(operation_id) {
-                    panic!("duplicate operation id: {operation_id}");
+        let mut secured_operations = HashSet::new();
+        for path in T::meta().into_iter().flat_map(|api| api.paths.into_iter()) {
+            for operation in path.operations {
+                if let Some(operation_id) = operation.operation_id {
+                    if !operation_ids.insert(operation_id) {
+                        return Err(DuplicateOperationIdError { operation_id });
+                    }
+                }
+                if !operation.security.is_empty() {
+                    secured_operations.insert((path.route_path.clone(), operation.method));
                 }
             }
         }
@@ -630,21 +750,41 @@ impl<T: OpenApi, W: Webhook> IntoEndpoint for OpenApiService<T, W> {
         let route = items
             .into_iter()
             .fold(Route::new(), |route, (path, paths)| {
-                route.at(
-                    path,
+                let route_method =
                     paths
                         .into_iter()
                         .fold(RouteMethod::new(), |route_method, (method, ep)| {
+                            let ep = match &self.default_security {
+                                Some(default_security)
+                                    if !secured_operations
+                                        .contains(&(path.clone(), method.clone())) =>
+                                {
+                                    (default_security.guard)(ep)
+                                }
+                                _ => ep,
+                            };
                             route_method.method(method, ep)
-                        }),
-                )
+                        });
+                match &self.path_prefix {
+                    Some(path_prefix) => route.at(join_path(path_prefix, &path), route_method),
+                    None => route.at(path, route_method),
+                }
             });
 
-        route
+        Ok(route
             .with(cookie_jar_manager)
             .before(extract_query)
             .map_to_response()
-            .boxed()
+            .boxed())
+    }
+}
+
+impl<T: OpenApi, W: Webhook> IntoEndpoint for OpenApiService<T, W> {
+    type Endpoint = BoxEndpoint<'static>;
+
+    fn into_endpoint(self) -> Self::Endpoint {
+        self.try_into_endpoint()
+            .unwrap_or_else(|err| panic!("{err}"))
     }
 }
 
@@ -721,4 +861,131 @@ mod tests {
         assert!(params[2].deprecated);
         assert_eq!(params[2].schema, f32::schema_ref());
     }
+
+    #[test]
+    fn duplicate_operation_id() {
+        struct Api1;
+
+        #[OpenApi(internal)]
+        impl Api1 {
+            #[oai(path = "/a", method = "get", operation_id = "test")]
+            async fn test(&self) {}
+        }
+
+        struct Api2;
+
+        #[OpenApi(internal)]
+        impl Api2 {
+            #[oai(path = "/b", method = "get", operation_id = "test")]
+            async fn test(&self) {}
+        }
+
+        let err = OpenApiService::new((Api1, Api2), "demo", "1.0")
+            .try_into_endpoint()
+            .err()
+            .unwrap();
+        assert_eq!(err.operation_id, "test");
+    }
+
+    #[tokio::test]
+    async fn compose() {
+        use poem::{http::StatusCode, test::TestClient};
+
+        use crate::{auth::ApiKey, SecurityScheme};
+
+        #[derive(SecurityScheme)]
+        #[oai(
+            internal,
+            ty = "api_key",
+            key_name = "X-API-Key",
+            key_in = "header",
+            checker = "check_key"
+        )]
+        struct MyApiKey(());
+
+        async fn check_key(_req: &Request, api_key: ApiKey) -> Option<()> {
+            (api_key.key == "123").then_some(())
+        }
+
+        #[derive(SecurityScheme)]
+        #[oai(internal, ty = "basic")]
+        struct MyBasic(crate::auth::Basic);
+
+        struct Api1;
+
+        #[OpenApi(internal)]
+        impl Api1 {
+            #[oai(path = "/users/:id", method = "get")]
+            async fn user(&self, id: crate::param::Path<i32>) -> crate::payload::PlainText<String> {
+                crate::payload::PlainText(id.0.to_string())
+            }
+        }
+
+        struct Api2;
+
+        #[OpenApi(internal)]
+        impl Api2 {
+            #[oai(path = "/login", method = "post")]
+            async fn login(&self, auth: MyBasic) -> crate::payload::PlainText<String> {
+                crate::payload::PlainText(auth.0.username)
+            }
+
+            #[oai(path = "/files/*path", method = "get")]
+            async fn file(
+                &self,
+                _auth: MyBasic,
+                path: crate::param::Path<String>,
+            ) -> crate::payload::PlainText<String> {
+                crate::payload::PlainText(path.0)
+            }
+
+            #[oai(path = "/items/:id<\\d+>", method = "get")]
+            async fn item(
+                &self,
+                _auth: MyBasic,
+                id: crate::param::Path<i32>,
+            ) -> crate::payload::PlainText<String> {
+                crate::payload::PlainText(id.0.to_string())
+            }
+        }
+
+        let api_service = OpenApiService::new((Api1, Api2), "demo", "1.0")
+            .path_prefix("/v1")
+            .security::<MyApiKey>();
+
+        let doc = api_service.document();
+        assert_eq!(doc.apis[0].paths[0].path, "/v1/users/{id}");
+        assert_eq!(doc.apis[1].paths[0].path, "/v1/login");
+        assert_eq!(doc.security, vec![HashMap::from([("MyApiKey", vec![])])]);
+        assert!(doc.registry.security_schemes.contains_key("MyApiKey"));
+
+        let cli = TestClient::new(api_service.try_into_endpoint().unwrap());
+
+        cli.get("/v1/users/1")
+            .send()
+            .await
+            .assert_status(StatusCode::UNAUTHORIZED);
+        cli.get("/v1/users/1")
+            .header("X-API-Key", "123")
+            .send()
+            .await
+            .assert_text("1")
+            .await;
+
+        // overrides the default security requirement
+        cli.post("/v1/login")
+            .header("Authorization", "Basic YWxpY2U6cGFzcw==")
+            .send()
+            .await
+            .assert_text("alice")
+            .await;
+        for (path, text) in [("/v1/files/a/b", "a/b"), ("/v1/items/1", "1")] {
+            cli.get(path)
+                .header("Authorization", "Basic YWxpY2U6cGFzcw==")
+                .send()
+                .await
+                .assert_text(text)
+                .await;
+        }
+    }
 }
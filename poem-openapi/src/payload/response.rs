@@ -94,7 +94,7 @@ impl<T: IntoResponse> IntoResponse for Response<T> {
         if let Some(status) = self.status {
             resp.set_status(status);
         }
-        resp.headers_mut().extend(self.headers);
+        resp.extend_headers(self.headers);
         resp
     }
 }
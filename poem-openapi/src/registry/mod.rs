@@ -492,6 +492,8 @@ pub struct MetaOperation {
 #[derive(Debug, PartialEq)]
 pub struct MetaPath {
     pub path: String,
+    /// The path of the route of the operations, such as `/users/:id`.
+    pub route_path: String,
     pub operations: Vec<MetaOperation>,
 }
 
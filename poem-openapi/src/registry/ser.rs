@@ -1,4 +1,4 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 
 use serde::{ser::SerializeMap, Serialize, Serializer};
 
@@ -84,6 +84,7 @@ pub(crate) struct Document<'a> {
     pub(crate) registry: Registry,
     pub(crate) external_document: Option<&'a MetaExternalDocument>,
     pub(crate) url_prefix: Option<&'a str>,
+    pub(crate) security: Vec<HashMap<&'static str, Vec<&'static str>>>,
 }
 
 impl<'a> Serialize for Document<'a> {
@@ -105,6 +106,9 @@ impl<'a> Serialize for Document<'a> {
         if !self.webhooks.is_empty() {
             s.serialize_entry("webhooks", &WebhookMap(&self.webhooks))?;
         }
+        if !self.security.is_empty() {
+            s.serialize_entry("security", &self.security)?;
+        }
         s.serialize_entry("paths", &PathMap(&self.apis, self.url_prefix))?;
         s.serialize_entry(
             "components",
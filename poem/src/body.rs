@@ -1,16 +1,18 @@
 use std::{
     fmt::{Debug, Formatter},
+    future::Future,
     io::{Error as IoError, ErrorKind},
     pin::Pin,
-    task::Poll,
+    task::{Context, Poll},
 };
 
 use bytes::{Bytes, BytesMut};
-use futures_util::{Stream, TryStreamExt};
+use futures_util::{future::BoxFuture, FutureExt, Stream, TryStreamExt};
+use http::HeaderMap;
 use http_body_util::BodyExt;
-use hyper::body::{Body as _, Frame};
+use hyper::body::{Body as _, Frame, SizeHint};
 use serde::{de::DeserializeOwned, Serialize};
-use sync_wrapper::SyncStream;
+use sync_wrapper::{SyncFuture, SyncStream};
 use tokio::io::{AsyncRead, AsyncReadExt};
 
 use crate::{
@@ -162,13 +164,69 @@ impl Body {
         size_hint.lower() == 0 && size_hint.upper() == Some(0)
     }
 
+    /// Consumes this body object to return a new body that sends the trailers
+    /// resolved by `trailers` after all data has been sent.
+    ///
+    /// If the future resolves to an empty [`HeaderMap`], no trailers are sent.
+    ///
+    /// Trailers are always sent over HTTP/2. Over HTTP/1.1 they are only sent
+    /// with chunked encoding when the client indicates it accepts them with
+    /// `TE: trailers`, and each field must be declared in the `Trailer`
+    /// response header. Otherwise, the trailers are dropped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::{http::HeaderMap, Body};
+    ///
+    /// let body = Body::from("hello").with_trailers(async move {
+    ///     let mut trailers = HeaderMap::new();
+    ///     trailers.insert("checksum", "abc".parse().unwrap());
+    ///     trailers
+    /// });
+    /// ```
+    pub fn with_trailers<F>(self, trailers: F) -> Self
+    where
+        F: Future<Output = HeaderMap> + Send + 'static,
+    {
+        Self(BoxBody::new(TrailersBody {
+            body: Some(self.0),
+            trailers: Some(SyncFuture::new(trailers.boxed())),
+        }))
+    }
+
+    /// Consumes this body object to return a new body with the specified
+    /// exact length, so that the response is sent with `Content-Length`
+    /// instead of chunked encoding.
+    ///
+    /// The length must be the exact length of the data, otherwise reading the
+    /// body fails when the data exceeds the length or ends before it, and the
+    /// connection is closed with an error.
+    pub fn with_content_length(self, len: u64) -> Self {
+        Self(BoxBody::new(SizedBody {
+            body: self.0,
+            remaining: len,
+        }))
+    }
+
+    /// Drops the trailers of this body, it is used for the clients that
+    /// cannot receive trailers.
+    #[cfg(feature = "server")]
+    pub(crate) fn without_trailers(self) -> Self {
+        if self.0.size_hint().exact().is_some() {
+            // bodies with a known length are never sent with trailers
+            return self;
+        }
+        Self(BoxBody::new(WithoutTrailersBody(self.0)))
+    }
+
     /// Consumes this body object to return a [`Bytes`] that contains all data.
     pub async fn into_bytes(self) -> Result<Bytes, ReadBodyError> {
         Ok(self
             .0
             .collect()
             .await
-            .map_err(|err| ReadBodyError::Io(IoError::new(ErrorKind::Other, err)))?
+            .map_err(ReadBodyError::Io)?
             .to_bytes())
     }
 
@@ -271,6 +329,137 @@ impl Body {
     }
 }
 
+struct SizedBody {
+    body: BoxBody,
+    remaining: u64,
+}
+
+impl hyper::body::Body for SizedBody {
+    type Data = Bytes;
+    type Error = IoError;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let res = Pin::new(&mut self.body).poll_frame(cx);
+        match &res {
+            Poll::Ready(Some(Ok(frame))) => {
+                if let Some(data) = frame.data_ref() {
+                    self.remaining =
+                        self.remaining
+                            .checked_sub(data.len() as u64)
+                            .ok_or_else(|| {
+                                IoError::new(
+                                    ErrorKind::InvalidData,
+                                    "the body is longer than its content length",
+                                )
+                            })?;
+                }
+            }
+            Poll::Ready(None) if self.remaining > 0 => {
+                return Poll::Ready(Some(Err(IoError::new(
+                    ErrorKind::UnexpectedEof,
+                    "the body is shorter than its content length",
+                ))));
+            }
+            _ => {}
+        }
+        res
+    }
+
+    fn is_end_stream(&self) -> bool {
+        // polls the inner body to report the error if it ends too early
+        self.remaining == 0 && self.body.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::with_exact(self.remaining)
+    }
+}
+
+struct TrailersBody {
+    body: Option<BoxBody>,
+    trailers: Option<SyncFuture<BoxFuture<'static, HeaderMap>>>,
+}
+
+impl hyper::body::Body for TrailersBody {
+    type Data = Bytes;
+    type Error = IoError;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let this = &mut *self;
+
+        if let Some(body) = &mut this.body {
+            match Pin::new(body).poll_frame(cx) {
+                Poll::Ready(None) => this.body = None,
+                res => return res,
+            }
+        }
+
+        match &mut this.trailers {
+            Some(fut) => {
+                let trailers = futures_util::ready!(Pin::new(fut).poll(cx));
+                this.trailers = None;
+                if trailers.is_empty() {
+                    Poll::Ready(None)
+                } else {
+                    Poll::Ready(Some(Ok(Frame::trailers(trailers))))
+                }
+            }
+            None => Poll::Ready(None),
+        }
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.body.is_none() && self.trailers.is_none()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        // never report an exact size, otherwise the response will be sent with
+        // `Content-Length` and the trailers cannot be sent over HTTP/1.1
+        let mut size_hint = SizeHint::new();
+        if let Some(body) = &self.body {
+            size_hint.set_lower(body.size_hint().lower());
+        }
+        size_hint
+    }
+}
+
+#[cfg(feature = "server")]
+struct WithoutTrailersBody(BoxBody);
+
+#[cfg(feature = "server")]
+impl hyper::body::Body for WithoutTrailersBody {
+    type Data = Bytes;
+    type Error = IoError;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        loop {
+            match futures_util::ready!(Pin::new(&mut self.0).poll_frame(cx)) {
+                Some(Ok(frame)) if frame.is_trailers() => {
+                    tracing::debug!("the client does not accept trailers, dropping them");
+                }
+                res => return Poll::Ready(res),
+            }
+        }
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.0.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.0.size_hint()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -317,4 +506,64 @@ mod tests {
         let body = Body::from_json("abc").unwrap();
         assert_eq!(body.into_json::<String>().await.unwrap(), "abc");
     }
+
+    #[tokio::test]
+    async fn with_trailers() {
+        let body = Body::from("abc").with_trailers(async move {
+            let mut trailers = HeaderMap::new();
+            trailers.insert("checksum", "123".parse().unwrap());
+            trailers
+        });
+        assert_eq!(body.0.size_hint().exact(), None);
+        let collected = body.0.collect().await.unwrap();
+        assert_eq!(
+            collected
+                .trailers()
+                .and_then(|trailers| trailers.get("checksum")),
+            Some(&"123".parse().unwrap())
+        );
+        assert_eq!(collected.to_bytes(), "abc");
+
+        let body = Body::from("abc").with_trailers(async move { HeaderMap::new() });
+        let collected = body.0.collect().await.unwrap();
+        assert!(collected.trailers().is_none());
+        assert_eq!(collected.to_bytes(), "abc");
+    }
+
+    #[tokio::test]
+    async fn with_content_length() {
+        let chunks = || {
+            Body::from_bytes_stream(futures_util::stream::iter([
+                Ok::<_, IoError>("ab"),
+                Ok("cd"),
+            ]))
+        };
+
+        let body = chunks().with_content_length(4);
+        assert_eq!(body.0.size_hint().exact(), Some(4));
+        assert_eq!(body.into_string().await.unwrap(), "abcd");
+
+        let err = chunks().with_content_length(3).into_bytes().await;
+        assert!(matches!(err, Err(ReadBodyError::Io(err)) if err.kind() == ErrorKind::InvalidData));
+
+        let err = chunks().with_content_length(5).into_bytes().await;
+        assert!(
+            matches!(err, Err(ReadBodyError::Io(err)) if err.kind() == ErrorKind::UnexpectedEof)
+        );
+    }
+
+    #[cfg(feature = "server")]
+    #[tokio::test]
+    async fn without_trailers() {
+        let body = Body::from("abc")
+            .with_trailers(async move {
+                let mut trailers = HeaderMap::new();
+                trailers.insert("checksum", "123".parse().unwrap());
+                trailers
+            })
+            .without_trailers();
+        let collected = body.0.collect().await.unwrap();
+        assert!(collected.trailers().is_none());
+        assert_eq!(collected.to_bytes(), "abc");
+    }
 }
@@ -8,6 +8,7 @@ use super::{
 };
 use crate::{
     error::IntoResult,
+    http::StatusCode,
     middleware::{AddData, AddDataEndpoint},
     Error, IntoResponse, Middleware, Request, Response, Result,
 };
@@ -93,6 +94,30 @@ where
     }
 }
 
+struct BlockingFnEndpoint<T, F> {
+    _mark: PhantomData<T>,
+    f: Arc<F>,
+}
+
+impl<F, T, R> Endpoint for BlockingFnEndpoint<T, F>
+where
+    F: Fn(Request) -> R + Send + Sync + 'static,
+    T: IntoResponse + Sync,
+    R: IntoResult<T> + Send + 'static,
+{
+    type Output = T;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        let f = self.f.clone();
+        match tokio::task::spawn_blocking(move || f(req)).await {
+            Ok(res) => res.into_result(),
+            // propagate the panic, so that it can be caught by `CatchPanic`
+            Err(err) if err.is_panic() => std::panic::resume_unwind(err.into_panic()),
+            Err(_) => Err(Error::from_status(StatusCode::SERVICE_UNAVAILABLE)),
+        }
+    }
+}
+
 /// Combines two different endpoints for [`Endpoint::with_if`].
 pub enum EitherEndpoint<A, B> {
     A(A),
@@ -175,6 +200,42 @@ where
     }
 }
 
+/// Create an endpoint with a blocking function, which is run on the blocking
+/// thread pool of Tokio with [`tokio::task::spawn_blocking`], so that the
+/// CPU-bound or blocking work does not block the async runtime.
+///
+/// The output can be any type that implements [`IntoResult`].
+///
+/// # Example
+///
+/// ```
+/// use poem::{endpoint::make_blocking, test::TestClient, Request};
+///
+/// let ep = make_blocking(|req: Request| {
+///     // some blocking work
+///     std::thread::sleep(std::time::Duration::from_millis(10));
+///     req.uri().path().to_string()
+/// });
+/// let cli = TestClient::new(ep);
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = cli.get("/hello").send().await;
+/// resp.assert_status_is_ok();
+/// resp.assert_text("/hello").await;
+/// # });
+/// ```
+pub fn make_blocking<F, T, R>(f: F) -> impl Endpoint<Output = T>
+where
+    F: Fn(Request) -> R + Send + Sync + 'static,
+    T: IntoResponse + Sync,
+    R: IntoResult<T> + Send + 'static,
+{
+    BlockingFnEndpoint {
+        _mark: PhantomData,
+        f: Arc::new(f),
+    }
+}
+
 impl<T: Endpoint + ?Sized> Endpoint for &T {
     type Output = T::Output;
 
@@ -782,10 +843,10 @@ mod test {
     use http::{HeaderValue, Uri};
 
     use crate::{
-        endpoint::{make, make_sync},
+        endpoint::{make, make_blocking, make_sync},
         get, handler,
         http::{Method, StatusCode},
-        middleware::SetHeader,
+        middleware::{CatchPanic, SetHeader},
         test::TestClient,
         web::Data,
         Endpoint, EndpointExt, Error, IntoEndpoint, Request, Route,
@@ -804,6 +865,27 @@ mod test {
         );
     }
 
+    #[tokio::test]
+    async fn test_make_blocking() {
+        let ep = make_blocking(|req| format!("method={}", req.method())).map_to_response();
+        let mut resp = ep
+            .call(Request::builder().method(Method::DELETE).finish())
+            .await
+            .unwrap();
+        assert_eq!(
+            resp.take_body().into_string().await.unwrap(),
+            "method=DELETE"
+        );
+
+        // the panic is propagated to the async task
+        let resp = make_blocking(|_| -> () { panic!() })
+            .with(CatchPanic::new())
+            .call(Request::default())
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
     #[tokio::test]
     async fn test_before() {
         assert_eq!(
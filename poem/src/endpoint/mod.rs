@@ -21,6 +21,8 @@ mod static_files;
 mod to_response;
 #[cfg(feature = "tower-compat")]
 mod tower_compat;
+#[cfg(feature = "static-files")]
+mod transforming_file;
 
 pub use after::After;
 pub use and_then::AndThen;
@@ -31,16 +33,19 @@ pub use catch_error::CatchError;
 #[cfg(feature = "embed")]
 pub use embed::{EmbeddedFileEndpoint, EmbeddedFilesEndpoint};
 pub use endpoint::{
-    make, make_sync, BoxEndpoint, DynEndpoint, Endpoint, EndpointExt, IntoEndpoint, ToDynEndpoint,
+    make, make_blocking, make_sync, BoxEndpoint, DynEndpoint, Endpoint, EndpointExt, IntoEndpoint,
+    ToDynEndpoint,
 };
 pub use inspect_all_err::InspectAllError;
 pub use inspect_err::InspectError;
 pub use map::Map;
 pub use map_to_response::MapToResponse;
 #[cfg(feature = "prometheus")]
-pub use prometheus_exporter::PrometheusExporter;
+pub use prometheus_exporter::{Exemplars, PrometheusExporter};
 #[cfg(feature = "static-files")]
 pub use static_files::{StaticFileEndpoint, StaticFilesEndpoint};
 pub use to_response::ToResponse;
 #[cfg(feature = "tower-compat")]
 pub use tower_compat::TowerCompatExt;
+#[cfg(feature = "static-files")]
+pub use transforming_file::{TransformParams, Transformer, TransformingFileEndpoint};
@@ -1,12 +1,32 @@
-use libprometheus::{Encoder, Registry, TextEncoder};
+use std::{
+    collections::HashMap,
+    fmt::Write,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use libopentelemetry::{trace::TraceContextExt, Context};
+use libprometheus::{
+    core::{Collector, Metric},
+    proto::{LabelPair, MetricFamily, MetricType},
+    Encoder, Histogram, Registry, TextEncoder,
+};
+use parking_lot::Mutex;
 
 use crate::{
-    http::{Method, StatusCode},
+    http::{header, Method, StatusCode},
     Endpoint, IntoEndpoint, Request, Response, Result,
 };
 
+const OPENMETRICS_CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
 /// An endpoint that exports metrics for Prometheus.
 ///
+/// If the `Accept` header of the request contains
+/// `application/openmetrics-text`, the metrics are exported in the
+/// [OpenMetrics](https://openmetrics.io) format with the exemplars recorded by
+/// [`Exemplars`], otherwise in the Prometheus text format.
+///
 /// # Example
 ///
 /// ```
@@ -19,12 +39,22 @@ use crate::{
 #[cfg_attr(docsrs, doc(cfg(feature = "prometheus")))]
 pub struct PrometheusExporter {
     registry: Registry,
+    exemplars: Exemplars,
 }
 
 impl PrometheusExporter {
     /// Create a `PrometheusExporter` endpoint.
     pub fn new(registry: Registry) -> Self {
-        Self { registry }
+        Self {
+            registry,
+            exemplars: Exemplars::default(),
+        }
+    }
+
+    /// Sets the exemplars to be exported in the OpenMetrics format.
+    #[must_use]
+    pub fn exemplars(self, exemplars: Exemplars) -> Self {
+        Self { exemplars, ..self }
     }
 }
 
@@ -34,6 +64,7 @@ impl IntoEndpoint for PrometheusExporter {
     fn into_endpoint(self) -> Self::Endpoint {
         PrometheusExporterEndpoint {
             registry: self.registry.clone(),
+            exemplars: self.exemplars,
         }
     }
 }
@@ -41,6 +72,7 @@ impl IntoEndpoint for PrometheusExporter {
 #[doc(hidden)]
 pub struct PrometheusExporterEndpoint {
     registry: Registry,
+    exemplars: Exemplars,
 }
 
 impl Endpoint for PrometheusExporterEndpoint {
@@ -51,8 +83,22 @@ impl Endpoint for PrometheusExporterEndpoint {
             return Ok(StatusCode::METHOD_NOT_ALLOWED.into());
         }
 
-        let encoder = TextEncoder::new();
         let metric_families = self.registry.gather();
+
+        let accept_openmetrics = req
+            .headers()
+            .get(header::ACCEPT)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.contains("application/openmetrics-text"))
+            .unwrap_or_default();
+        if accept_openmetrics {
+            let result = encode_openmetrics(&metric_families, &self.exemplars);
+            return Ok(Response::builder()
+                .content_type(OPENMETRICS_CONTENT_TYPE)
+                .body(result));
+        }
+
+        let encoder = TextEncoder::new();
         let mut result = Vec::new();
         match encoder.encode(&metric_families, &mut result) {
             Ok(()) => Ok(Response::builder().content_type("text/plain").body(result)),
@@ -60,3 +106,349 @@ impl Endpoint for PrometheusExporterEndpoint {
         }
     }
 }
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ExemplarKey {
+    name: String,
+    labels: Vec<(String, String)>,
+    bucket: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Exemplar {
+    labels: Vec<(String, String)>,
+    value: f64,
+    timestamp: f64,
+}
+
+/// Exemplars of histogram buckets, which link the observations to traces.
+///
+/// Each bucket keeps the exemplar of its latest observation.
+///
+/// # Example
+///
+/// ```
+/// use libprometheus::{Histogram, HistogramOpts, Registry};
+/// use poem::{
+///     endpoint::{Exemplars, PrometheusExporter},
+///     Route,
+/// };
+///
+/// let registry = Registry::new();
+/// let histogram = Histogram::with_opts(HistogramOpts::new("latency", "request latency")).unwrap();
+/// registry.register(Box::new(histogram.clone())).unwrap();
+///
+/// let exemplars = Exemplars::new();
+/// exemplars.observe(&histogram, 0.25, &[("trace_id", "4bf92f3577b34da6")]);
+///
+/// let app = Route::new().nest(
+///     "/metrics",
+///     PrometheusExporter::new(registry).exemplars(exemplars),
+/// );
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "prometheus")))]
+#[derive(Clone, Default)]
+pub struct Exemplars(Arc<Mutex<HashMap<ExemplarKey, Exemplar>>>);
+
+impl Exemplars {
+    /// Create an empty `Exemplars`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Observes a value in the histogram and records an exemplar with the
+    /// specified labels for the bucket of the value.
+    pub fn observe(&self, histogram: &Histogram, value: f64, labels: &[(&str, &str)]) {
+        histogram.observe(value);
+
+        let Some(desc) = histogram.desc().into_iter().next() else {
+            return;
+        };
+        let metric = histogram.metric();
+        let bucket = metric
+            .get_histogram()
+            .get_bucket()
+            .iter()
+            .position(|bucket| value <= bucket.get_upper_bound())
+            .unwrap_or(metric.get_histogram().get_bucket().len());
+        let key = ExemplarKey {
+            name: desc.fq_name.clone(),
+            labels: label_pairs(metric.get_label()),
+            bucket,
+        };
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64();
+
+        self.0.lock().insert(
+            key,
+            Exemplar {
+                labels: labels
+                    .iter()
+                    .map(|(name, value)| (name.to_string(), value.to_string()))
+                    .collect(),
+                value,
+                timestamp,
+            },
+        );
+    }
+
+    /// Observes a value in the histogram and records an exemplar with the
+    /// `trace_id` of the current OpenTelemetry context.
+    ///
+    /// If there is no active span, no exemplar is recorded.
+    pub fn observe_with_trace_id(&self, histogram: &Histogram, value: f64) {
+        let cx = Context::current();
+        let span = cx.span();
+        let span_context = span.span_context();
+        if span_context.is_valid() {
+            let trace_id = span_context.trace_id().to_string();
+            self.observe(histogram, value, &[("trace_id", &trace_id)]);
+        } else {
+            histogram.observe(value);
+        }
+    }
+
+    fn get(&self, name: &str, labels: &[LabelPair], bucket: usize) -> Option<Exemplar> {
+        let key = ExemplarKey {
+            name: name.to_string(),
+            labels: label_pairs(labels),
+            bucket,
+        };
+        self.0.lock().get(&key).cloned()
+    }
+}
+
+fn label_pairs(labels: &[LabelPair]) -> Vec<(String, String)> {
+    let mut labels = labels
+        .iter()
+        .map(|label| (label.get_name().to_string(), label.get_value().to_string()))
+        .collect::<Vec<_>>();
+    labels.sort();
+    labels
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn format_float(value: f64) -> String {
+    if value == f64::INFINITY {
+        "+Inf".to_string()
+    } else if value == f64::NEG_INFINITY {
+        "-Inf".to_string()
+    } else if value.is_nan() {
+        "NaN".to_string()
+    } else if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{value:.1}")
+    } else {
+        value.to_string()
+    }
+}
+
+fn write_sample(
+    out: &mut String,
+    name: &str,
+    labels: &[LabelPair],
+    extra_label: Option<(&str, &str)>,
+    value: &str,
+) {
+    out.push_str(name);
+    if !labels.is_empty() || extra_label.is_some() {
+        out.push('{');
+        let labels = labels
+            .iter()
+            .map(|label| (label.get_name(), label.get_value()))
+            .chain(extra_label);
+        for (idx, (name, value)) in labels.enumerate() {
+            if idx > 0 {
+                out.push(',');
+            }
+            let _ = write!(out, "{name}=\"{}\"", escape(value));
+        }
+        out.push('}');
+    }
+    out.push(' ');
+    out.push_str(value);
+}
+
+fn write_exemplar(out: &mut String, exemplar: Option<Exemplar>) {
+    if let Some(exemplar) = exemplar {
+        out.push_str(" # {");
+        for (idx, (name, value)) in exemplar.labels.iter().enumerate() {
+            if idx > 0 {
+                out.push(',');
+            }
+            let _ = write!(out, "{name}=\"{}\"", escape(value));
+        }
+        let _ = write!(out, "}} {} {:.3}", exemplar.value, exemplar.timestamp);
+    }
+    out.push('\n');
+}
+
+fn encode_openmetrics(metric_families: &[MetricFamily], exemplars: &Exemplars) -> String {
+    let mut out = String::new();
+
+    for mf in metric_families {
+        let name = mf.get_name();
+        let (family_name, ty) = match mf.get_field_type() {
+            MetricType::COUNTER => (name.strip_suffix("_total").unwrap_or(name), "counter"),
+            MetricType::GAUGE => (name, "gauge"),
+            MetricType::HISTOGRAM => (name, "histogram"),
+            MetricType::SUMMARY => (name, "summary"),
+            MetricType::UNTYPED => (name, "unknown"),
+        };
+
+        let _ = writeln!(out, "# TYPE {family_name} {ty}");
+        if !mf.get_help().is_empty() {
+            let help = mf.get_help().replace('\\', "\\\\").replace('\n', "\\n");
+            let _ = writeln!(out, "# HELP {family_name} {help}");
+        }
+
+        for m in mf.get_metric() {
+            let labels = m.get_label();
+            match mf.get_field_type() {
+                MetricType::COUNTER => {
+                    let name = format!("{family_name}_total");
+                    let value = m.get_counter().get_value().to_string();
+                    write_sample(&mut out, &name, labels, None, &value);
+                    out.push('\n');
+                }
+                MetricType::GAUGE => {
+                    let value = m.get_gauge().get_value().to_string();
+                    write_sample(&mut out, name, labels, None, &value);
+                    out.push('\n');
+                }
+                MetricType::UNTYPED => {
+                    let value = m.get_untyped().get_value().to_string();
+                    write_sample(&mut out, name, labels, None, &value);
+                    out.push('\n');
+                }
+                MetricType::HISTOGRAM => {
+                    let h = m.get_histogram();
+                    let bucket_name = format!("{name}_bucket");
+                    let mut has_inf = false;
+                    for (idx, bucket) in h.get_bucket().iter().enumerate() {
+                        has_inf |= bucket.get_upper_bound() == f64::INFINITY;
+                        let le = format_float(bucket.get_upper_bound());
+                        let value = bucket.get_cumulative_count().to_string();
+                        write_sample(&mut out, &bucket_name, labels, Some(("le", &le)), &value);
+                        write_exemplar(&mut out, exemplars.get(name, labels, idx));
+                    }
+                    if !has_inf {
+                        let value = h.get_sample_count().to_string();
+                        write_sample(&mut out, &bucket_name, labels, Some(("le", "+Inf")), &value);
+                        write_exemplar(&mut out, exemplars.get(name, labels, h.get_bucket().len()));
+                    }
+                    let count = h.get_sample_count().to_string();
+                    write_sample(&mut out, &format!("{name}_count"), labels, None, &count);
+                    out.push('\n');
+                    let sum = h.get_sample_sum().to_string();
+                    write_sample(&mut out, &format!("{name}_sum"), labels, None, &sum);
+                    out.push('\n');
+                }
+                MetricType::SUMMARY => {
+                    let s = m.get_summary();
+                    for quantile in s.get_quantile() {
+                        let q = format_float(quantile.get_quantile());
+                        let value = quantile.get_value().to_string();
+                        write_sample(&mut out, name, labels, Some(("quantile", &q)), &value);
+                        out.push('\n');
+                    }
+                    let count = s.get_sample_count().to_string();
+                    write_sample(&mut out, &format!("{name}_count"), labels, None, &count);
+                    out.push('\n');
+                    let sum = s.get_sample_sum().to_string();
+                    write_sample(&mut out, &format!("{name}_sum"), labels, None, &sum);
+                    out.push('\n');
+                }
+            }
+        }
+    }
+
+    out.push_str("# EOF\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use libprometheus::{Counter, HistogramOpts, Opts};
+
+    use super::*;
+    use crate::test::TestClient;
+
+    #[tokio::test]
+    async fn openmetrics() {
+        let registry = Registry::new();
+        let counter = Counter::with_opts(Opts::new("requests_total", "total requests")).unwrap();
+        registry.register(Box::new(counter.clone())).unwrap();
+        let histogram = Histogram::with_opts(
+            HistogramOpts::new("latency", "request latency")
+                .const_label("path", "/")
+                .buckets(vec![0.1, 1.0]),
+        )
+        .unwrap();
+        registry.register(Box::new(histogram.clone())).unwrap();
+
+        counter.inc();
+        let exemplars = Exemplars::new();
+        exemplars.observe(&histogram, 0.5, &[("trace_id", "abc")]);
+        histogram.observe(0.05);
+
+        let cli = TestClient::new(PrometheusExporter::new(registry).exemplars(exemplars));
+        let resp = cli
+            .get("/")
+            .header(
+                header::ACCEPT,
+                "application/openmetrics-text; version=1.0.0",
+            )
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+        resp.assert_content_type(OPENMETRICS_CONTENT_TYPE);
+
+        let text = resp.0.into_body().into_string().await.unwrap();
+        let (_, timestamp) = text.split_once(" # {trace_id=\"abc\"} 0.5 ").unwrap();
+        let (_, text) = timestamp.split_once('\n').unwrap();
+        assert!(text.starts_with("latency_bucket{path=\"/\",le=\"+Inf\"} 2\n"));
+        assert!(text.ends_with("# EOF\n"));
+
+        let resp = cli.get("/").send().await;
+        resp.assert_content_type("text/plain");
+        let text = resp.0.into_body().into_string().await.unwrap();
+        assert!(text.contains("requests_total 1"));
+        assert!(!text.contains("# EOF"));
+    }
+
+    #[test]
+    fn encode() {
+        let registry = Registry::new();
+        let counter = Counter::with_opts(Opts::new("requests_total", "total\nrequests")).unwrap();
+        registry.register(Box::new(counter.clone())).unwrap();
+        let histogram =
+            Histogram::with_opts(HistogramOpts::new("latency", "latency").buckets(vec![1.0]))
+                .unwrap();
+        registry.register(Box::new(histogram.clone())).unwrap();
+        counter.inc_by(2.0);
+        histogram.observe(0.5);
+        histogram.observe(3.0);
+
+        assert_eq!(
+            encode_openmetrics(&registry.gather(), &Exemplars::new()),
+            "# TYPE latency histogram\n\
+             # HELP latency latency\n\
+             latency_bucket{le=\"1.0\"} 1\n\
+             latency_bucket{le=\"+Inf\"} 2\n\
+             latency_count 2\n\
+             latency_sum 3.5\n\
+             # TYPE requests counter\n\
+             # HELP requests total\\nrequests\n\
+             requests_total 2\n\
+             # EOF\n"
+        );
+    }
+}
@@ -77,6 +77,7 @@ pub struct StaticFilesEndpoint {
     fallback_to_index: bool,
     prefer_utf8: bool,
     redirect_to_slash: bool,
+    accept_ranges: bool,
 }
 
 impl StaticFilesEndpoint {
@@ -102,6 +103,7 @@ impl StaticFilesEndpoint {
             fallback_to_index: false,
             prefer_utf8: true,
             redirect_to_slash: false,
+            accept_ranges: true,
         }
     }
 
@@ -142,6 +144,20 @@ impl StaticFilesEndpoint {
         }
     }
 
+    /// Specifies whether range requests are supported.
+    ///
+    /// If it is `false`, the `Range` header is ignored and the responses
+    /// contain `Accept-Ranges: none`.
+    ///
+    /// Default is `true`.
+    #[must_use]
+    pub fn accept_ranges(self, value: bool) -> Self {
+        Self {
+            accept_ranges: value,
+            ..self
+        }
+    }
+
     /// Redirects to a slash-ended path when browsing a directory.
     #[must_use]
     pub fn redirect_to_slash_directory(self) -> Self {
@@ -161,6 +177,26 @@ impl StaticFilesEndpoint {
     }
 }
 
+/// Joins the decoded path of the request to the base directory, and rejects
+/// the result if it is outside of the base directory.
+pub(crate) fn resolve_path(base: &Path, path: &str) -> Result<PathBuf, StaticFileError> {
+    let mut file_path = base.to_path_buf();
+    for p in Path::new(path) {
+        if p == OsStr::new(".") {
+            continue;
+        } else if p == OsStr::new("..") {
+            file_path.pop();
+        } else {
+            file_path.push(p);
+        }
+    }
+
+    if !file_path.starts_with(base) {
+        return Err(StaticFileError::Forbidden(file_path.display().to_string()));
+    }
+    Ok(file_path)
+}
+
 impl Endpoint for StaticFilesEndpoint {
     type Output = Response;
 
@@ -179,20 +215,7 @@ impl Endpoint for StaticFilesEndpoint {
             .decode_utf8()
             .map_err(|_| StaticFileError::InvalidPath)?;
 
-        let mut file_path = self.path.clone();
-        for p in Path::new(&*path) {
-            if p == OsStr::new(".") {
-                continue;
-            } else if p == OsStr::new("..") {
-                file_path.pop();
-            } else {
-                file_path.push(p);
-            }
-        }
-
-        if !file_path.starts_with(&self.path) {
-            return Err(StaticFileError::Forbidden(file_path.display().to_string()).into());
-        }
+        let file_path = resolve_path(&self.path, &path)?;
 
         if !file_path.exists() {
             if self.fallback_to_index {
@@ -201,6 +224,7 @@ impl Endpoint for StaticFilesEndpoint {
                     if index_path.is_file() {
                         return Ok(StaticFileRequest::from_request_without_body(&req)
                             .await?
+                            .accept_ranges(self.accept_ranges)
                             .create_response(&index_path, self.prefer_utf8)?
                             .into_response());
                     }
@@ -212,6 +236,7 @@ impl Endpoint for StaticFilesEndpoint {
         if file_path.is_file() {
             Ok(StaticFileRequest::from_request_without_body(&req)
                 .await?
+                .accept_ranges(self.accept_ranges)
                 .create_response(&file_path, self.prefer_utf8)?
                 .into_response())
         } else {
@@ -231,6 +256,7 @@ impl Endpoint for StaticFilesEndpoint {
                 if index_path.is_file() {
                     return Ok(StaticFileRequest::from_request_without_body(&req)
                         .await?
+                        .accept_ranges(self.accept_ranges)
                         .create_response(&index_path, self.prefer_utf8)?
                         .into_response());
                 }
@@ -283,6 +309,7 @@ impl Endpoint for StaticFilesEndpoint {
 pub struct StaticFileEndpoint {
     path: PathBuf,
     prefer_utf8: bool,
+    accept_ranges: bool,
 }
 
 impl StaticFileEndpoint {
@@ -299,6 +326,7 @@ impl StaticFileEndpoint {
         Self {
             path: path.into(),
             prefer_utf8: true,
+            accept_ranges: true,
         }
     }
 
@@ -312,6 +340,20 @@ impl StaticFileEndpoint {
             ..self
         }
     }
+
+    /// Specifies whether range requests are supported.
+    ///
+    /// If it is `false`, the `Range` header is ignored and the responses
+    /// contain `Accept-Ranges: none`.
+    ///
+    /// Default is `true`.
+    #[must_use]
+    pub fn accept_ranges(self, value: bool) -> Self {
+        Self {
+            accept_ranges: value,
+            ..self
+        }
+    }
 }
 
 impl Endpoint for StaticFileEndpoint {
@@ -320,6 +362,7 @@ impl Endpoint for StaticFileEndpoint {
     async fn call(&self, req: Request) -> Result<Self::Output> {
         Ok(StaticFileRequest::from_request_without_body(&req)
             .await?
+            .accept_ranges(self.accept_ranges)
             .create_response(&self.path, self.prefer_utf8)?
             .into_response())
     }
@@ -0,0 +1,537 @@
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::SystemTime,
+};
+
+use bytes::Bytes;
+use parking_lot::Mutex;
+use tokio::sync::OnceCell;
+
+use super::static_files::resolve_path;
+use crate::{
+    error::StaticFileError,
+    http::{Method, StatusCode},
+    web::{Query, StaticFileRequest},
+    Endpoint, Error, FromRequest, IntoResponse, Request, Response, Result,
+};
+
+/// The query parameters of the request, which are passed to the
+/// [`Transformer`].
+pub type TransformParams = BTreeMap<String, String>;
+
+/// Represents a transformation of the files served by
+/// [`TransformingFileEndpoint`], such as resizing images.
+///
+/// It is implemented for the functions of
+/// `Fn(Bytes, &TransformParams) -> Result<Bytes>`.
+pub trait Transformer: Send + Sync + 'static {
+    /// Transforms the content of the file with the query parameters of the
+    /// request.
+    ///
+    /// It is called in a blocking thread, so that it can do CPU-intensive
+    /// work.
+    fn transform(&self, data: Bytes, params: &TransformParams) -> Result<Bytes>;
+
+    /// Returns the content type of the transformed file, if it differs from
+    /// the content type of the original one, for example if the image is
+    /// converted to another format.
+    ///
+    /// Default is `None`, which means that the content type is guessed from
+    /// the file extension.
+    fn content_type(&self, path: &Path, params: &TransformParams) -> Option<String> {
+        let _ = (path, params);
+        None
+    }
+}
+
+impl<F> Transformer for F
+where
+    F: Fn(Bytes, &TransformParams) -> Result<Bytes> + Send + Sync + 'static,
+{
+    fn transform(&self, data: Bytes, params: &TransformParams) -> Result<Bytes> {
+        (self)(data, params)
+    }
+}
+
+#[derive(Clone, Hash, Eq, PartialEq)]
+struct CacheKey {
+    path: PathBuf,
+    params: TransformParams,
+    modified: Option<SystemTime>,
+}
+
+struct CacheEntry {
+    cell: Arc<OnceCell<Bytes>>,
+    size: usize,
+}
+
+#[derive(Default)]
+struct TransformCache {
+    entries: HashMap<CacheKey, CacheEntry>,
+    order: VecDeque<CacheKey>,
+    size: usize,
+}
+
+impl TransformCache {
+    fn evict_oldest(&mut self) -> bool {
+        let Some(oldest) = self.order.pop_front() else {
+            return false;
+        };
+        if let Some(entry) = self.entries.remove(&oldest) {
+            self.size -= entry.size;
+        }
+        true
+    }
+
+    fn remove(&mut self, key: &CacheKey) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.size -= entry.size;
+            self.order.retain(|k| k != key);
+        }
+    }
+}
+
+/// Static files handling service that transforms the files with a
+/// [`Transformer`] on the fly, such as serving the resized variants of
+/// images.
+///
+/// The transformer receives the content of the file and the query parameters
+/// of the request, so `/images/foo.jpg?w=200` can be served by resizing
+/// `foo.jpg` to 200 pixels wide. The transformed files are cached in memory
+/// by the path, the query parameters and the modification time of the file,
+/// and the oldest entries are evicted when the cache is full.
+///
+/// Since every distinct query creates a cache entry and runs the
+/// transformer, the parameters the transformer uses should be set with
+/// [`TransformingFileEndpoint::allow_params`], so that the clients cannot
+/// evict the cache by appending other parameters.
+///
+/// # Errors
+///
+/// - [`StaticFileError`]
+/// - [`ParseQueryError`](crate::error::ParseQueryError)
+/// - The errors returned by the transformer
+///
+/// # Example
+///
+/// ```
+/// use bytes::Bytes;
+/// use poem::{
+///     endpoint::{TransformParams, TransformingFileEndpoint},
+///     http::StatusCode,
+///     Error, Result, Route,
+/// };
+///
+/// fn resize(data: Bytes, params: &TransformParams) -> Result<Bytes> {
+///     let Some(width) = params.get("w") else {
+///         return Ok(data);
+///     };
+///     let width: u32 = width
+///         .parse()
+///         .map_err(|err| Error::new(err, StatusCode::BAD_REQUEST))?;
+///     // decode, resize and encode the image...
+///     # let _ = width;
+///     Ok(data)
+/// }
+///
+/// let app = Route::new().nest(
+///     "/images",
+///     TransformingFileEndpoint::new("/var/www/images", resize).allow_params(["w"]),
+/// );
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "static-files")))]
+pub struct TransformingFileEndpoint<T> {
+    path: PathBuf,
+    transformer: Arc<T>,
+    allow_params: Option<Vec<String>>,
+    cache_capacity: usize,
+    cache_max_size: usize,
+    cache: Mutex<TransformCache>,
+}
+
+impl<T: Transformer> TransformingFileEndpoint<T> {
+    /// Create new service for the files in the specified directory, which are
+    /// transformed with the specified transformer.
+    pub fn new(path: impl Into<PathBuf>, transformer: T) -> Self {
+        Self {
+            path: path.into(),
+            transformer: Arc::new(transformer),
+            allow_params: None,
+            cache_capacity: 1024,
+            cache_max_size: 64 * 1024 * 1024,
+            cache: Default::default(),
+        }
+    }
+
+    /// Sets the query parameters that are passed to the transformer and make
+    /// up the cache key, the other parameters are ignored.
+    ///
+    /// Default is to pass all the query parameters.
+    #[must_use]
+    pub fn allow_params<I, P>(self, params: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<String>,
+    {
+        Self {
+            allow_params: Some(params.into_iter().map(Into::into).collect()),
+            ..self
+        }
+    }
+
+    /// Sets the maximum number of the transformed files in the cache, `0`
+    /// disables the cache.
+    ///
+    /// Default is `1024`.
+    #[must_use]
+    pub fn cache_capacity(self, capacity: usize) -> Self {
+        Self {
+            cache_capacity: capacity,
+            ..self
+        }
+    }
+
+    /// Sets the maximum total size in bytes of the transformed files in the
+    /// cache, a file larger than it is not cached and does not evict the other
+    /// files.
+    ///
+    /// Default is `64MB`.
+    #[must_use]
+    pub fn cache_max_size(self, size: usize) -> Self {
+        Self {
+            cache_max_size: size,
+            ..self
+        }
+    }
+
+    fn cache_cell(&self, key: &CacheKey) -> Arc<OnceCell<Bytes>> {
+        if self.cache_capacity == 0 {
+            return Default::default();
+        }
+
+        let mut cache = self.cache.lock();
+        if let Some(entry) = cache.entries.get(key) {
+            return entry.cell.clone();
+        }
+        while cache.entries.len() >= self.cache_capacity && cache.evict_oldest() {}
+        let cell = Arc::new(OnceCell::new());
+        cache.entries.insert(
+            key.clone(),
+            CacheEntry {
+                cell: cell.clone(),
+                size: 0,
+            },
+        );
+        cache.order.push_back(key.clone());
+        cell
+    }
+
+    /// Records the size of the transformed file, and evicts the oldest entries
+    /// until the cache is not larger than the maximum size, or removes the
+    /// file if it is larger than the maximum size.
+    fn cache_size(&self, key: &CacheKey, cell: &Arc<OnceCell<Bytes>>, size: usize) {
+        let mut cache = self.cache.lock();
+        let Some(entry) = cache.entries.get_mut(key) else {
+            return;
+        };
+        // the entry may have been evicted and created again, or recorded by
+        // another request
+        if !Arc::ptr_eq(&entry.cell, cell) || entry.size != 0 {
+            return;
+        }
+        if size > self.cache_max_size {
+            cache.remove(key);
+            return;
+        }
+        entry.size = size;
+        cache.size += size;
+        while cache.size > self.cache_max_size && cache.evict_oldest() {}
+    }
+
+    /// Removes the entry of a file that failed to be transformed.
+    fn cache_remove(&self, key: &CacheKey, cell: &Arc<OnceCell<Bytes>>) {
+        let mut cache = self.cache.lock();
+        if cache
+            .entries
+            .get(key)
+            .is_some_and(|entry| Arc::ptr_eq(&entry.cell, cell))
+        {
+            cache.remove(key);
+        }
+    }
+}
+
+impl<T: Transformer> Endpoint for TransformingFileEndpoint<T> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        if req.method() != Method::GET {
+            return Err(StaticFileError::MethodNotAllowed(req.method().clone()).into());
+        }
+
+        let path = percent_encoding::percent_decode_str(req.uri().path().trim_start_matches('/'))
+            .decode_utf8()
+            .map_err(|_| StaticFileError::InvalidPath)?;
+        let file_path = resolve_path(&self.path, &path)?;
+        let metadata = tokio::fs::metadata(&file_path)
+            .await
+            .ok()
+            .filter(|metadata| metadata.is_file())
+            .ok_or(StaticFileError::NotFound)?;
+        let Query(mut params) = Query::<TransformParams>::from_request_without_body(&req).await?;
+        if let Some(allow_params) = &self.allow_params {
+            params.retain(|name, _| allow_params.contains(name));
+        }
+
+        let key = CacheKey {
+            path: file_path.clone(),
+            params: params.clone(),
+            modified: metadata.modified().ok(),
+        };
+        let cell = self.cache_cell(&key);
+        let res = cell
+            .get_or_try_init(|| async {
+                let data = tokio::fs::read(&file_path)
+                    .await
+                    .map_err(StaticFileError::Io)?;
+                let transformer = self.transformer.clone();
+                let params = params.clone();
+                match tokio::task::spawn_blocking(move || {
+                    transformer.transform(data.into(), &params)
+                })
+                .await
+                {
+                    Ok(res) => res,
+                    Err(err) if err.is_panic() => std::panic::resume_unwind(err.into_panic()),
+                    Err(_) => Err(Error::from_status(StatusCode::SERVICE_UNAVAILABLE)),
+                }
+            })
+            .await;
+        let data = match res {
+            Ok(data) => data.clone(),
+            Err(err) => {
+                self.cache_remove(&key, &cell);
+                return Err(err);
+            }
+        };
+        self.cache_size(&key, &cell, data.len());
+
+        let content_type = self
+            .transformer
+            .content_type(&file_path, &params)
+            .or_else(|| {
+                mime_guess::from_path(&file_path)
+                    .first()
+                    .map(|mime| mime.to_string())
+            });
+        let resp = StaticFileRequest::from_request_without_body(&req)
+            .await?
+            .create_response_from_data(data)?;
+        Ok(match content_type {
+            Some(content_type) => resp.with_content_type(content_type),
+            None => resp,
+        }
+        .into_response())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use http::header;
+
+    use super::*;
+    use crate::test::TestClient;
+
+    #[tokio::test]
+    async fn transforming_file() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let transformer = {
+            let calls = calls.clone();
+            move |data: Bytes, params: &TransformParams| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                let n = match params.get("n") {
+                    Some(n) => n
+                        .parse()
+                        .map_err(|err| Error::new(err, StatusCode::BAD_REQUEST))?,
+                    None => data.len(),
+                };
+                Ok(data.slice(..n))
+            }
+        };
+        let cli = TestClient::new(TransformingFileEndpoint::new(".", transformer));
+        let content = std::fs::read_to_string("Cargo.toml").unwrap();
+
+        for _ in 0..2 {
+            let resp = cli.get("/Cargo.toml").query("n", &7).send().await;
+            resp.assert_status_is_ok();
+            resp.assert_header(header::CONTENT_TYPE, "text/x-toml");
+            resp.assert_text(&content[..7]).await;
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        cli.get("/Cargo.toml")
+            .send()
+            .await
+            .assert_text(&content)
+            .await;
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        cli.get("/Cargo.toml")
+            .query("n", &"a")
+            .send()
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+        cli.get("/missing.jpg")
+            .send()
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+        cli.get("/../Cargo.toml")
+            .send()
+            .await
+            .assert_status(StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn cache_capacity() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let transformer = {
+            let calls = calls.clone();
+            move |data: Bytes, _: &TransformParams| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Ok(data)
+            }
+        };
+        let cli =
+            TestClient::new(TransformingFileEndpoint::new(".", transformer).cache_capacity(1));
+
+        for n in [1, 2, 1] {
+            cli.get("/Cargo.toml")
+                .query("n", &n)
+                .send()
+                .await
+                .assert_status_is_ok();
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn cache_failed_transform() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let transformer = {
+            let calls = calls.clone();
+            move |data: Bytes, params: &TransformParams| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                match params.get("n").map(String::as_str) {
+                    Some("error") => Err(Error::from_status(StatusCode::BAD_REQUEST)),
+                    _ => Ok(data),
+                }
+            }
+        };
+        let cli =
+            TestClient::new(TransformingFileEndpoint::new(".", transformer).cache_capacity(2));
+
+        cli.get("/Cargo.toml")
+            .query("n", &1)
+            .send()
+            .await
+            .assert_status_is_ok();
+        cli.get("/Cargo.toml")
+            .query("n", &"error")
+            .send()
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+        // the failed transform does not occupy a slot of the cache
+        for n in [2, 1] {
+            cli.get("/Cargo.toml")
+                .query("n", &n)
+                .send()
+                .await
+                .assert_status_is_ok();
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn cache_max_size() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let transformer = {
+            let calls = calls.clone();
+            move |data: Bytes, params: &TransformParams| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                let n = params.get("n").map_or(Ok(data.len()), |n| n.parse());
+                Ok(data.slice(..n.map_err(|err| Error::new(err, StatusCode::BAD_REQUEST))?))
+            }
+        };
+        let cli =
+            TestClient::new(TransformingFileEndpoint::new(".", transformer).cache_max_size(10));
+
+        // the two files fit in the cache
+        for n in [4, 6, 4, 6] {
+            cli.get("/Cargo.toml")
+                .query("n", &n)
+                .send()
+                .await
+                .assert_status_is_ok();
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // the oldest files are evicted
+        for n in [5, 4] {
+            cli.get("/Cargo.toml")
+                .query("n", &n)
+                .send()
+                .await
+                .assert_status_is_ok();
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+
+        // a file larger than the cache is not cached
+        for _ in 0..2 {
+            cli.get("/Cargo.toml")
+                .query("n", &11)
+                .send()
+                .await
+                .assert_status_is_ok();
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 6);
+
+        // and does not evict the other files
+        for n in [5, 4] {
+            cli.get("/Cargo.toml")
+                .query("n", &n)
+                .send()
+                .await
+                .assert_status_is_ok();
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 6);
+    }
+
+    #[tokio::test]
+    async fn allow_params() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let transformer = {
+            let calls = calls.clone();
+            move |data: Bytes, params: &TransformParams| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                assert!(!params.contains_key("x"));
+                Ok(data)
+            }
+        };
+        let cli =
+            TestClient::new(TransformingFileEndpoint::new(".", transformer).allow_params(["n"]));
+
+        for x in [1, 2] {
+            cli.get("/Cargo.toml")
+                .query("n", &1)
+                .query("x", &x)
+                .send()
+                .await
+                .assert_status_is_ok();
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -4,13 +4,15 @@ use std::{
     convert::Infallible,
     error::Error as StdError,
     fmt::{self, Debug, Display, Formatter},
+    io::ErrorKind,
     string::FromUtf8Error,
+    sync::Arc,
 };
 
 use headers::{ContentRange, HeaderMapExt};
 use http::{Extensions, Method};
 
-use crate::{http::StatusCode, IntoResponse, Response};
+use crate::{http::StatusCode, web::Json, IntoResponse, Response};
 
 macro_rules! define_http_error {
     ($($(#[$docs:meta])* ($name:ident, $status:ident);)*) => {
@@ -467,11 +469,85 @@ impl Error {
         self.extensions.insert(data);
     }
 
+    /// Inserts a value to extensions and returns this error, see
+    /// [`Error::set_data`].
+    ///
+    /// The extensions can carry the structured data of the error, which is
+    /// added where the error is produced or by the intermediate middlewares,
+    /// and rendered by the outermost one.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use poem::{
+    ///     handler, http::StatusCode, test::TestClient, web::Json, EndpointExt, Error, IntoResponse,
+    ///     Result,
+    /// };
+    ///
+    /// #[derive(Clone)]
+    /// struct Problem {
+    ///     code: &'static str,
+    ///     retryable: bool,
+    /// }
+    ///
+    /// #[handler]
+    /// fn index() -> Result<()> {
+    ///     Err(Error::from_status(StatusCode::SERVICE_UNAVAILABLE).with_data(Problem {
+    ///         code: "upstream_unavailable",
+    ///         retryable: true,
+    ///     }))
+    /// }
+    ///
+    /// let app = index.catch_all_error(|err| async move {
+    ///     match err.data::<Problem>() {
+    ///         Some(problem) => Json(serde_json::json!({
+    ///             "code": problem.code,
+    ///             "retryable": problem.retryable,
+    ///         }))
+    ///         .with_status(err.status())
+    ///         .into_response(),
+    ///         None => err.into_response(),
+    ///     }
+    /// });
+    /// let cli = TestClient::new(app);
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let resp = cli.get("/").send().await;
+    /// resp.assert_status(StatusCode::SERVICE_UNAVAILABLE);
+    /// resp.assert_json(serde_json::json!({ "code": "upstream_unavailable", "retryable": true }))
+    ///     .await;
+    /// # });
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn with_data(mut self, data: impl Clone + Send + Sync + 'static) -> Self {
+        self.set_data(data);
+        self
+    }
+
     /// Get a reference from extensions
     pub fn data<T: Send + Sync + 'static>(&self) -> Option<&T> {
         self.extensions.get()
     }
 
+    /// Get a mutable reference from extensions, to enrich the data that has
+    /// been inserted.
+    pub fn data_mut<T: Send + Sync + 'static>(&mut self) -> Option<&mut T> {
+        self.extensions.get_mut()
+    }
+
+    /// Returns a reference to the associated extensions.
+    #[inline]
+    pub fn extensions(&self) -> &Extensions {
+        &self.extensions
+    }
+
+    /// Returns a mutable reference to the associated extensions.
+    #[inline]
+    pub fn extensions_mut(&mut self) -> &mut Extensions {
+        &mut self.extensions
+    }
+
     /// Get the status code of the error
     pub fn status(&self) -> StatusCode {
         match &self.as_response {
@@ -668,6 +744,9 @@ impl ResponseError for ReadBodyError {
         match self {
             ReadBodyError::BodyHasBeenTaken => StatusCode::INTERNAL_SERVER_ERROR,
             ReadBodyError::Utf8(_) => StatusCode::BAD_REQUEST,
+            ReadBodyError::Io(err) if err.kind() == ErrorKind::TimedOut => {
+                StatusCode::REQUEST_TIMEOUT
+            }
             ReadBodyError::Io(_) => StatusCode::BAD_REQUEST,
             ReadBodyError::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
         }
@@ -790,6 +869,34 @@ impl ResponseError for ParseXmlError {
     }
 }
 
+/// A possible error value when parsing Protobuf.
+#[cfg(feature = "protobuf")]
+#[derive(Debug, thiserror::Error)]
+pub enum ParseProtobufError {
+    /// Invalid content type.
+    #[error("invalid content type `{0}`, expect: `application/x-protobuf`")]
+    InvalidContentType(String),
+
+    /// `Content-Type` header is required.
+    #[error("expect content type `application/x-protobuf`")]
+    ContentTypeRequired,
+
+    /// Decode error.
+    #[error("parse error: {0}")]
+    Parse(#[from] prost::DecodeError),
+}
+
+#[cfg(feature = "protobuf")]
+impl ResponseError for ParseProtobufError {
+    fn status(&self) -> StatusCode {
+        match self {
+            ParseProtobufError::InvalidContentType(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            ParseProtobufError::ContentTypeRequired => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            ParseProtobufError::Parse(_) => StatusCode::BAD_REQUEST,
+        }
+    }
+}
+
 /// A possible error value when parsing YAML.
 #[cfg(feature = "yaml")]
 #[derive(Debug, thiserror::Error)]
@@ -853,6 +960,14 @@ pub enum ParseMultipartError {
     /// Io error
     #[error("io: {0}")]
     Io(#[from] std::io::Error),
+
+    /// Failed to deserialize the fields.
+    #[error("deserialize: {0}")]
+    Deserialize(#[from] serde::de::value::Error),
+
+    /// A file is larger than the limit.
+    #[error("the file is larger than the limit of {0} bytes")]
+    FileTooLarge(u64),
 }
 
 #[cfg(feature = "multipart")]
@@ -864,6 +979,8 @@ impl ResponseError for ParseMultipartError {
             ParseMultipartError::Multipart(_) => StatusCode::BAD_REQUEST,
             ParseMultipartError::Utf8(_) => StatusCode::BAD_REQUEST,
             ParseMultipartError::Io(_) => StatusCode::BAD_REQUEST,
+            ParseMultipartError::Deserialize(_) => StatusCode::BAD_REQUEST,
+            ParseMultipartError::FileTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
         }
     }
 }
@@ -886,6 +1003,30 @@ impl ResponseError for ParseTypedHeaderError {
     }
 }
 
+/// A possible error value when parsing the `Authorization` header.
+#[derive(Debug, thiserror::Error, Eq, PartialEq)]
+pub enum ParseAuthorizationError {
+    /// The `Authorization` header is required.
+    ///
+    /// The response is `401 Unauthorized` without the `WWW-Authenticate`
+    /// header, which the application must add with its own challenge.
+    #[error("header `authorization` is required")]
+    Missing,
+
+    /// Invalid `Authorization` header.
+    #[error("invalid `authorization` header")]
+    Invalid,
+}
+
+impl ResponseError for ParseAuthorizationError {
+    fn status(&self) -> StatusCode {
+        match self {
+            ParseAuthorizationError::Missing => StatusCode::UNAUTHORIZED,
+            ParseAuthorizationError::Invalid => StatusCode::BAD_REQUEST,
+        }
+    }
+}
+
 /// A possible error value when handling websocket.
 #[cfg(feature = "websocket")]
 #[cfg_attr(docsrs, doc(cfg(feature = "websocket")))]
@@ -910,6 +1051,30 @@ impl ResponseError for WebSocketError {
     }
 }
 
+/// A possible error value occurred in the `WebSocketLimit` middleware.
+#[cfg(feature = "websocket")]
+#[cfg_attr(docsrs, doc(cfg(feature = "websocket")))]
+#[derive(Debug, thiserror::Error, Eq, PartialEq)]
+pub enum WebSocketLimitError {
+    /// Too many websocket connections
+    #[error("too many websocket connections")]
+    TooManyConnections,
+
+    /// Too many websocket connections of the key
+    #[error("too many websocket connections of the key")]
+    TooManyConnectionsPerKey,
+}
+
+#[cfg(feature = "websocket")]
+impl ResponseError for WebSocketLimitError {
+    fn status(&self) -> StatusCode {
+        match self {
+            WebSocketLimitError::TooManyConnections => StatusCode::SERVICE_UNAVAILABLE,
+            WebSocketLimitError::TooManyConnectionsPerKey => StatusCode::TOO_MANY_REQUESTS,
+        }
+    }
+}
+
 /// A possible error value when upgrading connection.
 #[derive(Debug, thiserror::Error)]
 pub enum UpgradeError {
@@ -1011,6 +1176,102 @@ impl ResponseError for SizedLimitError {
     }
 }
 
+/// A possible error value occurred in the `QueryLimit` middleware.
+#[derive(Debug, thiserror::Error, Eq, PartialEq)]
+pub enum QueryLimitError {
+    /// Query string too long
+    #[error("query string too long")]
+    QueryTooLong,
+
+    /// Too many query parameters
+    #[error("too many query parameters")]
+    TooManyParams,
+}
+
+impl ResponseError for QueryLimitError {
+    fn status(&self) -> StatusCode {
+        match self {
+            QueryLimitError::QueryTooLong => StatusCode::URI_TOO_LONG,
+            QueryLimitError::TooManyParams => StatusCode::BAD_REQUEST,
+        }
+    }
+}
+
+/// A possible error value occurred in the `SameOrigin` middleware.
+#[derive(Debug, thiserror::Error, Eq, PartialEq)]
+pub enum SameOriginError {
+    /// Missing `Origin` and `Referer` headers
+    #[error("missing `Origin` and `Referer` headers")]
+    MissingOrigin,
+
+    /// Cross-origin request
+    #[error("cross-origin request")]
+    CrossOrigin,
+}
+
+impl ResponseError for SameOriginError {
+    fn status(&self) -> StatusCode {
+        StatusCode::FORBIDDEN
+    }
+}
+
+/// A possible error value occurred in the `MaxResponseSize` middleware.
+#[derive(Debug, thiserror::Error, Eq, PartialEq)]
+pub enum MaxResponseSizeError {
+    /// Response too large
+    #[error("response too large")]
+    ResponseTooLarge,
+}
+
+impl ResponseError for MaxResponseSizeError {
+    fn status(&self) -> StatusCode {
+        StatusCode::INTERNAL_SERVER_ERROR
+    }
+}
+
+/// A possible error value occurred in the `WebhookSignature` middleware.
+#[cfg(feature = "webhook")]
+#[cfg_attr(docsrs, doc(cfg(feature = "webhook")))]
+#[derive(Debug, thiserror::Error, Eq, PartialEq)]
+pub enum WebhookSignatureError {
+    /// Missing signature header
+    #[error("missing signature header")]
+    MissingSignature,
+
+    /// The signature does not match
+    #[error("invalid signature")]
+    InvalidSignature,
+
+    /// Missing timestamp header
+    #[error("missing timestamp header")]
+    MissingTimestamp,
+
+    /// The timestamp is invalid or outside the tolerance
+    #[error("invalid timestamp")]
+    InvalidTimestamp,
+}
+
+#[cfg(feature = "webhook")]
+impl ResponseError for WebhookSignatureError {
+    fn status(&self) -> StatusCode {
+        StatusCode::UNAUTHORIZED
+    }
+}
+
+/// A possible error value occurred in the `TenantContext` middleware.
+#[derive(Debug, thiserror::Error, Eq, PartialEq)]
+pub enum TenantContextError {
+    /// The tenant is required but cannot be resolved
+    #[error("missing tenant")]
+    MissingTenant,
+}
+
+impl ResponseError for TenantContextError {
+    fn status(&self) -> StatusCode {
+        StatusCode::BAD_REQUEST
+    }
+}
+
 /// A possible error value occurred when adding a route.
 #[derive(Debug, thiserror::Error, Eq, PartialEq)]
 pub enum RouteError {
@@ -1116,6 +1377,226 @@ impl ResponseError for RedisSessionError {
     }
 }
 
+/// Details of an error that occurred while parsing the request, passed to
+/// [`ErrorFormat`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ParseErrorDetails {
+    /// The status code of the error.
+    pub status: StatusCode,
+    /// A stable, machine-readable code of the error, such as
+    /// `invalid_content_type` or `header_required`.
+    pub code: &'static str,
+    /// The error message.
+    pub message: String,
+    /// The name of the field or header that caused the error, if known.
+    ///
+    /// The names of the fields of the request bodies and parameters are
+    /// found in the messages of the serde errors, so this is best-effort and
+    /// may be `None` for the formats with other messages.
+    pub field: Option<String>,
+}
+
+impl ParseErrorDetails {
+    fn new(status: StatusCode, code: &'static str, message: String) -> Self {
+        let field = serde_field(&message);
+        Self {
+            status,
+            code,
+            message,
+            field,
+        }
+    }
+
+    fn from_error(err: &Error) -> Option<Self> {
+        macro_rules! content_type_errors {
+            ($err:expr, $ty:ident, $($parse:ident => $code:literal),*) => {
+                if let Some(e) = $err.downcast_ref::<$ty>() {
+                    let code = match e {
+                        $ty::InvalidContentType(_) => "invalid_content_type",
+                        $ty::ContentTypeRequired => "content_type_required",
+                        $($ty::$parse(_) => $code,)*
+                    };
+                    return Some(Self::new(e.status(), code, e.to_string()));
+                }
+            };
+        }
+
+        if let Some(e) = err.downcast_ref::<ReadBodyError>() {
+            let code = match e {
+                ReadBodyError::BodyHasBeenTaken => return None,
+                ReadBodyError::Utf8(_) => "invalid_utf8",
+                ReadBodyError::PayloadTooLarge => "payload_too_large",
+                ReadBodyError::Io(_) => "read_body",
+            };
+            return Some(Self::new(e.status(), code, e.to_string()));
+        }
+
+        content_type_errors!(err, ParseFormError, UrlDecode => "invalid_body");
+        content_type_errors!(err, ParseJsonError, Parse => "invalid_body");
+        #[cfg(feature = "xml")]
+        content_type_errors!(err, ParseXmlError, Parse => "invalid_body");
+        #[cfg(feature = "yaml")]
+        content_type_errors!(err, ParseYamlError, Parse => "invalid_body");
+        #[cfg(feature = "protobuf")]
+        content_type_errors!(err, ParseProtobufError, Parse => "invalid_body");
+        #[cfg(feature = "multipart")]
+        content_type_errors!(
+            err,
+            ParseMultipartError,
+            Multipart => "invalid_body",
+            Utf8 => "invalid_utf8",
+            Io => "read_body",
+            Deserialize => "invalid_body",
+            FileTooLarge => "payload_too_large"
+        );
+
+        #[cfg(feature = "cookie")]
+        if let Some(e) = err.downcast_ref::<ParseCookieError>() {
+            let code = match e {
+                ParseCookieError::CookieHeaderRequired => "cookie_required",
+                ParseCookieError::CookieIllegal | ParseCookieError::ParseJsonValue(_) => {
+                    "invalid_cookie"
+                }
+            };
+            return Some(Self::new(e.status(), code, e.to_string()));
+        }
+
+        if let Some(e) = err.downcast_ref::<ParseTypedHeaderError>() {
+            return Some(match e {
+                ParseTypedHeaderError::HeaderRequired(name) => Self {
+                    status: e.status(),
+                    code: "header_required",
+                    message: e.to_string(),
+                    field: Some(name.clone()),
+                },
+                ParseTypedHeaderError::TypedHeader(_) => {
+                    Self::new(e.status(), "invalid_header", e.to_string())
+                }
+            });
+        }
+
+        if let Some(e) = err.downcast_ref::<ParseAuthorizationError>() {
+            return Some(match e {
+                ParseAuthorizationError::Missing => Self {
+                    status: e.status(),
+                    code: "header_required",
+                    message: e.to_string(),
+                    field: Some("authorization".to_string()),
+                },
+                ParseAuthorizationError::Invalid => {
+                    Self::new(e.status(), "invalid_header", e.to_string())
+                }
+            });
+        }
+
+        if let Some(e) = err.downcast_ref::<ParseQueryError>() {
+            return Some(Self::new(e.status(), "invalid_query", e.to_string()));
+        }
+
+        if let Some(e) = err.downcast_ref::<ParsePathError>() {
+            return Some(Self::new(e.status(), "invalid_path", e.to_string()));
+        }
+
+        None
+    }
+}
+
+/// Extracts the field name from the serde error messages, such as ``missing
+/// field `name` ``.
+///
+/// It relies on the English messages of the derived `Deserialize`
+/// implementations, so it is best-effort.
+fn serde_field(message: &str) -> Option<String> {
+    ["missing field `", "unknown field `", "duplicate field `"]
+        .iter()
+        .find_map(|prefix| {
+            let (_, rest) = message.split_once(prefix)?;
+            let (field, _) = rest.split_once('`')?;
+            Some(field.to_string())
+        })
+}
+
+/// Customizes how the errors that occurred while parsing the request are
+/// rendered.
+///
+/// It applies to the errors of the built-in extractors, such as
+/// [`ParseJsonError`], [`ParseQueryError`] and [`ParseTypedHeaderError`],
+/// other errors are not changed.
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     error::ErrorFormat, handler, http::StatusCode, post, test::TestClient, web::Json, Route,
+/// };
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct User {
+///     name: String,
+/// }
+///
+/// #[handler]
+/// fn index(user: Json<User>) -> String {
+///     user.0.name
+/// }
+///
+/// let app = Route::new()
+///     .at("/", post(index))
+///     .error_format(ErrorFormat::json());
+/// let cli = TestClient::new(app);
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = cli.post("/").body_json(&serde_json::json!({})).send().await;
+/// resp.assert_status(StatusCode::BAD_REQUEST);
+/// let json = resp.json().await;
+/// let value = json.value().object();
+/// value.get("code").assert_string("invalid_body");
+/// value.get("field").assert_string("name");
+/// # });
+/// ```
+#[derive(Clone)]
+pub struct ErrorFormat(Arc<dyn Fn(&ParseErrorDetails) -> Response + Send + Sync>);
+
+impl ErrorFormat {
+    /// Create an `ErrorFormat` with a function that renders the error.
+    pub fn new<F>(f: F) -> Self
+    where
+        F: Fn(&ParseErrorDetails) -> Response + Send + Sync + 'static,
+    {
+        Self(Arc::new(f))
+    }
+
+    /// Renders the errors as JSON objects with `code`, `message` and `field`
+    /// properties.
+    pub fn json() -> Self {
+        Self::new(|details| {
+            Json(serde_json::json!({
+                "code": details.code,
+                "message": details.message,
+                "field": details.field,
+            }))
+            .with_status(details.status)
+            .into_response()
+        })
+    }
+
+    /// Renders the error if it occurred while parsing the request, otherwise
+    /// returns it unchanged.
+    pub(crate) fn apply(&self, err: Error) -> Error {
+        match ParseErrorDetails::from_error(&err) {
+            // the source is kept, so the error can still be downcast
+            Some(details) => Error {
+                as_response: AsResponse::Response((self.0)(&details)),
+                source: err.source,
+                extensions: err.extensions,
+                msg: Some(details.message),
+            },
+            None => err,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::io::{Error as IoError, ErrorKind};
@@ -1163,6 +1644,35 @@ mod tests {
         assert_eq!(err.into_response().status(), StatusCode::BAD_GATEWAY);
     }
 
+    #[test]
+    fn test_error_data() {
+        #[derive(Debug, Clone, Eq, PartialEq)]
+        struct Problem {
+            code: &'static str,
+            fields: Vec<&'static str>,
+        }
+
+        let mut err = Error::from_status(StatusCode::BAD_REQUEST).with_data(Problem {
+            code: "invalid",
+            fields: vec!["name"],
+        });
+        err.data_mut::<Problem>().unwrap().fields.push("age");
+        err.extensions_mut().insert(true);
+
+        // the data is kept if the error is not the specified type
+        let err = err.downcast::<IoError>().unwrap_err();
+        assert_eq!(err.extensions().get::<bool>(), Some(&true));
+
+        let resp = err.into_response();
+        assert_eq!(
+            resp.data::<Problem>(),
+            Some(&Problem {
+                code: "invalid",
+                fields: vec!["name", "age"],
+            })
+        );
+    }
+
     #[cfg(feature = "anyhow")]
     #[test]
     fn test_anyhow_error() {
@@ -1189,6 +1699,67 @@ mod tests {
         assert_eq!(err.into_response().status(), StatusCode::BAD_GATEWAY);
     }
 
+    #[tokio::test]
+    async fn test_error_format() {
+        let err: Error = ParseJsonError::ContentTypeRequired.into();
+        let err = ErrorFormat::new(|details| {
+            details
+                .code
+                .with_status(StatusCode::UNPROCESSABLE_ENTITY)
+                .into_response()
+        })
+        .apply(err);
+        assert!(err.is::<ParseJsonError>());
+        assert!(matches!(
+            err.downcast_ref::<ParseJsonError>(),
+            Some(ParseJsonError::ContentTypeRequired)
+        ));
+
+        let resp = err.into_response();
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            resp.into_body().into_string().await.unwrap(),
+            "content_type_required"
+        );
+    }
+
+    #[cfg(feature = "protobuf")]
+    #[test]
+    fn test_parse_error_details_protobuf() {
+        use prost::Message;
+
+        let err: Error = ParseProtobufError::ContentTypeRequired.into();
+        let details = ParseErrorDetails::from_error(&err).unwrap();
+        assert_eq!(details.status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(details.code, "content_type_required");
+
+        let err: Error =
+            ParseProtobufError::from(String::decode(&[0x0a, 0x05][..]).unwrap_err()).into();
+        let details = ParseErrorDetails::from_error(&err).unwrap();
+        assert_eq!(details.status, StatusCode::BAD_REQUEST);
+        assert_eq!(details.code, "invalid_body");
+    }
+
+    #[test]
+    fn test_parse_error_details_authorization() {
+        let err: Error = ParseAuthorizationError::Missing.into();
+        assert_eq!(
+            ParseErrorDetails::from_error(&err).unwrap(),
+            ParseErrorDetails {
+                status: StatusCode::UNAUTHORIZED,
+                code: "header_required",
+                message: "header `authorization` is required".to_string(),
+                field: Some("authorization".to_string()),
+            }
+        );
+
+        let err: Error = ParseAuthorizationError::Invalid.into();
+        let details = ParseErrorDetails::from_error(&err).unwrap();
+        assert_eq!(details.status, StatusCode::BAD_REQUEST);
+        assert_eq!(details.code, "invalid_header");
+        assert_eq!(details.field, None);
+    }
+
     #[tokio::test]
     async fn test_custom_as_response() {
         #[derive(Debug, thiserror::Error)]
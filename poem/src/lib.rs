@@ -234,6 +234,7 @@
 //! |compression  | Support decompress request body and compress response body |
 //! |cookie            | Support for Cookie             |
 //! |csrf | Support for Cross-Site Request Forgery (CSRF) protection |
+//! |csp | Support for Content-Security-Policy nonces |
 //! |multipart         | Support for Multipart          |
 //! |native-tls        | Support for HTTP server over TLS with [`native-tls`](https://crates.io/crates/native-tls)  |
 //! |openssl-tls        | Support for HTTP server over TLS with [`openssl-tls`](https://crates.io/crates/openssl)  |
@@ -256,6 +257,9 @@
 //! | embed  | Integrate with [`rust-embed`](https://crates.io/crates/rust-embed) crate. |
 //! | xml | Integrate with [`quick-xml`](https://crates.io/crates/quick-xml) crate. |
 //! | yaml | Integrate with [`serde-yaml`](https://crates.io/crates/serde-yaml) crate.                   |
+//! | webhook | Support for verifying webhook signatures |
+//! | digest | Support for computing the digests of response bodies |
+//! | protobuf | Integrate with [`prost`](https://crates.io/crates/prost) crate. |
 
 #![doc(html_favicon_url = "https://raw.githubusercontent.com/poem-web/poem/master/favicon.ico")]
 #![doc(html_logo_url = "https://raw.githubusercontent.com/poem-web/poem/master/logo.png")]
@@ -303,7 +307,7 @@ pub use request::{OnUpgrade, Request, RequestBuilder, RequestParts, Upgraded};
 pub use response::{Response, ResponseBuilder, ResponseParts};
 pub use route::{
     connect, delete, get, head, options, patch, post, put, trace, PathPattern, Route, RouteDomain,
-    RouteMethod, RouteScheme,
+    RouteMethod, RouteScheme, TrailingSlashPolicy,
 };
 #[cfg(feature = "server")]
 pub use server::Server;
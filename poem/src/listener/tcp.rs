@@ -1,6 +1,7 @@
-use std::io::Result;
+use std::{io::Result, net::SocketAddr};
 
 use http::uri::Scheme;
+use socket2::{Domain, Protocol, Socket, Type};
 use tokio::{
     io::Result as IoResult,
     net::{TcpListener as TokioTcpListener, TcpStream, ToSocketAddrs},
@@ -14,12 +15,91 @@ use crate::{
 /// A TCP listener.
 pub struct TcpListener<T> {
     addr: T,
+    reuse_address: Option<bool>,
+    reuse_port: Option<bool>,
+    nodelay: bool,
 }
 
 impl<T> TcpListener<T> {
     /// Binds to the provided address, and returns a [`TcpListener<T>`].
     pub fn bind(addr: T) -> Self {
-        Self { addr }
+        Self {
+            addr,
+            reuse_address: None,
+            reuse_port: None,
+            nodelay: false,
+        }
+    }
+
+    /// Sets the value of the `SO_REUSEADDR` option on the listening socket.
+    ///
+    /// If not specified, it is enabled on Unix platforms, like
+    /// [`tokio::net::TcpListener::bind`] does, so that the server can be
+    /// restarted while the previous connections are in `TIME_WAIT`.
+    #[must_use]
+    pub fn reuse_address(self, reuse: bool) -> Self {
+        Self {
+            reuse_address: Some(reuse),
+            ..self
+        }
+    }
+
+    /// Sets the value of the `SO_REUSEPORT` option on the listening socket.
+    ///
+    /// This allows multiple processes to bind to the same port. On Linux the
+    /// kernel load-balances incoming connections between them, while on BSD
+    /// and macOS only the most recently bound socket receives connections.
+    ///
+    /// _This option is only available on Unix platforms._
+    #[cfg(all(unix, not(any(target_os = "solaris", target_os = "illumos"))))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(all(unix, not(any(target_os = "solaris", target_os = "illumos")))))
+    )]
+    #[must_use]
+    pub fn reuse_port(self, reuse: bool) -> Self {
+        Self {
+            reuse_port: Some(reuse),
+            ..self
+        }
+    }
+
+    /// Sets the value of the `TCP_NODELAY` option on every accepted
+    /// connection.
+    ///
+    /// Default is `false`.
+    #[must_use]
+    pub fn nodelay(self, nodelay: bool) -> Self {
+        Self { nodelay, ..self }
+    }
+}
+
+struct SocketOptions {
+    reuse_address: Option<bool>,
+    reuse_port: Option<bool>,
+}
+
+impl SocketOptions {
+    fn is_specified(&self) -> bool {
+        self.reuse_address.is_some() || self.reuse_port.is_some()
+    }
+
+    fn bind(&self, addr: SocketAddr) -> IoResult<TokioTcpListener> {
+        let socket = Socket::new(Domain::for_address(addr), Type::STREAM, Some(Protocol::TCP))?;
+        // tokio enables `SO_REUSEADDR` on Unix platforms
+        match self.reuse_address {
+            Some(reuse) => socket.set_reuse_address(reuse)?,
+            None if cfg!(unix) => socket.set_reuse_address(true)?,
+            None => {}
+        }
+        #[cfg(all(unix, not(any(target_os = "solaris", target_os = "illumos"))))]
+        if let Some(reuse) = self.reuse_port {
+            socket.set_reuse_port(reuse)?;
+        }
+        socket.set_nonblocking(true)?;
+        socket.bind(&addr.into())?;
+        socket.listen(1024)?;
+        TokioTcpListener::from_std(socket.into())
     }
 }
 
@@ -27,11 +107,50 @@ impl<T: ToSocketAddrs + Send> Listener for TcpListener<T> {
     type Acceptor = TcpAcceptor;
 
     async fn into_acceptor(self) -> IoResult<Self::Acceptor> {
-        let listener = TokioTcpListener::bind(self.addr).await?;
+        let TcpListener {
+            addr,
+            reuse_address,
+            reuse_port,
+            nodelay,
+        } = self;
+        let options = SocketOptions {
+            reuse_address,
+            reuse_port,
+        };
+
+        let listener = if options.is_specified() {
+            let mut last_err = None;
+            let mut listener = None;
+
+            for addr in tokio::net::lookup_host(addr).await? {
+                match options.bind(addr) {
+                    Ok(l) => {
+                        listener = Some(l);
+                        break;
+                    }
+                    Err(err) => last_err = Some(err),
+                }
+            }
+
+            match listener {
+                Some(listener) => listener,
+                None => {
+                    return Err(last_err.unwrap_or_else(|| {
+                        std::io::Error::new(
+                            std::io::ErrorKind::InvalidInput,
+                            "could not resolve to any address",
+                        )
+                    }))
+                }
+            }
+        } else {
+            TokioTcpListener::bind(addr).await?
+        };
         let local_addr = listener.local_addr().map(|addr| LocalAddr(addr.into()))?;
         Ok(TcpAcceptor {
             local_addr,
             listener,
+            nodelay,
         })
     }
 }
@@ -40,6 +159,7 @@ impl<T: ToSocketAddrs + Send> Listener for TcpListener<T> {
 pub struct TcpAcceptor {
     local_addr: LocalAddr,
     listener: TokioTcpListener,
+    nodelay: bool,
 }
 
 impl TcpAcceptor {
@@ -49,6 +169,7 @@ impl TcpAcceptor {
         Ok(Self {
             local_addr,
             listener: TokioTcpListener::from_std(listener)?,
+            nodelay: false,
         })
     }
 
@@ -58,8 +179,16 @@ impl TcpAcceptor {
         Ok(Self {
             local_addr,
             listener,
+            nodelay: false,
         })
     }
+
+    /// Sets the value of the `TCP_NODELAY` option on every accepted
+    /// connection.
+    #[must_use]
+    pub fn nodelay(self, nodelay: bool) -> Self {
+        Self { nodelay, ..self }
+    }
 }
 
 impl Acceptor for TcpAcceptor {
@@ -72,14 +201,16 @@ impl Acceptor for TcpAcceptor {
 
     #[inline]
     async fn accept(&mut self) -> Result<(Self::Io, LocalAddr, RemoteAddr, Scheme)> {
-        self.listener.accept().await.map(|(io, addr)| {
-            (
-                io,
-                self.local_addr.clone(),
-                RemoteAddr(addr.into()),
-                Scheme::HTTP,
-            )
-        })
+        let (io, addr) = self.listener.accept().await?;
+        if self.nodelay {
+            io.set_nodelay(true)?;
+        }
+        Ok((
+            io,
+            self.local_addr.clone(),
+            RemoteAddr(addr.into()),
+            Scheme::HTTP,
+        ))
     }
 }
 
@@ -105,4 +236,67 @@ mod tests {
         let (mut stream, _, _, _) = acceptor.accept().await.unwrap();
         assert_eq!(stream.read_i32().await.unwrap(), 10);
     }
+
+    #[tokio::test]
+    async fn tcp_listener_with_socket_options() {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .reuse_address(true)
+            .nodelay(true);
+        let mut acceptor = listener.into_acceptor().await.unwrap();
+        let local_addr = acceptor.local_addr().remove(0);
+
+        tokio::spawn(async move {
+            let mut stream = TcpStream::connect(*local_addr.as_socket_addr().unwrap())
+                .await
+                .unwrap();
+            stream.write_i32(10).await.unwrap();
+        });
+
+        let (mut stream, _, _, _) = acceptor.accept().await.unwrap();
+        assert!(stream.nodelay().unwrap());
+        assert_eq!(stream.read_i32().await.unwrap(), 10);
+    }
+
+    #[cfg(all(unix, not(any(target_os = "solaris", target_os = "illumos"))))]
+    #[tokio::test]
+    async fn tcp_listener_reuse_port() {
+        let acceptor = TcpListener::bind("127.0.0.1:0")
+            .reuse_port(true)
+            .into_acceptor()
+            .await
+            .unwrap();
+        let addr = *acceptor.local_addr()[0].as_socket_addr().unwrap();
+
+        // a second listener can bind to the same port
+        TcpListener::bind(addr)
+            .reuse_port(true)
+            .into_acceptor()
+            .await
+            .unwrap();
+    }
+
+    #[cfg(all(unix, not(any(target_os = "solaris", target_os = "illumos"))))]
+    #[tokio::test]
+    async fn tcp_listener_rebind() {
+        let mut acceptor = TcpListener::bind("127.0.0.1:0")
+            .reuse_port(false)
+            .into_acceptor()
+            .await
+            .unwrap();
+        let addr = *acceptor.local_addr()[0].as_socket_addr().unwrap();
+
+        // the server closes the connection first, so it is left in `TIME_WAIT`
+        let mut client = TcpStream::connect(addr).await.unwrap();
+        let (stream, _, _, _) = acceptor.accept().await.unwrap();
+        drop(stream);
+        assert_eq!(client.read(&mut [0; 1]).await.unwrap(), 0);
+        drop(client);
+        drop(acceptor);
+
+        TcpListener::bind(addr)
+            .reuse_port(false)
+            .into_acceptor()
+            .await
+            .unwrap();
+    }
 }
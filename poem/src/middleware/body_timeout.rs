@@ -0,0 +1,206 @@
+use std::{
+    future::Future,
+    io::{Error as IoError, ErrorKind},
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use bytes::Bytes;
+use hyper::body::{Frame, SizeHint};
+use tokio::time::{Instant, Sleep};
+
+use crate::{body::BoxBody, Body, Endpoint, Middleware, Request, Result};
+
+/// Middleware for limiting how long the client has to send the request body,
+/// independent of how long the handler runs.
+///
+/// By default, the timeout is reset whenever a chunk of the body is
+/// received, so that only the stalled uploads are aborted. With
+/// [`BodyTimeout::absolute`], the whole body must be received within the
+/// timeout. The timer starts when the body is first read, so that the time
+/// the handler spends before reading the body is not counted.
+///
+/// If the timeout elapses, reading the body fails with
+/// [`ReadBodyError::Io`](crate::error::ReadBodyError::Io) of
+/// [`ErrorKind::TimedOut`], which is `408 Request Timeout`.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use poem::{handler, middleware::BodyTimeout, post, EndpointExt, Route};
+///
+/// #[handler]
+/// async fn upload(data: Vec<u8>) -> String {
+///     data.len().to_string()
+/// }
+///
+/// let app = Route::new().at(
+///     "/upload",
+///     post(upload).with(BodyTimeout::new(Duration::from_secs(10))),
+/// );
+/// ```
+pub struct BodyTimeout {
+    timeout: Duration,
+    absolute: bool,
+}
+
+impl BodyTimeout {
+    /// Create `BodyTimeout` middleware with the specified timeout between
+    /// the chunks of the body.
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            absolute: false,
+        }
+    }
+
+    /// Sets whether the timeout applies to the whole body instead of each
+    /// chunk.
+    #[must_use]
+    pub fn absolute(self, absolute: bool) -> Self {
+        Self { absolute, ..self }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for BodyTimeout {
+    type Output = BodyTimeoutEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        BodyTimeoutEndpoint {
+            inner: ep,
+            timeout: self.timeout,
+            absolute: self.absolute,
+        }
+    }
+}
+
+/// Endpoint for BodyTimeout middleware.
+pub struct BodyTimeoutEndpoint<E> {
+    inner: E,
+    timeout: Duration,
+    absolute: bool,
+}
+
+impl<E: Endpoint> Endpoint for BodyTimeoutEndpoint<E> {
+    type Output = E::Output;
+
+    async fn call(&self, mut req: Request) -> Result<Self::Output> {
+        let body = req.take_body();
+        req.set_body(Body(BoxBody::new(TimeoutBody {
+            inner: body.0,
+            timeout: self.timeout,
+            absolute: self.absolute,
+            sleep: None,
+        })));
+        self.inner.call(req).await
+    }
+}
+
+struct TimeoutBody {
+    inner: BoxBody,
+    timeout: Duration,
+    absolute: bool,
+    sleep: Option<Pin<Box<Sleep>>>,
+}
+
+impl hyper::body::Body for TimeoutBody {
+    type Data = Bytes;
+    type Error = IoError;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        if let Poll::Ready(res) = Pin::new(&mut self.inner).poll_frame(cx) {
+            if !self.absolute {
+                self.sleep = None;
+            }
+            return Poll::Ready(res);
+        }
+
+        let timeout = self.timeout;
+        let sleep = self
+            .sleep
+            .get_or_insert_with(|| Box::pin(tokio::time::sleep_until(Instant::now() + timeout)));
+        if sleep.as_mut().poll(cx).is_ready() {
+            return Poll::Ready(Some(Err(IoError::new(
+                ErrorKind::TimedOut,
+                "request body timeout",
+            ))));
+        }
+        Poll::Pending
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::{stream, StreamExt};
+    use http::StatusCode;
+
+    use super::*;
+    use crate::{endpoint::make, EndpointExt};
+
+    fn stalled_body() -> Body {
+        Body::from_bytes_stream(
+            stream::iter([Ok::<_, IoError>(Bytes::from_static(b"abc"))]).chain(stream::pending()),
+        )
+    }
+
+    #[tokio::test]
+    async fn body_timeout() {
+        let ep = make(|mut req| async move {
+            // a slow handler is not affected by the timeout
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            req.take_body().into_string().await
+        })
+        .with(BodyTimeout::new(Duration::from_millis(50)));
+
+        let resp = ep
+            .get_response(Request::builder().body("abc"))
+            .await
+            .into_body()
+            .into_string()
+            .await
+            .unwrap();
+        assert_eq!(resp, "abc");
+
+        let resp = ep
+            .get_response(Request::builder().body(stalled_body()))
+            .await;
+        assert_eq!(resp.status(), StatusCode::REQUEST_TIMEOUT);
+    }
+
+    #[tokio::test]
+    async fn absolute() {
+        let slow_body = || {
+            Body::from_bytes_stream(stream::iter(0..5).then(|_| async {
+                tokio::time::sleep(Duration::from_millis(30)).await;
+                Ok::<_, IoError>(Bytes::from_static(b"a"))
+            }))
+        };
+        let ep = || make(|mut req| async move { req.take_body().into_string().await });
+
+        let resp = ep()
+            .with(BodyTimeout::new(Duration::from_millis(60)))
+            .get_response(Request::builder().body(slow_body()))
+            .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let resp = ep()
+            .with(BodyTimeout::new(Duration::from_millis(60)).absolute(true))
+            .get_response(Request::builder().body(slow_body()))
+            .await;
+        assert_eq!(resp.status(), StatusCode::REQUEST_TIMEOUT);
+    }
+}
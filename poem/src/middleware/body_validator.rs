@@ -0,0 +1,387 @@
+use std::{
+    io::{Error as IoError, ErrorKind},
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use hyper::body::{Frame, SizeHint};
+use parking_lot::Mutex;
+
+use crate::{
+    body::BoxBody, error::ReadBodyError, Body, Endpoint, Error, Middleware, Request, Result,
+};
+
+/// Represents a validator of the request body, which inspects the chunks as
+/// they are received.
+///
+/// It is implemented for the functions of `FnMut(&Bytes) -> Result<()>`, a
+/// struct can be used to also validate the body when it is finished.
+pub trait ChunkValidator: Send + 'static {
+    /// Validates a chunk of the request body.
+    fn validate(&mut self, chunk: &Bytes) -> Result<()>;
+
+    /// Validates the request body after all the chunks have been received.
+    ///
+    /// Default does nothing.
+    fn finish(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<F> ChunkValidator for F
+where
+    F: FnMut(&Bytes) -> Result<()> + Send + 'static,
+{
+    fn validate(&mut self, chunk: &Bytes) -> Result<()> {
+        (self)(chunk)
+    }
+}
+
+/// Middleware for validating the request body as it streams, such as
+/// scanning for viruses, without buffering it.
+///
+/// A validator is created by the specified function for each request, so it
+/// can keep the state across the chunks. The body is passed unchanged to the
+/// endpoint, and the chunks are validated when the endpoint reads them.
+///
+/// If the validator fails, reading the body fails, and the error returned by
+/// the validator is returned instead of the output of the endpoint, so its
+/// status code is the one of the response. If the endpoint returns an error
+/// other than the error of reading the body, that error is returned instead.
+///
+/// Only the chunks that the endpoint reads are validated. If the endpoint
+/// drops the body before reading all of it, such as when it rejects the
+/// request early, the rest of the body is never read, so it is not accepted
+/// nor validated. Note that:
+///
+/// - The endpoint runs before the validation is finished, so the side effects
+///   it commits before the body fails the validation, such as writing the
+///   chunks that have been read to a database, are not rolled back.
+/// - A body that is kept by the output of the endpoint, such as a response
+///   that streams the request body back, is not validated until it is read.
+///
+/// # Example
+///
+/// ```
+/// use bytes::Bytes;
+/// use poem::{
+///     handler, http::StatusCode, middleware::BodyValidator, post, test::TestClient, EndpointExt,
+///     Error, Result, Route,
+/// };
+///
+/// #[handler]
+/// async fn upload(data: Vec<u8>) -> String {
+///     data.len().to_string()
+/// }
+///
+/// let validator = BodyValidator::new(|| {
+///     |chunk: &Bytes| -> Result<()> {
+///         if chunk.windows(5).any(|w| w == b"virus") {
+///             return Err(Error::from_status(StatusCode::UNPROCESSABLE_ENTITY));
+///         }
+///         Ok(())
+///     }
+/// });
+/// let app = Route::new().at("/upload", post(upload).with(validator));
+/// let cli = TestClient::new(app);
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// cli.post("/upload")
+///     .body("hello")
+///     .send()
+///     .await
+///     .assert_text("5")
+///     .await;
+/// cli.post("/upload")
+///     .body("a virus")
+///     .send()
+///     .await
+///     .assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+/// # });
+/// ```
+pub struct BodyValidator<F> {
+    f: Arc<F>,
+}
+
+impl<F, V> BodyValidator<F>
+where
+    F: Fn() -> V + Send + Sync + 'static,
+    V: ChunkValidator,
+{
+    /// Create `BodyValidator` middleware with a function that creates the
+    /// validator of each request.
+    pub fn new(f: F) -> Self {
+        Self { f: Arc::new(f) }
+    }
+}
+
+impl<E, F, V> Middleware<E> for BodyValidator<F>
+where
+    E: Endpoint,
+    F: Fn() -> V + Send + Sync + 'static,
+    V: ChunkValidator,
+{
+    type Output = BodyValidatorEndpoint<E, F>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        BodyValidatorEndpoint {
+            inner: ep,
+            f: self.f.clone(),
+        }
+    }
+}
+
+/// Endpoint for BodyValidator middleware.
+pub struct BodyValidatorEndpoint<E, F> {
+    inner: E,
+    f: Arc<F>,
+}
+
+impl<E, F, V> Endpoint for BodyValidatorEndpoint<E, F>
+where
+    E: Endpoint,
+    F: Fn() -> V + Send + Sync + 'static,
+    V: ChunkValidator,
+{
+    type Output = E::Output;
+
+    async fn call(&self, mut req: Request) -> Result<Self::Output> {
+        let state = Arc::new(Mutex::new(ValidatorState {
+            validator: (self.f)(),
+            failure: None,
+        }));
+        let body = req.take_body();
+        req.set_body(Body(BoxBody::new(ValidatingBody {
+            inner: Some(body.0),
+            state: state.clone(),
+        })));
+
+        let res = self.inner.call(req).await;
+
+        let failure = state.lock().failure.take();
+        match (res, failure) {
+            // the endpoint may ignore the error of reading the body
+            (Ok(_), Some(err)) => Err(err),
+            (Err(err), Some(failure)) if is_validation_error(&err) => Err(failure),
+            (res, _) => res,
+        }
+    }
+}
+
+/// The error of reading the body that failed the validation.
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+struct ValidationError(String);
+
+fn is_validation_error(err: &Error) -> bool {
+    let io_err = match err.downcast_ref::<ReadBodyError>() {
+        Some(ReadBodyError::Io(err)) => Some(err),
+        _ => err.downcast_ref::<IoError>(),
+    };
+    io_err
+        .and_then(IoError::get_ref)
+        .is_some_and(|err| err.is::<ValidationError>())
+}
+
+struct ValidatorState<V> {
+    validator: V,
+    failure: Option<Error>,
+}
+
+/// The inner body is `None` when it is finished or failed.
+struct ValidatingBody<V> {
+    inner: Option<BoxBody>,
+    state: Arc<Mutex<ValidatorState<V>>>,
+}
+
+impl<V: ChunkValidator> ValidatingBody<V> {
+    fn validate(&self, chunk: Option<&Bytes>) -> Result<(), IoError> {
+        let mut state = self.state.lock();
+        let res = match chunk {
+            Some(chunk) => state.validator.validate(chunk),
+            None => state.validator.finish(),
+        };
+        res.map_err(|err| {
+            let io_err = IoError::new(ErrorKind::InvalidData, ValidationError(err.to_string()));
+            state.failure = Some(err);
+            io_err
+        })
+    }
+}
+
+impl<V: ChunkValidator> hyper::body::Body for ValidatingBody<V> {
+    type Data = Bytes;
+    type Error = IoError;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let Some(inner) = &mut self.inner else {
+            return Poll::Ready(None);
+        };
+        let res = match futures_util::ready!(Pin::new(inner).poll_frame(cx)) {
+            Some(Ok(frame)) => match frame.data_ref() {
+                Some(chunk) => self.validate(Some(chunk)).map(|_| Some(frame)),
+                None => Ok(Some(frame)),
+            },
+            None => self.validate(None).map(|_| None),
+            Some(Err(err)) => Err(err),
+        };
+        match res {
+            Ok(Some(frame)) => Poll::Ready(Some(Ok(frame))),
+            Ok(None) => {
+                self.inner = None;
+                Poll::Ready(None)
+            }
+            Err(err) => {
+                self.inner = None;
+                Poll::Ready(Some(Err(err)))
+            }
+        }
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_none()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        match &self.inner {
+            Some(inner) => inner.size_hint(),
+            None => SizeHint::with_exact(0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::stream;
+    use http::StatusCode;
+    use http_body_util::BodyExt;
+
+    use super::*;
+    use crate::{
+        endpoint::{make, make_sync},
+        EndpointExt,
+    };
+
+    struct MaxSize {
+        remaining: usize,
+        ends_with_newline: bool,
+    }
+
+    impl ChunkValidator for MaxSize {
+        fn validate(&mut self, chunk: &Bytes) -> Result<()> {
+            self.remaining = self
+                .remaining
+                .checked_sub(chunk.len())
+                .ok_or_else(|| Error::from_status(StatusCode::PAYLOAD_TOO_LARGE))?;
+            self.ends_with_newline = chunk.ends_with(b"\n");
+            Ok(())
+        }
+
+        fn finish(&mut self) -> Result<()> {
+            if !self.ends_with_newline {
+                return Err(Error::from_status(StatusCode::UNPROCESSABLE_ENTITY));
+            }
+            Ok(())
+        }
+    }
+
+    fn chunks(chunks: &'static [&'static str]) -> Body {
+        Body::from_bytes_stream(stream::iter(
+            chunks
+                .iter()
+                .map(|chunk| Ok::<_, IoError>(Bytes::from_static(chunk.as_bytes()))),
+        ))
+    }
+
+    #[tokio::test]
+    async fn body_validator() {
+        let ep = make(|mut req| async move { req.take_body().into_string().await }).with(
+            BodyValidator::new(|| MaxSize {
+                remaining: 6,
+                ends_with_newline: false,
+            }),
+        );
+
+        let resp = ep
+            .call(Request::builder().body(chunks(&["abc", "de\n"])))
+            .await
+            .unwrap();
+        assert_eq!(resp, "abcde\n");
+
+        let resp = ep
+            .get_response(Request::builder().body(chunks(&["abc", "def", "\n"])))
+            .await;
+        assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let resp = ep
+            .get_response(Request::builder().body(chunks(&["abc", "def"])))
+            .await;
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[tokio::test]
+    async fn unread_body() {
+        let ep = make(|mut req| async move {
+            // read the first chunk only
+            let _ = req.take_body().0.frame().await;
+            "ok"
+        })
+        .with(BodyValidator::new(|| MaxSize {
+            remaining: 6,
+            ends_with_newline: false,
+        }));
+
+        // the chunks that are not read are not validated
+        for body in [&["abc", "de\n"][..], &["abc", "def", "\n"]] {
+            let resp = ep
+                .call(Request::builder().body(chunks(body)))
+                .await
+                .unwrap();
+            assert_eq!(resp, "ok");
+        }
+
+        // the body is not read at all
+        let resp = make_sync(|_| "ok")
+            .with(BodyValidator::new(|| MaxSize {
+                remaining: 6,
+                ends_with_newline: false,
+            }))
+            .get_response(Request::builder().body(chunks(&["abc", "def"])))
+            .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn ignored_read_error() {
+        let ep = make(|mut req| async move {
+            let _ = req.take_body().into_bytes().await;
+            "ok"
+        })
+        .with(BodyValidator::new(|| {
+            |_: &Bytes| Err(Error::from_status(StatusCode::FORBIDDEN))
+        }));
+
+        let resp = ep.get_response(Request::builder().body("abc")).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn endpoint_error() {
+        let ep = make(|mut req| async move {
+            let _ = req.take_body().into_bytes().await;
+            Err::<(), _>(Error::from_status(StatusCode::BAD_REQUEST))
+        })
+        .with(BodyValidator::new(|| {
+            |_: &Bytes| Err(Error::from_status(StatusCode::FORBIDDEN))
+        }));
+
+        // the error of the endpoint is not replaced
+        let resp = ep.get_response(Request::builder().body("abc")).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+}
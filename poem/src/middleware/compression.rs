@@ -73,6 +73,10 @@ fn parse_accept_encoding(
 /// It selects the decompression algorithm according to the request
 /// `Content-Encoding` header, and selects the compression algorithm according
 /// to the request `Accept-Encoding` header.
+///
+/// The `text/event-stream` responses, such as [`SSE`](crate::web::sse::SSE),
+/// are compressed event by event, so that each event is still delivered to
+/// the client immediately.
 #[cfg_attr(docsrs, doc(cfg(feature = "compression")))]
 #[derive(Default)]
 pub struct Compression {
@@ -0,0 +1,283 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use http::{header, HeaderName, HeaderValue, Method, StatusCode};
+use hyper::body::Body as _;
+use ring::digest;
+
+use crate::{
+    error::InternalServerError, web::DigestBody, Endpoint, IntoResponse, Middleware, Request,
+    Response, Result,
+};
+
+const CONTENT_DIGEST: HeaderName = HeaderName::from_static("content-digest");
+
+/// The hash algorithm of the `Content-Digest` header, see
+/// [RFC9530](https://datatracker.ietf.org/doc/html/rfc9530#section-5).
+#[cfg_attr(docsrs, doc(cfg(feature = "digest")))]
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum DigestAlgorithm {
+    /// `sha-256`
+    #[default]
+    Sha256,
+    /// `sha-512`
+    Sha512,
+}
+
+impl DigestAlgorithm {
+    pub(crate) fn ring_algorithm(self) -> &'static digest::Algorithm {
+        match self {
+            DigestAlgorithm::Sha256 => &digest::SHA256,
+            DigestAlgorithm::Sha512 => &digest::SHA512,
+        }
+    }
+
+    /// Returns the value of the `Content-Digest` header for the specified
+    /// digest.
+    pub(crate) fn content_digest(self, digest: &[u8]) -> HeaderValue {
+        let name = match self {
+            DigestAlgorithm::Sha256 => "sha-256",
+            DigestAlgorithm::Sha512 => "sha-512",
+        };
+        HeaderValue::try_from(format!("{name}=:{}:", STANDARD.encode(digest)))
+            .expect("valid header value")
+    }
+}
+
+/// Middleware for adding the digest of the response body in the
+/// `Content-Digest` header, as defined in
+/// [RFC9530](https://datatracker.ietf.org/doc/html/rfc9530), so that the
+/// clients can verify the integrity of the body.
+///
+/// If the size of the body is known and does not exceed
+/// [`ContentDigest::max_buffer_size`], the body is buffered to compute the
+/// digest. Otherwise, the digest is computed while the body is being sent,
+/// and sent in the `Content-Digest` trailer, which is only received by the
+/// clients that support trailers.
+///
+/// The responses that already have a digest, the responses to `HEAD`
+/// requests and the responses without a body are not changed.
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     handler,
+///     middleware::{ContentDigest, DigestAlgorithm},
+///     test::TestClient,
+///     EndpointExt,
+/// };
+///
+/// #[handler]
+/// fn index() -> &'static str {
+///     "hello"
+/// }
+///
+/// let cli = TestClient::new(index.with(ContentDigest::new().algorithm(DigestAlgorithm::Sha256)));
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = cli.get("/").send().await;
+/// resp.assert_header(
+///     "content-digest",
+///     "sha-256=:LPJNul+wow4m6DsqxbninhsWHlwfp0JecwQzYpOLmCQ=:",
+/// );
+/// resp.assert_text("hello").await;
+/// # });
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "digest")))]
+pub struct ContentDigest {
+    algorithm: DigestAlgorithm,
+    max_buffer_size: u64,
+}
+
+impl Default for ContentDigest {
+    fn default() -> Self {
+        Self {
+            algorithm: DigestAlgorithm::Sha256,
+            max_buffer_size: 1024 * 1024,
+        }
+    }
+}
+
+impl ContentDigest {
+    /// Create `ContentDigest` middleware with the `sha-256` algorithm.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the hash algorithm of the digest.
+    ///
+    /// Default is [`DigestAlgorithm::Sha256`].
+    #[must_use]
+    pub fn algorithm(self, algorithm: DigestAlgorithm) -> Self {
+        Self { algorithm, ..self }
+    }
+
+    /// Sets the maximum size of the bodies that are buffered to send the
+    /// digest in the header, the larger bodies send it in the trailer.
+    ///
+    /// Default is `1MB`.
+    #[must_use]
+    pub fn max_buffer_size(self, size: u64) -> Self {
+        Self {
+            max_buffer_size: size,
+            ..self
+        }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for ContentDigest {
+    type Output = ContentDigestEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        ContentDigestEndpoint {
+            inner: ep,
+            algorithm: self.algorithm,
+            max_buffer_size: self.max_buffer_size,
+        }
+    }
+}
+
+/// Endpoint for ContentDigest middleware.
+#[cfg_attr(docsrs, doc(cfg(feature = "digest")))]
+pub struct ContentDigestEndpoint<E> {
+    inner: E,
+    algorithm: DigestAlgorithm,
+    max_buffer_size: u64,
+}
+
+fn has_digest(resp: &Response) -> bool {
+    resp.headers().contains_key(CONTENT_DIGEST)
+        || resp
+            .headers()
+            .get_all(header::TRAILER)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .any(|name| name.trim().eq_ignore_ascii_case(CONTENT_DIGEST.as_str()))
+}
+
+impl<E: Endpoint> Endpoint for ContentDigestEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        let is_head = req.method() == Method::HEAD;
+        let mut resp = self.inner.call(req).await?.into_response();
+        if is_head
+            || resp.status() == StatusCode::NO_CONTENT
+            || resp.status() == StatusCode::NOT_MODIFIED
+            || has_digest(&resp)
+        {
+            return Ok(resp);
+        }
+
+        let body = resp.take_body();
+        match body.0.size_hint().exact() {
+            Some(size) if size <= self.max_buffer_size => {
+                let data = body.into_bytes().await.map_err(InternalServerError)?;
+                let digest = digest::digest(self.algorithm.ring_algorithm(), &data);
+                resp.headers_mut().insert(
+                    CONTENT_DIGEST,
+                    self.algorithm.content_digest(digest.as_ref()),
+                );
+                resp.set_body(data);
+            }
+            _ => {
+                // the trailers cannot be sent with `Content-Length` over HTTP/1.1
+                resp.headers_mut().remove(header::CONTENT_LENGTH);
+                resp.headers_mut()
+                    .append(header::TRAILER, HeaderValue::from_static("content-digest"));
+                resp.set_body(DigestBody::wrap(body, self.algorithm, false));
+            }
+        }
+        Ok(resp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::stream;
+    use http_body_util::BodyExt;
+
+    use super::*;
+    use crate::{endpoint::make_sync, web::HashingBody, Body, EndpointExt};
+
+    #[tokio::test]
+    async fn buffered() {
+        let resp = make_sync(|_| "hello")
+            .with(ContentDigest::new().algorithm(DigestAlgorithm::Sha512))
+            .call(Request::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            resp.headers().get(CONTENT_DIGEST).unwrap(),
+            "sha-512=:m3HSJL1i83hdltRq0+o9czGb+8KJDKra4t/3JRlnPKcjI8PZm6XBHXx6zG4UuMXaDEZjR1wuXDre9G9zvN7AQw==:"
+        );
+        assert!(resp.headers().get(header::TRAILER).is_none());
+        assert_eq!(resp.into_body().into_string().await.unwrap(), "hello");
+    }
+
+    #[tokio::test]
+    async fn streaming() {
+        let resp = make_sync(|_| {
+            Body::from_bytes_stream(stream::iter([Ok::<_, std::io::Error>("hel"), Ok("lo")]))
+        })
+        .with(ContentDigest::new())
+        .call(Request::default())
+        .await
+        .unwrap();
+        assert!(resp.headers().get(CONTENT_DIGEST).is_none());
+        assert_eq!(
+            resp.headers().get(header::TRAILER).unwrap(),
+            "content-digest"
+        );
+
+        let collected = resp.into_body().0.collect().await.unwrap();
+        let trailers = collected.trailers().cloned().unwrap();
+        assert_eq!(collected.to_bytes(), "hello");
+        assert_eq!(
+            trailers.get(CONTENT_DIGEST).unwrap(),
+            "sha-256=:LPJNul+wow4m6DsqxbninhsWHlwfp0JecwQzYpOLmCQ=:"
+        );
+        assert!(trailers.get(header::ETAG).is_none());
+
+        // a large body is streamed even if its size is known
+        let resp = make_sync(|_| "hello")
+            .with(ContentDigest::new().max_buffer_size(4))
+            .call(Request::default())
+            .await
+            .unwrap();
+        assert!(resp.headers().get(CONTENT_DIGEST).is_none());
+        assert!(resp.headers().contains_key(header::TRAILER));
+    }
+
+    #[tokio::test]
+    async fn skip() {
+        let resp = make_sync(|_| {
+            Response::builder()
+                .header(CONTENT_DIGEST, "sha-256=:abc:")
+                .body("hello")
+        })
+        .with(ContentDigest::new())
+        .call(Request::default())
+        .await
+        .unwrap();
+        assert_eq!(resp.headers().get(CONTENT_DIGEST).unwrap(), "sha-256=:abc:");
+
+        let resp = make_sync(|_| HashingBody::new("hello"))
+            .with(ContentDigest::new())
+            .call(Request::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            resp.headers().get(header::TRAILER).unwrap(),
+            "etag, content-digest"
+        );
+        assert_eq!(resp.headers().get_all(header::TRAILER).iter().count(), 1);
+
+        let resp = make_sync(|_| "hello")
+            .with(ContentDigest::new())
+            .call(Request::builder().method(Method::HEAD).finish())
+            .await
+            .unwrap();
+        assert!(resp.headers().get(CONTENT_DIGEST).is_none());
+    }
+}
@@ -122,4 +122,32 @@ mod tests {
             .await
             .assert_status_is_ok();
     }
+
+    #[tokio::test]
+    async fn multiple_set_cookie() {
+        #[handler(internal)]
+        async fn index(cookie_jar: &CookieJar) -> impl IntoResponse {
+            cookie_jar.add(Cookie::new_with_str("a", "1"));
+            cookie_jar.add(Cookie::new_with_str("b", "2"));
+
+            let mut headers = http::HeaderMap::new();
+            headers.append(http::header::SET_COOKIE, "c=3".parse().unwrap());
+            headers.append(http::header::SET_COOKIE, "d=4".parse().unwrap());
+            (headers, "ok".with_header(http::header::SET_COOKIE, "e=5"))
+        }
+
+        let cli = TestClient::new(index.with(CookieJarManager::new()));
+        let resp = cli.get("/").send().await;
+        resp.assert_status_is_ok();
+
+        let resp: http::Response<_> = resp.0.into();
+        let mut values = resp
+            .headers()
+            .get_all(http::header::SET_COOKIE)
+            .iter()
+            .map(|value| value.to_str().unwrap())
+            .collect::<Vec<_>>();
+        values.sort_unstable();
+        assert_eq!(values, ["a=1", "b=2", "c=3", "d=4", "e=5"]);
+    }
 }
@@ -19,6 +19,9 @@ use crate::{
     IntoResponse, Result,
 };
 
+const ACCESS_CONTROL_REQUEST_PRIVATE_NETWORK: &str = "access-control-request-private-network";
+const ACCESS_CONTROL_ALLOW_PRIVATE_NETWORK: &str = "access-control-allow-private-network";
+
 /// Middleware for CORS
 ///
 /// # Errors
@@ -46,6 +49,7 @@ pub struct Cors {
     allow_methods: HashSet<Method>,
     expose_headers: HashSet<HeaderName>,
     max_age: i32,
+    allow_private_network: bool,
 }
 
 impl Cors {
@@ -203,6 +207,20 @@ impl Cors {
         self.max_age = max_age;
         self
     }
+
+    /// Set allow private network.
+    ///
+    /// If `true`, the preflight response contains the
+    /// `Access-Control-Allow-Private-Network: true` header when the request
+    /// contains `Access-Control-Request-Private-Network: true`, which allows
+    /// public websites to access the services in the private network.
+    ///
+    /// Reference: <https://wicg.github.io/private-network-access/>
+    #[must_use]
+    pub fn allow_private_network(mut self, allow_private_network: bool) -> Self {
+        self.allow_private_network = allow_private_network;
+        self
+    }
 }
 
 impl<E: Endpoint> Middleware<E> for Cors {
@@ -222,6 +240,7 @@ impl<E: Endpoint> Middleware<E> for Cors {
             allow_methods_header: self.allow_methods.clone().into_iter().collect(),
             expose_headers_header: self.expose_headers.clone().into_iter().collect(),
             max_age: self.max_age,
+            allow_private_network: self.allow_private_network,
         }
     }
 }
@@ -241,6 +260,7 @@ pub struct CorsEndpoint<E> {
     allow_methods_header: AccessControlAllowMethods,
     expose_headers_header: AccessControlExposeHeaders,
     max_age: i32,
+    allow_private_network: bool,
 }
 
 impl<E: Endpoint> CorsEndpoint<E> {
@@ -277,6 +297,7 @@ impl<E: Endpoint> CorsEndpoint<E> {
         &self,
         origin: &HeaderValue,
         request_headers: Option<&HeaderValue>,
+        request_private_network: bool,
     ) -> Response {
         let mut builder = Response::builder()
             .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin)
@@ -318,6 +339,10 @@ impl<E: Endpoint> CorsEndpoint<E> {
             builder = builder.header(header::ACCESS_CONTROL_ALLOW_CREDENTIALS, "true");
         }
 
+        if self.allow_private_network && request_private_network {
+            builder = builder.header(ACCESS_CONTROL_ALLOW_PRIVATE_NETWORK, "true");
+        }
+
         builder.body(())
     }
 
@@ -389,7 +414,17 @@ impl<E: Endpoint> Endpoint for CorsEndpoint<E> {
                 return Err(CorsError::HeadersNotAllowed.into());
             }
 
-            return Ok(self.build_preflight_response(&origin, request_headers));
+            let request_private_network = req
+                .headers()
+                .get(ACCESS_CONTROL_REQUEST_PRIVATE_NETWORK)
+                .map(|value| value.as_bytes().eq_ignore_ascii_case(b"true"))
+                .unwrap_or_default();
+
+            return Ok(self.build_preflight_response(
+                &origin,
+                request_headers,
+                request_private_network,
+            ));
         }
 
         let mut resp = self.inner.get_response(req).await;
@@ -472,6 +507,32 @@ mod tests {
         resp.assert_header(header::ACCESS_CONTROL_ALLOW_CREDENTIALS, "true");
     }
 
+    #[tokio::test]
+    async fn private_network() {
+        let ep = make_sync(|_| "hello").with(cors().allow_private_network(true));
+        let cli = TestClient::new(ep);
+
+        let resp = opt_request(&cli)
+            .header(ACCESS_CONTROL_REQUEST_PRIVATE_NETWORK, "true")
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+        resp.assert_header(ACCESS_CONTROL_ALLOW_PRIVATE_NETWORK, "true");
+
+        let resp = opt_request(&cli).send().await;
+        resp.assert_status_is_ok();
+        resp.assert_header_is_not_exist(ACCESS_CONTROL_ALLOW_PRIVATE_NETWORK);
+
+        let ep = make_sync(|_| "hello").with(cors());
+        let cli = TestClient::new(ep);
+        let resp = opt_request(&cli)
+            .header(ACCESS_CONTROL_REQUEST_PRIVATE_NETWORK, "true")
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+        resp.assert_header_is_not_exist(ACCESS_CONTROL_ALLOW_PRIVATE_NETWORK);
+    }
+
     #[tokio::test]
     async fn default_cors() {
         let ep = make_sync(|_| "hello").with(Cors::new());
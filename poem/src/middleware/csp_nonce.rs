@@ -0,0 +1,183 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use http::{header, HeaderName, HeaderValue};
+use rand::{thread_rng, Rng};
+
+use crate::{web::CspNonceToken, Endpoint, IntoResponse, Middleware, Request, Response, Result};
+
+const NONCE_PLACEHOLDER: &str = "{nonce}";
+
+/// Middleware for generating a nonce for each request and setting the
+/// `Content-Security-Policy` header with it, which allows the inline scripts
+/// with the nonce to be executed.
+///
+/// The nonce is 16 bytes from a cryptographically secure random number
+/// generator, encoded in base64, and can be obtained with the
+/// [`CspNonceToken`] extractor to be embedded in the rendered page. The
+/// header is not set if the response already has one.
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     get, handler,
+///     middleware::CspNonce,
+///     test::TestClient,
+///     web::{CspNonceToken, Html},
+///     EndpointExt, Route,
+/// };
+///
+/// #[handler]
+/// fn index(nonce: &CspNonceToken) -> Html<String> {
+///     Html(format!(
+///         r#"<script nonce="{}">alert("hello")</script>"#,
+///         nonce.0
+///     ))
+/// }
+///
+/// let app = Route::new().at("/", get(index)).with(CspNonce::new());
+/// let cli = TestClient::new(app);
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = cli.get("/").send().await;
+/// resp.assert_status_is_ok();
+/// let policy = resp.0.headers()["content-security-policy"]
+///     .to_str()
+///     .unwrap();
+/// assert!(policy.starts_with("script-src 'nonce-"));
+/// # });
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "csp")))]
+pub struct CspNonce {
+    policy: String,
+    report_only: bool,
+}
+
+impl Default for CspNonce {
+    fn default() -> Self {
+        Self {
+            policy: format!("script-src 'nonce-{NONCE_PLACEHOLDER}'"),
+            report_only: false,
+        }
+    }
+}
+
+impl CspNonce {
+    /// Create `CspNonce` middleware with the `script-src 'nonce-...'`
+    /// policy.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the directives of the policy, the `{nonce}` placeholders are
+    /// replaced with the nonce of each request.
+    ///
+    /// For example, `default-src 'self'; script-src 'nonce-{nonce}';
+    /// style-src 'nonce-{nonce}'`.
+    ///
+    /// # Panics
+    ///
+    /// Panic when the policy is not a valid header value.
+    #[must_use]
+    pub fn policy(self, policy: impl Into<String>) -> Self {
+        let policy = policy.into();
+        assert!(
+            HeaderValue::try_from(policy.replace(NONCE_PLACEHOLDER, "")).is_ok(),
+            "invalid Content-Security-Policy: {policy}"
+        );
+        Self { policy, ..self }
+    }
+
+    /// Sets the `Content-Security-Policy-Report-Only` header instead, so that
+    /// the violations are only reported.
+    #[must_use]
+    pub fn report_only(self, report_only: bool) -> Self {
+        Self {
+            report_only,
+            ..self
+        }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for CspNonce {
+    type Output = CspNonceEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        CspNonceEndpoint {
+            inner: ep,
+            policy: self.policy.clone(),
+            header_name: if self.report_only {
+                header::CONTENT_SECURITY_POLICY_REPORT_ONLY
+            } else {
+                header::CONTENT_SECURITY_POLICY
+            },
+        }
+    }
+}
+
+/// Endpoint for CspNonce middleware.
+#[cfg_attr(docsrs, doc(cfg(feature = "csp")))]
+pub struct CspNonceEndpoint<E> {
+    inner: E,
+    policy: String,
+    header_name: HeaderName,
+}
+
+impl<E: Endpoint> Endpoint for CspNonceEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, mut req: Request) -> Result<Self::Output> {
+        let nonce = STANDARD.encode(thread_rng().gen::<[u8; 16]>());
+        let policy = self.policy.replace(NONCE_PLACEHOLDER, &nonce);
+        req.extensions_mut().insert(CspNonceToken(nonce));
+
+        let mut resp = self.inner.call(req).await?.into_response();
+        if !resp.headers().contains_key(&self.header_name) {
+            resp.headers_mut().insert(
+                self.header_name.clone(),
+                HeaderValue::try_from(policy).expect("the policy has been validated"),
+            );
+        }
+        Ok(resp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{handler, test::TestClient, EndpointExt};
+
+    #[handler(internal)]
+    fn index(nonce: &CspNonceToken) -> String {
+        nonce.0.clone()
+    }
+
+    #[tokio::test]
+    async fn csp_nonce() {
+        let cli =
+            TestClient::new(index.with(
+                CspNonce::new().policy("script-src 'nonce-{nonce}'; style-src 'nonce-{nonce}'"),
+            ));
+
+        let mut nonces = Vec::new();
+        for _ in 0..2 {
+            let mut resp = cli.get("/").send().await;
+            resp.assert_status_is_ok();
+            let nonce = resp.0.take_body().into_string().await.unwrap();
+            assert_eq!(STANDARD.decode(&nonce).unwrap().len(), 16);
+            resp.assert_header(
+                header::CONTENT_SECURITY_POLICY,
+                format!("script-src 'nonce-{nonce}'; style-src 'nonce-{nonce}'"),
+            );
+            nonces.push(nonce);
+        }
+        assert_ne!(nonces[0], nonces[1]);
+    }
+
+    #[tokio::test]
+    async fn report_only() {
+        let cli = TestClient::new(index.with(CspNonce::new().report_only(true)));
+        let resp = cli.get("/").send().await;
+        resp.assert_header_is_not_exist(header::CONTENT_SECURITY_POLICY);
+        resp.assert_header_exist(header::CONTENT_SECURITY_POLICY_REPORT_ONLY);
+    }
+}
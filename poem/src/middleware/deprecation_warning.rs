@@ -0,0 +1,106 @@
+use http::{header, HeaderValue};
+
+use crate::{Endpoint, IntoResponse, Middleware, Request, Response, Result};
+
+/// Middleware for adding the `Warning` header with the `299` (Miscellaneous
+/// Persistent Warning) code, defined in
+/// [RFC7234](https://datatracker.ietf.org/doc/html/rfc7234#section-5.5), to
+/// the responses of deprecated endpoints.
+///
+/// The header is formatted as `299 - "<message>"`, the quotes and backslashes
+/// in the message are escaped and the control characters are replaced with
+/// spaces.
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     get, handler, middleware::DeprecationWarning, test::TestClient, EndpointExt, Route,
+/// };
+///
+/// #[handler]
+/// fn index() -> &'static str {
+///     "hello"
+/// }
+///
+/// let app = Route::new().at(
+///     "/v1/users",
+///     get(index).with(DeprecationWarning::new("use /v2/users instead")),
+/// );
+/// let cli = TestClient::new(app);
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = cli.get("/v1/users").send().await;
+/// resp.assert_header("Warning", r#"299 - "use /v2/users instead""#);
+/// # });
+/// ```
+pub struct DeprecationWarning {
+    value: HeaderValue,
+}
+
+impl DeprecationWarning {
+    /// Create `DeprecationWarning` middleware with the specified message.
+    pub fn new(message: impl AsRef<str>) -> Self {
+        let mut value = String::from("299 - \"");
+        for c in message.as_ref().chars() {
+            match c {
+                '"' | '\\' => {
+                    value.push('\\');
+                    value.push(c);
+                }
+                c if c.is_control() => value.push(' '),
+                c => value.push(c),
+            }
+        }
+        value.push('"');
+
+        Self {
+            value: HeaderValue::from_bytes(value.as_bytes())
+                .expect("the control characters have been removed"),
+        }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for DeprecationWarning {
+    type Output = DeprecationWarningEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        DeprecationWarningEndpoint {
+            inner: ep,
+            value: self.value.clone(),
+        }
+    }
+}
+
+/// Endpoint for DeprecationWarning middleware.
+pub struct DeprecationWarningEndpoint<E> {
+    inner: E,
+    value: HeaderValue,
+}
+
+impl<E: Endpoint> Endpoint for DeprecationWarningEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        let mut resp = self.inner.call(req).await?.into_response();
+        resp.headers_mut()
+            .append(header::WARNING, self.value.clone());
+        Ok(resp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{endpoint::make_sync, test::TestClient, EndpointExt};
+
+    #[tokio::test]
+    async fn deprecation_warning() {
+        let cli = TestClient::new(
+            make_sync(|_| "hello").with(DeprecationWarning::new("use \"v2\"\\\n now")),
+        );
+        let resp = cli.get("/").send().await;
+        resp.assert_status_is_ok();
+        resp.assert_header(header::WARNING, r#"299 - "use \"v2\"\\  now""#);
+    }
+}
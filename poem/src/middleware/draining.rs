@@ -0,0 +1,159 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use http::{header, StatusCode};
+
+use crate::{Endpoint, IntoResponse, Middleware, Request, Response, Result};
+
+/// Middleware for draining the traffic of specific routes at runtime, which
+/// rejects the requests with `SERVICE UNAVAILABLE` status code while it is
+/// enabled.
+///
+/// It can be cloned and shared, all clones control the same switch, so that
+/// one clone can be applied to the routes and another one can be kept to
+/// toggle them. Unlike [`Maintenance`](super::Maintenance), it only affects
+/// the endpoints that it is applied to.
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     get, handler, http::StatusCode, middleware::Draining, test::TestClient, EndpointExt, Route,
+/// };
+///
+/// #[handler]
+/// fn index() -> &'static str {
+///     "hello"
+/// }
+///
+/// let draining = Draining::new();
+/// let app = Route::new()
+///     .at("/v1/users", get(index).with(draining.clone()))
+///     .at("/v2/users", get(index));
+/// let cli = TestClient::new(app);
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// cli.get("/v1/users").send().await.assert_status_is_ok();
+///
+/// draining.enable();
+/// cli.get("/v1/users")
+///     .send()
+///     .await
+///     .assert_status(StatusCode::SERVICE_UNAVAILABLE);
+/// cli.get("/v2/users").send().await.assert_status_is_ok();
+/// # });
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Draining {
+    enabled: Arc<AtomicBool>,
+    retry_after: Option<Duration>,
+}
+
+impl Draining {
+    /// Create `Draining` middleware that is disabled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the value of the `Retry-After` header of the rejected requests.
+    #[must_use]
+    pub fn retry_after(self, retry_after: Duration) -> Self {
+        Self {
+            retry_after: Some(retry_after),
+            ..self
+        }
+    }
+
+    /// Starts draining the routes.
+    pub fn enable(&self) {
+        self.set(true);
+    }
+
+    /// Stops draining the routes.
+    pub fn disable(&self) {
+        self.set(false);
+    }
+
+    /// Sets whether the routes are being drained.
+    pub fn set(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Release);
+    }
+
+    /// Returns `true` if the routes are being drained.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Acquire)
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for Draining {
+    type Output = DrainingEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        DrainingEndpoint {
+            inner: ep,
+            draining: self.clone(),
+        }
+    }
+}
+
+/// Endpoint for the Draining middleware.
+pub struct DrainingEndpoint<E> {
+    inner: E,
+    draining: Draining,
+}
+
+impl<E: Endpoint> Endpoint for DrainingEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        if !self.draining.is_enabled() {
+            return self.inner.call(req).await.map(IntoResponse::into_response);
+        }
+
+        let mut resp = Response::builder().status(StatusCode::SERVICE_UNAVAILABLE);
+        if let Some(retry_after) = self.draining.retry_after {
+            resp = resp.header(header::RETRY_AFTER, retry_after.as_secs());
+        }
+        Ok(resp.finish())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{get, handler, test::TestClient, EndpointExt, Route};
+
+    #[handler(internal)]
+    fn index() -> &'static str {
+        "hello"
+    }
+
+    #[tokio::test]
+    async fn draining() {
+        let draining = Draining::new().retry_after(Duration::from_secs(30));
+        let cli = TestClient::new(
+            Route::new()
+                .at("/a", get(index).with(draining.clone()))
+                .at("/b", get(index).with(draining.clone()))
+                .at("/c", get(index)),
+        );
+
+        cli.get("/a").send().await.assert_status_is_ok();
+
+        draining.enable();
+        for path in ["/a", "/b"] {
+            let resp = cli.get(path).send().await;
+            resp.assert_status(StatusCode::SERVICE_UNAVAILABLE);
+            resp.assert_header(header::RETRY_AFTER, "30");
+        }
+        cli.get("/c").send().await.assert_status_is_ok();
+
+        draining.disable();
+        cli.get("/a").send().await.assert_status_is_ok();
+    }
+}
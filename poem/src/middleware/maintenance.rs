@@ -0,0 +1,244 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use bytes::Bytes;
+use http::{header, StatusCode};
+
+use crate::{Endpoint, IntoResponse, Middleware, Request, Response, Result};
+
+/// A switch that turns the maintenance mode of the [`Maintenance`] middleware
+/// on and off at runtime.
+///
+/// It can be cloned and shared, all clones control the same mode.
+#[derive(Debug, Clone, Default)]
+pub struct MaintenanceMode(Arc<AtomicBool>);
+
+impl MaintenanceMode {
+    /// Create a `MaintenanceMode` that is disabled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Turns on the maintenance mode.
+    pub fn enable(&self) {
+        self.set(true);
+    }
+
+    /// Turns off the maintenance mode.
+    pub fn disable(&self) {
+        self.set(false);
+    }
+
+    /// Sets whether the maintenance mode is enabled.
+    pub fn set(&self, enabled: bool) {
+        self.0.store(enabled, Ordering::Release);
+    }
+
+    /// Returns `true` if the maintenance mode is enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+}
+
+/// Middleware for rejecting the requests with `SERVICE UNAVAILABLE` status
+/// code while the [`MaintenanceMode`] is enabled.
+///
+/// The requests of the allowed paths, such as the health checks, are always
+/// passed to the inner endpoint. Toggling the mode takes effect immediately
+/// for new requests.
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     get, handler,
+///     http::StatusCode,
+///     middleware::{Maintenance, MaintenanceMode},
+///     test::TestClient,
+///     EndpointExt, Route,
+/// };
+///
+/// #[handler]
+/// fn index() -> &'static str {
+///     "hello"
+/// }
+///
+/// let mode = MaintenanceMode::new();
+/// let app = Route::new()
+///     .at("/", get(index))
+///     .at("/health", get(index))
+///     .with(Maintenance::new(mode.clone()).allow_path("/health"));
+/// let cli = TestClient::new(app);
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// cli.get("/").send().await.assert_status_is_ok();
+///
+/// mode.enable();
+/// cli.get("/")
+///     .send()
+///     .await
+///     .assert_status(StatusCode::SERVICE_UNAVAILABLE);
+/// cli.get("/health").send().await.assert_status_is_ok();
+/// # });
+/// ```
+pub struct Maintenance {
+    mode: MaintenanceMode,
+    allow_paths: Vec<String>,
+    allow_prefixes: Vec<String>,
+    retry_after: Option<Duration>,
+    content_type: Option<String>,
+    body: Bytes,
+}
+
+impl Maintenance {
+    /// Create `Maintenance` middleware controlled by the specified mode.
+    pub fn new(mode: MaintenanceMode) -> Self {
+        Self {
+            mode,
+            allow_paths: Vec::new(),
+            allow_prefixes: Vec::new(),
+            retry_after: None,
+            content_type: None,
+            body: Bytes::new(),
+        }
+    }
+
+    /// Allows the requests to the specified path during maintenance.
+    #[must_use]
+    pub fn allow_path(mut self, path: impl Into<String>) -> Self {
+        self.allow_paths.push(path.into());
+        self
+    }
+
+    /// Allows the requests to the paths that start with the specified prefix
+    /// during maintenance.
+    #[must_use]
+    pub fn allow_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.allow_prefixes.push(prefix.into());
+        self
+    }
+
+    /// Sets the value of the `Retry-After` header of the rejected requests.
+    #[must_use]
+    pub fn retry_after(self, retry_after: Duration) -> Self {
+        Self {
+            retry_after: Some(retry_after),
+            ..self
+        }
+    }
+
+    /// Sets the body of the responses during maintenance.
+    #[must_use]
+    pub fn body(self, content_type: impl Into<String>, body: impl Into<Bytes>) -> Self {
+        Self {
+            content_type: Some(content_type.into()),
+            body: body.into(),
+            ..self
+        }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for Maintenance {
+    type Output = MaintenanceEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        MaintenanceEndpoint {
+            inner: ep,
+            mode: self.mode.clone(),
+            allow_paths: self.allow_paths.clone(),
+            allow_prefixes: self.allow_prefixes.clone(),
+            retry_after: self.retry_after,
+            content_type: self.content_type.clone(),
+            body: self.body.clone(),
+        }
+    }
+}
+
+/// Endpoint for the Maintenance middleware.
+pub struct MaintenanceEndpoint<E> {
+    inner: E,
+    mode: MaintenanceMode,
+    allow_paths: Vec<String>,
+    allow_prefixes: Vec<String>,
+    retry_after: Option<Duration>,
+    content_type: Option<String>,
+    body: Bytes,
+}
+
+impl<E: Endpoint> MaintenanceEndpoint<E> {
+    fn is_allowed(&self, path: &str) -> bool {
+        self.allow_paths.iter().any(|allow_path| allow_path == path)
+            || self
+                .allow_prefixes
+                .iter()
+                .any(|prefix| path.starts_with(prefix.as_str()))
+    }
+}
+
+impl<E: Endpoint> Endpoint for MaintenanceEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        if !self.mode.is_enabled() || self.is_allowed(req.uri().path()) {
+            return self.inner.call(req).await.map(IntoResponse::into_response);
+        }
+
+        let mut resp = Response::builder().status(StatusCode::SERVICE_UNAVAILABLE);
+        if let Some(retry_after) = self.retry_after {
+            resp = resp.header(header::RETRY_AFTER, retry_after.as_secs());
+        }
+        if let Some(content_type) = &self.content_type {
+            resp = resp.content_type(content_type);
+        }
+        Ok(resp.body(self.body.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{handler, test::TestClient, EndpointExt};
+
+    #[handler(internal)]
+    fn index() -> &'static str {
+        "hello"
+    }
+
+    #[tokio::test]
+    async fn maintenance() {
+        let mode = MaintenanceMode::new();
+        let cli = TestClient::new(
+            index.with(
+                Maintenance::new(mode.clone())
+                    .allow_path("/health")
+                    .allow_prefix("/admin/")
+                    .retry_after(Duration::from_secs(120))
+                    .body("application/json", r#"{"error":"maintenance"}"#),
+            ),
+        );
+
+        cli.get("/a").send().await.assert_status_is_ok();
+
+        mode.enable();
+        let resp = cli.get("/a").send().await;
+        resp.assert_status(StatusCode::SERVICE_UNAVAILABLE);
+        resp.assert_header(header::RETRY_AFTER, "120");
+        resp.assert_content_type("application/json");
+        resp.assert_text(r#"{"error":"maintenance"}"#).await;
+
+        cli.get("/health").send().await.assert_status_is_ok();
+        cli.get("/admin/users").send().await.assert_status_is_ok();
+        cli.get("/health/a")
+            .send()
+            .await
+            .assert_status(StatusCode::SERVICE_UNAVAILABLE);
+
+        mode.disable();
+        cli.get("/a").send().await.assert_status_is_ok();
+    }
+}
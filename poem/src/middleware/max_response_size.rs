@@ -0,0 +1,190 @@
+use std::{
+    io::Error as IoError,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use hyper::body::{Body as _, Frame, SizeHint};
+
+use crate::{
+    body::BoxBody, error::MaxResponseSizeError, Body, Endpoint, IntoResponse, Middleware, Request,
+    Response, Result,
+};
+
+/// Middleware for limiting the size of the response bodies, which protects
+/// the server and the clients from runaway handlers.
+///
+/// If the size of the body is known in advance and exceeds the limit, the
+/// response is replaced with an `INTERNAL SERVER ERROR`. Otherwise, the body
+/// is streamed until the limit is exceeded, and then the stream is aborted.
+/// Both cases are logged.
+///
+/// # Errors
+///
+/// - [`MaxResponseSizeError`]
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     handler, http::StatusCode, middleware::MaxResponseSize, test::TestClient, EndpointExt,
+/// };
+///
+/// #[handler]
+/// fn index() -> Vec<u8> {
+///     vec![0; 1024]
+/// }
+///
+/// let cli = TestClient::new(index.with(MaxResponseSize::new(100)));
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// cli.get("/")
+///     .send()
+///     .await
+///     .assert_status(StatusCode::INTERNAL_SERVER_ERROR);
+/// # });
+/// ```
+pub struct MaxResponseSize {
+    max_size: u64,
+}
+
+impl MaxResponseSize {
+    /// Create `MaxResponseSize` middleware with the maximum size of the
+    /// response bodies in bytes.
+    pub fn new(max_size: u64) -> Self {
+        Self { max_size }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for MaxResponseSize {
+    type Output = MaxResponseSizeEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        MaxResponseSizeEndpoint {
+            inner: ep,
+            max_size: self.max_size,
+        }
+    }
+}
+
+/// Endpoint for the MaxResponseSize middleware.
+pub struct MaxResponseSizeEndpoint<E> {
+    inner: E,
+    max_size: u64,
+}
+
+impl<E: Endpoint> Endpoint for MaxResponseSizeEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        let path = req.uri().path().to_string();
+        let mut resp = self.inner.call(req).await?.into_response();
+        let body = resp.take_body();
+
+        if let Some(size) = body.0.size_hint().exact() {
+            if size > self.max_size {
+                tracing::error!(
+                    path = %path,
+                    size,
+                    max_size = self.max_size,
+                    "response body exceeds the maximum size"
+                );
+                return Err(MaxResponseSizeError::ResponseTooLarge.into());
+            }
+            resp.set_body(body);
+            return Ok(resp);
+        }
+
+        resp.set_body(Body(BoxBody::new(LimitedBody {
+            inner: body.0,
+            remaining: self.max_size,
+            path,
+        })));
+        Ok(resp)
+    }
+}
+
+struct LimitedBody {
+    inner: BoxBody,
+    remaining: u64,
+    path: String,
+}
+
+impl hyper::body::Body for LimitedBody {
+    type Data = Bytes;
+    type Error = IoError;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let res = futures_util::ready!(Pin::new(&mut self.inner).poll_frame(cx));
+        if let Some(Ok(frame)) = &res {
+            if let Some(data) = frame.data_ref() {
+                match self.remaining.checked_sub(data.len() as u64) {
+                    Some(remaining) => self.remaining = remaining,
+                    None => {
+                        tracing::error!(
+                            path = %self.path,
+                            "response body exceeds the maximum size, aborting the stream"
+                        );
+                        return Poll::Ready(Some(Err(IoError::other(
+                            MaxResponseSizeError::ResponseTooLarge,
+                        ))));
+                    }
+                }
+            }
+        }
+        Poll::Ready(res)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::stream;
+    use http::StatusCode;
+
+    use super::*;
+    use crate::{endpoint::make_sync, test::TestClient, EndpointExt};
+
+    #[tokio::test]
+    async fn max_response_size() {
+        let cli = TestClient::new(make_sync(|_| "hello").with(MaxResponseSize::new(5)));
+        cli.get("/").send().await.assert_text("hello").await;
+
+        let cli = TestClient::new(make_sync(|_| "hello!").with(MaxResponseSize::new(5)));
+        cli.get("/")
+            .send()
+            .await
+            .assert_status(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[tokio::test]
+    async fn streaming() {
+        let ep = || {
+            make_sync(|_| {
+                Body::from_bytes_stream(stream::iter(
+                    ["abc", "def", "ghi"]
+                        .map(|s| Ok::<_, IoError>(Bytes::from_static(s.as_bytes()))),
+                ))
+            })
+        };
+
+        let cli = TestClient::new(ep().with(MaxResponseSize::new(9)));
+        cli.get("/").send().await.assert_text("abcdefghi").await;
+
+        let cli = TestClient::new(ep().with(MaxResponseSize::new(8)));
+        let resp = cli.get("/").send().await;
+        resp.assert_status_is_ok();
+        assert!(resp.0.into_body().into_vec().await.is_err());
+    }
+}
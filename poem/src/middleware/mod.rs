@@ -1,36 +1,57 @@
 //! Commonly used middleware.
 
 mod add_data;
+mod body_timeout;
+mod body_validator;
 mod catch_panic;
 #[cfg(feature = "compression")]
 mod compression;
+#[cfg(feature = "digest")]
+mod content_digest;
 #[cfg(feature = "cookie")]
 mod cookie_jar_manager;
 mod cors;
+#[cfg(feature = "csp")]
+mod csp_nonce;
 #[cfg(feature = "csrf")]
 mod csrf;
+mod deprecation_warning;
+mod draining;
 mod force_https;
+mod maintenance;
+mod max_response_size;
 mod normalize_path;
 #[cfg(feature = "opentelemetry")]
 mod opentelemetry_metrics;
 #[cfg(feature = "opentelemetry")]
 mod opentelemetry_tracing;
 mod propagate_header;
+mod query_limit;
 #[cfg(feature = "requestid")]
 mod requestid;
+mod same_origin;
 mod sensitive_header;
 mod set_header;
 mod size_limit;
+mod tenant_context;
 #[cfg(feature = "tokio-metrics")]
 mod tokio_metrics_mw;
 #[cfg(feature = "tower-compat")]
 mod tower_compat;
 mod tracing_mw;
+#[cfg(feature = "webhook")]
+mod webhook_signature;
+#[cfg(feature = "websocket")]
+mod websocket_limit;
 
 #[cfg(feature = "compression")]
 pub use self::compression::{Compression, CompressionEndpoint};
+#[cfg(feature = "digest")]
+pub use self::content_digest::{ContentDigest, ContentDigestEndpoint, DigestAlgorithm};
 #[cfg(feature = "cookie")]
 pub use self::cookie_jar_manager::{CookieJarManager, CookieJarManagerEndpoint};
+#[cfg(feature = "csp")]
+pub use self::csp_nonce::{CspNonce, CspNonceEndpoint};
 #[cfg(feature = "csrf")]
 pub use self::csrf::{Csrf, CsrfEndpoint};
 #[cfg(feature = "opentelemetry")]
@@ -43,16 +64,33 @@ pub use self::requestid::{ReqId, RequestId, RequestIdEndpoint, ReuseId};
 pub use self::tokio_metrics_mw::{TokioMetrics, TokioMetricsEndpoint};
 #[cfg(feature = "tower-compat")]
 pub use self::tower_compat::TowerLayerCompatExt;
+#[cfg(feature = "webhook")]
+pub use self::webhook_signature::{
+    HmacAlgorithm, SignatureEncoding, WebhookSignature, WebhookSignatureEndpoint,
+};
+#[cfg(feature = "websocket")]
+pub(crate) use self::websocket_limit::WebSocketConnectionGuard;
+#[cfg(feature = "websocket")]
+pub use self::websocket_limit::{WebSocketLimit, WebSocketLimitEndpoint};
 pub use self::{
     add_data::{AddData, AddDataEndpoint},
+    body_timeout::{BodyTimeout, BodyTimeoutEndpoint},
+    body_validator::{BodyValidator, BodyValidatorEndpoint, ChunkValidator},
     catch_panic::{CatchPanic, CatchPanicEndpoint, PanicHandler},
     cors::{Cors, CorsEndpoint},
+    deprecation_warning::{DeprecationWarning, DeprecationWarningEndpoint},
+    draining::{Draining, DrainingEndpoint},
     force_https::ForceHttps,
+    maintenance::{Maintenance, MaintenanceEndpoint, MaintenanceMode},
+    max_response_size::{MaxResponseSize, MaxResponseSizeEndpoint},
     normalize_path::{NormalizePath, NormalizePathEndpoint, TrailingSlash},
     propagate_header::{PropagateHeader, PropagateHeaderEndpoint},
+    query_limit::{QueryLimit, QueryLimitEndpoint},
+    same_origin::{SameOrigin, SameOriginEndpoint},
     sensitive_header::{SensitiveHeader, SensitiveHeaderEndpoint},
     set_header::{SetHeader, SetHeaderEndpoint},
     size_limit::{SizeLimit, SizeLimitEndpoint},
+    tenant_context::{TenantContext, TenantContextEndpoint, TenantId},
     tracing_mw::{Tracing, TracingEndpoint},
 };
 use crate::endpoint::Endpoint;
@@ -60,7 +60,7 @@ impl<E: Endpoint> Endpoint for PropagateHeaderEndpoint<E> {
         }
 
         let mut resp = self.inner.call(req).await?.into_response();
-        resp.headers_mut().extend(headers);
+        resp.extend_headers(headers);
         Ok(resp)
     }
 }
@@ -0,0 +1,181 @@
+use crate::{error::QueryLimitError, Endpoint, Middleware, Request, Result};
+
+/// Middleware for limiting the length of the query string and the number of
+/// query parameters, which protects the query parsing from resource
+/// exhaustion.
+///
+/// The requests are rejected before the query string is parsed, with `URI
+/// TOO LONG` if the query string is too long, and `BAD REQUEST` if there are
+/// too many parameters.
+///
+/// # Errors
+///
+/// - [`QueryLimitError`]
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+///
+/// use poem::{
+///     handler, http::StatusCode, middleware::QueryLimit, test::TestClient, web::Query,
+///     EndpointExt,
+/// };
+///
+/// #[handler]
+/// fn index(Query(params): Query<HashMap<String, String>>) -> String {
+///     params.len().to_string()
+/// }
+///
+/// let cli = TestClient::new(index.with(QueryLimit::new().max_params(2)));
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// cli.get("/")
+///     .query("a", &1)
+///     .query("b", &2)
+///     .send()
+///     .await
+///     .assert_text("2")
+///     .await;
+/// cli.get("/")
+///     .query("a", &1)
+///     .query("b", &2)
+///     .query("c", &3)
+///     .send()
+///     .await
+///     .assert_status(StatusCode::BAD_REQUEST);
+/// # });
+/// ```
+pub struct QueryLimit {
+    max_length: usize,
+    max_params: usize,
+}
+
+impl Default for QueryLimit {
+    fn default() -> Self {
+        Self {
+            max_length: 16 * 1024,
+            max_params: 1000,
+        }
+    }
+}
+
+impl QueryLimit {
+    /// Create `QueryLimit` middleware, which allows query strings of up to
+    /// 16KiB with up to 1000 parameters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum length of the query string in bytes.
+    #[must_use]
+    pub fn max_length(self, max_length: usize) -> Self {
+        Self { max_length, ..self }
+    }
+
+    /// Sets the maximum number of query parameters.
+    #[must_use]
+    pub fn max_params(self, max_params: usize) -> Self {
+        Self { max_params, ..self }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for QueryLimit {
+    type Output = QueryLimitEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        QueryLimitEndpoint {
+            inner: ep,
+            max_length: self.max_length,
+            max_params: self.max_params,
+        }
+    }
+}
+
+/// Endpoint for QueryLimit middleware.
+pub struct QueryLimitEndpoint<E> {
+    inner: E,
+    max_length: usize,
+    max_params: usize,
+}
+
+impl<E: Endpoint> Endpoint for QueryLimitEndpoint<E> {
+    type Output = E::Output;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        if let Some(query) = req.uri().query() {
+            if query.len() > self.max_length {
+                return Err(QueryLimitError::QueryTooLong.into());
+            }
+
+            let params = query
+                .split('&')
+                .filter(|param| !param.is_empty())
+                .take(self.max_params + 1)
+                .count();
+            if params > self.max_params {
+                return Err(QueryLimitError::TooManyParams.into());
+            }
+        }
+
+        self.inner.call(req).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::{StatusCode, Uri};
+
+    use super::*;
+    use crate::{endpoint::make_sync, EndpointExt};
+
+    #[tokio::test]
+    async fn query_limit() {
+        let ep = make_sync(|_| ()).with(QueryLimit::new().max_length(20).max_params(3));
+
+        let call = |uri: String| {
+            let req = Request::builder().uri(Uri::try_from(uri).unwrap()).finish();
+            ep.call(req)
+        };
+
+        assert!(call("/".to_string()).await.is_ok());
+        assert!(call("/?a=1&b=2&&c=3".to_string()).await.is_ok());
+        assert_eq!(
+            call("/?a=1&b=2&c=3&d=4".to_string())
+                .await
+                .unwrap_err()
+                .status(),
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(
+            call(format!("/?a={}", "x".repeat(20)))
+                .await
+                .unwrap_err()
+                .status(),
+            StatusCode::URI_TOO_LONG
+        );
+    }
+
+    #[tokio::test]
+    async fn many_params() {
+        let ep = make_sync(|_| ()).with(QueryLimit::new());
+        // `Uri` cannot be longer than 64KiB
+        let query = vec!["a"; 30_000].join("&");
+        let req = Request::builder()
+            .uri(Uri::try_from(format!("/?{query}")).unwrap())
+            .finish();
+        assert_eq!(
+            ep.call(req).await.unwrap_err().status(),
+            StatusCode::URI_TOO_LONG
+        );
+
+        let ep = make_sync(|_| ()).with(QueryLimit::new().max_length(usize::MAX));
+        let req = Request::builder()
+            .uri(Uri::try_from(format!("/?{query}")).unwrap())
+            .finish();
+        assert_eq!(
+            ep.call(req).await.unwrap_err().status(),
+            StatusCode::BAD_REQUEST
+        );
+    }
+}
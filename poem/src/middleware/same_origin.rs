@@ -0,0 +1,322 @@
+use http::{header, uri::Authority, Method, Uri};
+
+use crate::{error::SameOriginError, web::is_secure, Endpoint, Middleware, Request, Result};
+
+/// Middleware for rejecting cross-origin state-changing requests, which
+/// protects against CSRF without tokens.
+///
+/// The origin of the requests with methods other than `GET`, `HEAD`,
+/// `OPTIONS` and `TRACE` is read from the `Origin` header, falling back to
+/// the `Referer` header, and must be one of the allowed origins. If no origin
+/// is allowed explicitly, the origin must match the scheme of the request
+/// and its `Host` header, or the authority of the URI if there is no `Host`
+/// header, such as over HTTP/2.
+///
+/// The scheme is `https` if the request is [`Secure`](crate::web::Secure).
+/// Behind a proxy that terminates TLS, the proxy must be added as
+/// [`TrustedProxies`](crate::web::TrustedProxies) to forward the scheme,
+/// otherwise the origins must be allowed with [`SameOrigin::allow_origin`].
+///
+/// The requests without both headers, and the requests with an opaque
+/// origin (`Origin: null`) are rejected, unless
+/// [`SameOrigin::allow_missing_origin`] is enabled for the first case.
+///
+/// # Errors
+///
+/// - [`SameOriginError`]
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     handler, http::StatusCode, middleware::SameOrigin, post, test::TestClient, EndpointExt,
+///     Route,
+/// };
+///
+/// #[handler]
+/// fn transfer() {}
+///
+/// let app = Route::new()
+///     .at("/transfer", post(transfer))
+///     .with(SameOrigin::new().allow_origin("https://example.com"));
+/// let cli = TestClient::new(app);
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// cli.post("/transfer")
+///     .header("Origin", "https://example.com")
+///     .send()
+///     .await
+///     .assert_status_is_ok();
+/// cli.post("/transfer")
+///     .header("Origin", "https://evil.com")
+///     .send()
+///     .await
+///     .assert_status(StatusCode::FORBIDDEN);
+/// # });
+/// ```
+#[derive(Default)]
+pub struct SameOrigin {
+    allow_origins: Vec<String>,
+    allow_missing_origin: bool,
+}
+
+impl SameOrigin {
+    /// Create `SameOrigin` middleware, which only allows the origin of the
+    /// `Host` header.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allows the specified origin, such as `https://example.com`.
+    ///
+    /// If any origin is allowed, the `Host` header is not trusted anymore.
+    ///
+    /// # Panics
+    ///
+    /// Panic when the origin is not a valid URL with a scheme and a host.
+    #[must_use]
+    pub fn allow_origin(mut self, origin: impl AsRef<str>) -> Self {
+        let origin = origin.as_ref();
+        self.allow_origins
+            .push(parse_origin(origin).unwrap_or_else(|| panic!("invalid origin: {origin}")));
+        self
+    }
+
+    /// Allows the requests without both `Origin` and `Referer` headers, such
+    /// as the requests that are not sent by the browsers.
+    #[must_use]
+    pub fn allow_missing_origin(self, allow: bool) -> Self {
+        Self {
+            allow_missing_origin: allow,
+            ..self
+        }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for SameOrigin {
+    type Output = SameOriginEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        SameOriginEndpoint {
+            inner: ep,
+            allow_origins: self.allow_origins.clone(),
+            allow_missing_origin: self.allow_missing_origin,
+        }
+    }
+}
+
+/// Endpoint for SameOrigin middleware.
+pub struct SameOriginEndpoint<E> {
+    inner: E,
+    allow_origins: Vec<String>,
+    allow_missing_origin: bool,
+}
+
+/// Returns the serialized origin of the URL, in lowercase and without the
+/// default port.
+fn parse_origin(url: &str) -> Option<String> {
+    let uri = url.parse::<Uri>().ok()?;
+    let scheme = uri.scheme_str()?.to_ascii_lowercase();
+    let authority = uri.authority()?;
+    Some(format!(
+        "{scheme}://{}",
+        normalize_authority(&scheme, authority)
+    ))
+}
+
+fn normalize_authority(scheme: &str, authority: &Authority) -> String {
+    let host = authority.host().to_ascii_lowercase();
+    match (scheme, authority.port_u16()) {
+        ("http", Some(80)) | ("https", Some(443)) | (_, None) => host,
+        (_, Some(port)) => format!("{host}:{port}"),
+    }
+}
+
+impl<E: Endpoint> SameOriginEndpoint<E> {
+    fn check(&self, req: &Request) -> Result<(), SameOriginError> {
+        let origin = match req.headers().get(header::ORIGIN) {
+            Some(origin) => origin.to_str().ok(),
+            None => match req.headers().get(header::REFERER) {
+                Some(referer) => referer.to_str().ok(),
+                None if self.allow_missing_origin => return Ok(()),
+                None => return Err(SameOriginError::MissingOrigin),
+            },
+        };
+        // the opaque origin `null` and the malformed headers cannot match
+        let origin = origin
+            .and_then(parse_origin)
+            .ok_or(SameOriginError::CrossOrigin)?;
+
+        let allowed = if self.allow_origins.is_empty() {
+            let scheme = if is_secure(req) { "https" } else { "http" };
+            let host = match req.headers().get(header::HOST) {
+                Some(host) => host
+                    .to_str()
+                    .ok()
+                    .and_then(|host| host.parse::<Authority>().ok()),
+                None => req.uri().authority().cloned(),
+            };
+            host.is_some_and(|host| {
+                origin == format!("{scheme}://{}", normalize_authority(scheme, &host))
+            })
+        } else {
+            self.allow_origins.contains(&origin)
+        };
+
+        if allowed {
+            Ok(())
+        } else {
+            Err(SameOriginError::CrossOrigin)
+        }
+    }
+}
+
+impl<E: Endpoint> Endpoint for SameOriginEndpoint<E> {
+    type Output = E::Output;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        let is_safe = matches!(
+            *req.method(),
+            Method::GET | Method::HEAD | Method::OPTIONS | Method::TRACE
+        );
+        if !is_safe {
+            self.check(&req)?;
+        }
+        self.inner.call(req).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use super::*;
+    use crate::{endpoint::make_sync, test::TestClient, EndpointExt};
+
+    #[tokio::test]
+    async fn same_as_host() {
+        let cli = TestClient::new(make_sync(|_| ()).with(SameOrigin::new()));
+
+        cli.get("/").send().await.assert_status_is_ok();
+
+        cli.post("/")
+            .header("Host", "example.com")
+            .header("Origin", "http://EXAMPLE.com:80")
+            .send()
+            .await
+            .assert_status_is_ok();
+        cli.post("/")
+            .header("Host", "example.com:8080")
+            .header("Referer", "http://example.com:8080/a?b=c")
+            .send()
+            .await
+            .assert_status_is_ok();
+        cli.post("/")
+            .header("Host", "example.com")
+            .header("Origin", "http://example.com:8443")
+            .send()
+            .await
+            .assert_status(StatusCode::FORBIDDEN);
+        // the scheme must match the one of the request
+        cli.post("/")
+            .header("Host", "example.com")
+            .header("Origin", "https://example.com")
+            .send()
+            .await
+            .assert_status(StatusCode::FORBIDDEN);
+        cli.post("/")
+            .header("Host", "example.com")
+            .header("Origin", "null")
+            .send()
+            .await
+            .assert_status(StatusCode::FORBIDDEN);
+        cli.post("/")
+            .header("Host", "example.com")
+            .send()
+            .await
+            .assert_status(StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn uri_authority() {
+        let ep = make_sync(|_| ()).with(SameOrigin::new());
+
+        let mut req = Request::builder()
+            .method(Method::POST)
+            .version(http::Version::HTTP_2)
+            .uri(Uri::from_static("https://example.com/transfer"))
+            .header(header::ORIGIN, "https://example.com")
+            .finish();
+        req.state_mut().scheme = http::uri::Scheme::HTTPS;
+        assert_eq!(ep.get_response(req).await.status(), StatusCode::OK);
+
+        let req = Request::builder()
+            .method(Method::POST)
+            .version(http::Version::HTTP_2)
+            .uri(Uri::from_static("http://example.com/transfer"))
+            .header(header::ORIGIN, "https://example.com")
+            .finish();
+        assert_eq!(ep.get_response(req).await.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn trusted_proxy() {
+        let ep = make_sync(|_| ()).with(SameOrigin::new());
+        let proxy_req = || {
+            let mut req = Request::builder()
+                .method(Method::POST)
+                .header(header::HOST, "example.com")
+                .header("X-Forwarded-Proto", "https")
+                .header(header::ORIGIN, "https://example.com")
+                .finish();
+            req.state_mut().remote_addr =
+                crate::web::RemoteAddr(crate::Addr::SocketAddr(([10, 0, 0, 1], 1234).into()));
+            req
+        };
+
+        // the forwarded scheme of an untrusted peer is ignored
+        assert_eq!(
+            ep.get_response(proxy_req()).await.status(),
+            StatusCode::FORBIDDEN
+        );
+
+        let ep = ep.data(crate::web::TrustedProxies::new().proxy([10, 0, 0, 1].into()));
+        assert_eq!(ep.get_response(proxy_req()).await.status(), StatusCode::OK);
+    }
+
+    #[test]
+    #[should_panic]
+    fn invalid_origin() {
+        let _ = SameOrigin::new().allow_origin("example.com");
+    }
+
+    #[tokio::test]
+    async fn allow_origins() {
+        let cli = TestClient::new(
+            make_sync(|_| ()).with(
+                SameOrigin::new()
+                    .allow_origin("https://a.com")
+                    .allow_origin("https://b.com/")
+                    .allow_missing_origin(true),
+            ),
+        );
+
+        for origin in ["https://a.com", "https://b.com"] {
+            cli.delete("/")
+                .header("Origin", origin)
+                .send()
+                .await
+                .assert_status_is_ok();
+        }
+
+        // the host is not trusted if the origins are allowed explicitly
+        cli.post("/")
+            .header("Host", "c.com")
+            .header("Origin", "https://c.com")
+            .send()
+            .await
+            .assert_status(StatusCode::FORBIDDEN);
+
+        cli.post("/").send().await.assert_status_is_ok();
+    }
+}
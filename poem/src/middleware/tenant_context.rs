@@ -0,0 +1,259 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    ops::Deref,
+    sync::Arc,
+};
+
+use http::{header, StatusCode};
+use tracing::{info_span, Instrument};
+
+use crate::{error::TenantContextError, Endpoint, Error, Middleware, Request, Result};
+
+/// The tenant of a request resolved by the [`TenantContext`] middleware.
+///
+/// It can be extracted in handlers with [`Data<&TenantId>`](crate::web::Data).
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct TenantId(pub String);
+
+impl Deref for TenantId {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Display for TenantId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl<T: Into<String>> From<T> for TenantId {
+    fn from(id: T) -> Self {
+        TenantId(id.into())
+    }
+}
+
+type Resolver = Arc<dyn Fn(&Request) -> Option<TenantId> + Send + Sync>;
+
+/// Middleware for resolving the tenant of requests in multi-tenant
+/// applications.
+///
+/// The resolved [`TenantId`] is added to the request data and recorded as the
+/// `tenant_id` field of a `tenant` span, so the events produced by the inner
+/// endpoint contain it. To include it in the request logs of the
+/// [`Tracing`](crate::middleware::Tracing) middleware, apply this middleware
+/// after it.
+///
+/// By default, the tenant is required and the request is rejected with `BAD
+/// REQUEST` status code if it cannot be resolved.
+///
+/// # Errors
+///
+/// - [`TenantContextError`]
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     handler,
+///     http::StatusCode,
+///     middleware::{TenantContext, TenantId},
+///     test::TestClient,
+///     web::Data,
+///     EndpointExt,
+/// };
+///
+/// #[handler]
+/// fn index(tenant: Data<&TenantId>) -> String {
+///     tenant.to_string()
+/// }
+///
+/// let app = index.with(TenantContext::from_header("X-Tenant-Id"));
+/// let cli = TestClient::new(app);
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = cli.get("/").header("X-Tenant-Id", "acme").send().await;
+/// resp.assert_status_is_ok();
+/// resp.assert_text("acme").await;
+///
+/// let resp = cli.get("/").send().await;
+/// resp.assert_status(StatusCode::BAD_REQUEST);
+/// # });
+/// ```
+pub struct TenantContext {
+    resolver: Resolver,
+    required: bool,
+    status: StatusCode,
+}
+
+impl TenantContext {
+    /// Create `TenantContext` middleware with a function that resolves the
+    /// tenant from the request.
+    pub fn new<F>(resolver: F) -> Self
+    where
+        F: Fn(&Request) -> Option<TenantId> + Send + Sync + 'static,
+    {
+        Self {
+            resolver: Arc::new(resolver),
+            required: true,
+            status: StatusCode::BAD_REQUEST,
+        }
+    }
+
+    /// Create `TenantContext` middleware that reads the tenant from the
+    /// specified header.
+    pub fn from_header(name: impl Into<String>) -> Self {
+        let name = name.into();
+        Self::new(move |req| {
+            req.header(&name)
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(TenantId::from)
+        })
+    }
+
+    /// Create `TenantContext` middleware that reads the tenant from the
+    /// subdomain of the request host, which is the host of the URI for
+    /// HTTP/2 requests, or the `Host` header.
+    ///
+    /// For example, if the domain is `example.com`, the tenant of
+    /// `acme.example.com` is `acme`. Nested subdomains are not resolved, and
+    /// the host is compared case-insensitively, so the tenant is lowercase.
+    pub fn from_subdomain(domain: impl Into<String>) -> Self {
+        let suffix = format!(".{}", domain.into().trim_start_matches('.')).to_ascii_lowercase();
+        Self::new(move |req| {
+            let host = match req.uri().host() {
+                Some(host) => host,
+                None => {
+                    let host = req
+                        .headers()
+                        .get(header::HOST)
+                        .and_then(|host| host.to_str().ok())?;
+                    host.rsplit_once(':').map_or(host, |(host, _)| host)
+                }
+            };
+            let host = host.to_ascii_lowercase();
+            let subdomain = host.strip_suffix(suffix.as_str())?;
+            (!subdomain.is_empty() && !subdomain.contains('.')).then(|| subdomain.into())
+        })
+    }
+
+    /// Sets whether the tenant is required, defaults to `true`.
+    ///
+    /// If it is `false`, the requests without a tenant are passed to the inner
+    /// endpoint.
+    #[must_use]
+    pub fn required(self, required: bool) -> Self {
+        Self { required, ..self }
+    }
+
+    /// Sets the status code of the response when the tenant is required but
+    /// cannot be resolved, defaults to `BAD REQUEST`.
+    #[must_use]
+    pub fn missing_status(self, status: StatusCode) -> Self {
+        Self { status, ..self }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for TenantContext {
+    type Output = TenantContextEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        TenantContextEndpoint {
+            inner: ep,
+            resolver: self.resolver.clone(),
+            required: self.required,
+            status: self.status,
+        }
+    }
+}
+
+/// Endpoint for the TenantContext middleware.
+pub struct TenantContextEndpoint<E> {
+    inner: E,
+    resolver: Resolver,
+    required: bool,
+    status: StatusCode,
+}
+
+impl<E: Endpoint> Endpoint for TenantContextEndpoint<E> {
+    type Output = E::Output;
+
+    async fn call(&self, mut req: Request) -> Result<Self::Output> {
+        match (self.resolver)(&req) {
+            Some(tenant_id) => {
+                let span = info_span!("tenant", tenant_id = %tenant_id);
+                req.set_data(tenant_id);
+                self.inner.call(req).instrument(span).await
+            }
+            None if self.required => {
+                Err(Error::new(TenantContextError::MissingTenant, self.status))
+            }
+            None => self.inner.call(req).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{handler, test::TestClient, EndpointExt};
+
+    #[handler(internal)]
+    fn index(tenant: Option<crate::web::Data<&TenantId>>) -> String {
+        tenant.map(|tenant| tenant.to_string()).unwrap_or_default()
+    }
+
+    #[tokio::test]
+    async fn from_subdomain() {
+        let cli = TestClient::new(index.with(
+            TenantContext::from_subdomain("example.com").missing_status(StatusCode::NOT_FOUND),
+        ));
+
+        for host in [
+            "acme.example.com",
+            "acme.example.com:8080",
+            "Acme.Example.COM",
+        ] {
+            let resp = cli.get("/").header(header::HOST, host).send().await;
+            resp.assert_status_is_ok();
+            resp.assert_text("acme").await;
+        }
+
+        // the authority of HTTP/2 requests is in the URI
+        let resp = index
+            .with(TenantContext::from_subdomain("example.com"))
+            .get_response(
+                Request::builder()
+                    .version(http::Version::HTTP_2)
+                    .uri("https://acme.example.com:8443/".parse().unwrap())
+                    .finish(),
+            )
+            .await;
+        assert_eq!(resp.into_body().into_string().await.unwrap(), "acme");
+
+        for host in ["example.com", "a.b.example.com", "acme.example.org"] {
+            cli.get("/")
+                .header(header::HOST, host)
+                .send()
+                .await
+                .assert_status(StatusCode::NOT_FOUND);
+        }
+    }
+
+    #[tokio::test]
+    async fn optional() {
+        let cli =
+            TestClient::new(index.with(TenantContext::from_header("X-Tenant-Id").required(false)));
+
+        let resp = cli.get("/").header("X-Tenant-Id", "acme").send().await;
+        resp.assert_status_is_ok();
+        resp.assert_text("acme").await;
+
+        let resp = cli.get("/").send().await;
+        resp.assert_status_is_ok();
+        resp.assert_text("").await;
+    }
+}
@@ -0,0 +1,384 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use bytes::{BufMut, BytesMut};
+use ring::hmac;
+
+use crate::{error::WebhookSignatureError, Endpoint, Middleware, Request, Result};
+
+/// The HMAC algorithm used by [`WebhookSignature`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum HmacAlgorithm {
+    /// HMAC-SHA1, only use it for legacy webhook providers.
+    Sha1,
+    /// HMAC-SHA256
+    Sha256,
+}
+
+impl HmacAlgorithm {
+    fn as_ring(&self) -> hmac::Algorithm {
+        match self {
+            HmacAlgorithm::Sha1 => hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY,
+            HmacAlgorithm::Sha256 => hmac::HMAC_SHA256,
+        }
+    }
+}
+
+/// The encoding of the signature in the request header.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SignatureEncoding {
+    /// Hexadecimal encoding.
+    Hex,
+    /// Standard base64 encoding.
+    Base64,
+}
+
+/// Middleware for verifying the HMAC signature of webhook requests.
+///
+/// The signature is computed over the raw request body with the shared secret
+/// and compared in constant time with the value of the signature header. If
+/// the signature is missing or does not match, it returns `UNAUTHORIZED`
+/// status code. The body is passed unchanged to the inner endpoint.
+///
+/// If [`WebhookSignature::timestamp`] is specified, the signed payload is
+/// `{timestamp}.{body}` and requests whose timestamp is outside the tolerance
+/// are rejected, which prevents replay attacks.
+///
+/// # Errors
+///
+/// - [`WebhookSignatureError`]
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     handler, http::StatusCode, middleware::WebhookSignature, test::TestClient, EndpointExt,
+/// };
+///
+/// #[handler]
+/// fn index(body: String) -> String {
+///     body
+/// }
+///
+/// let app = index.with(
+///     WebhookSignature::new("X-Hub-Signature-256", "It's a Secret to Everybody")
+///         .prefix("sha256="),
+/// );
+/// let cli = TestClient::new(app);
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = cli
+///     .post("/")
+///     .header(
+///         "X-Hub-Signature-256",
+///         "sha256=757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17",
+///     )
+///     .body("Hello, World!")
+///     .send()
+///     .await;
+/// resp.assert_status_is_ok();
+/// resp.assert_text("Hello, World!").await;
+///
+/// let resp = cli
+///     .post("/")
+///     .header("X-Hub-Signature-256", "sha256=0000")
+///     .body("Hello, World!")
+///     .send()
+///     .await;
+/// resp.assert_status(StatusCode::UNAUTHORIZED);
+/// # });
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "webhook")))]
+pub struct WebhookSignature {
+    header: String,
+    secret: Vec<u8>,
+    algorithm: HmacAlgorithm,
+    encoding: SignatureEncoding,
+    prefix: Option<String>,
+    timestamp: Option<(String, Duration)>,
+}
+
+impl WebhookSignature {
+    /// Create `WebhookSignature` middleware that reads the signature from the
+    /// specified header.
+    ///
+    /// By default, the algorithm is [`HmacAlgorithm::Sha256`] and the encoding
+    /// is [`SignatureEncoding::Hex`].
+    pub fn new(header: impl Into<String>, secret: impl AsRef<[u8]>) -> Self {
+        Self {
+            header: header.into(),
+            secret: secret.as_ref().to_vec(),
+            algorithm: HmacAlgorithm::Sha256,
+            encoding: SignatureEncoding::Hex,
+            prefix: None,
+            timestamp: None,
+        }
+    }
+
+    /// Sets the HMAC algorithm.
+    #[must_use]
+    pub fn algorithm(self, algorithm: HmacAlgorithm) -> Self {
+        Self { algorithm, ..self }
+    }
+
+    /// Sets the encoding of the signature.
+    #[must_use]
+    pub fn encoding(self, encoding: SignatureEncoding) -> Self {
+        Self { encoding, ..self }
+    }
+
+    /// Sets the prefix of the signature header value, such as `sha256=`.
+    #[must_use]
+    pub fn prefix(self, prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: Some(prefix.into()),
+            ..self
+        }
+    }
+
+    /// Reads the unix timestamp (in seconds) from the specified header and
+    /// rejects the request if it differs from the current time by more than
+    /// `tolerance`.
+    ///
+    /// The timestamp is included in the signed payload as
+    /// `{timestamp}.{body}`.
+    #[must_use]
+    pub fn timestamp(self, header: impl Into<String>, tolerance: Duration) -> Self {
+        Self {
+            timestamp: Some((header.into(), tolerance)),
+            ..self
+        }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for WebhookSignature {
+    type Output = WebhookSignatureEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        WebhookSignatureEndpoint {
+            inner: ep,
+            header: self.header.clone(),
+            key: hmac::Key::new(self.algorithm.as_ring(), &self.secret),
+            encoding: self.encoding,
+            prefix: self.prefix.clone(),
+            timestamp: self.timestamp.clone(),
+        }
+    }
+}
+
+/// Endpoint for the WebhookSignature middleware.
+#[cfg_attr(docsrs, doc(cfg(feature = "webhook")))]
+pub struct WebhookSignatureEndpoint<E> {
+    inner: E,
+    header: String,
+    key: hmac::Key,
+    encoding: SignatureEncoding,
+    prefix: Option<String>,
+    timestamp: Option<(String, Duration)>,
+}
+
+impl<E> WebhookSignatureEndpoint<E> {
+    fn signature(&self, req: &Request) -> Result<Vec<u8>, WebhookSignatureError> {
+        let value = req
+            .header(&self.header)
+            .ok_or(WebhookSignatureError::MissingSignature)?;
+        let value = match &self.prefix {
+            Some(prefix) => value
+                .strip_prefix(prefix.as_str())
+                .ok_or(WebhookSignatureError::InvalidSignature)?,
+            None => value,
+        };
+        match self.encoding {
+            SignatureEncoding::Hex => hex::decode(value.trim()).ok(),
+            SignatureEncoding::Base64 => STANDARD.decode(value.trim()).ok(),
+        }
+        .ok_or(WebhookSignatureError::InvalidSignature)
+    }
+
+    fn check_timestamp(&self, req: &Request) -> Result<Option<String>, WebhookSignatureError> {
+        let Some((header, tolerance)) = &self.timestamp else {
+            return Ok(None);
+        };
+        let value = req
+            .header(header)
+            .ok_or(WebhookSignatureError::MissingTimestamp)?;
+        let timestamp = value
+            .trim()
+            .parse::<u64>()
+            .map_err(|_| WebhookSignatureError::InvalidTimestamp)?;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        if now.abs_diff(timestamp) > tolerance.as_secs() {
+            return Err(WebhookSignatureError::InvalidTimestamp);
+        }
+        Ok(Some(value.trim().to_string()))
+    }
+}
+
+impl<E: Endpoint> Endpoint for WebhookSignatureEndpoint<E> {
+    type Output = E::Output;
+
+    async fn call(&self, mut req: Request) -> Result<Self::Output> {
+        let signature = self.signature(&req)?;
+        let timestamp = self.check_timestamp(&req)?;
+        let body = req.take_body().into_bytes().await?;
+
+        let verified = match timestamp {
+            Some(timestamp) => {
+                let mut payload = BytesMut::with_capacity(timestamp.len() + 1 + body.len());
+                payload.put_slice(timestamp.as_bytes());
+                payload.put_u8(b'.');
+                payload.put_slice(&body);
+                hmac::verify(&self.key, &payload, &signature)
+            }
+            None => hmac::verify(&self.key, &body, &signature),
+        };
+        if verified.is_err() {
+            return Err(WebhookSignatureError::InvalidSignature.into());
+        }
+
+        req.set_body(body);
+        self.inner.call(req).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use super::*;
+    use crate::{handler, test::TestClient, EndpointExt};
+
+    #[handler(internal)]
+    fn index(body: String) -> String {
+        body
+    }
+
+    fn sign(algorithm: HmacAlgorithm, secret: &str, payload: &str) -> hmac::Tag {
+        hmac::sign(
+            &hmac::Key::new(algorithm.as_ring(), secret.as_bytes()),
+            payload.as_bytes(),
+        )
+    }
+
+    #[tokio::test]
+    async fn hex_signature() {
+        let cli = TestClient::new(index.with(WebhookSignature::new("X-Signature", "secret")));
+        let signature = hex::encode(sign(HmacAlgorithm::Sha256, "secret", "hello"));
+
+        let resp = cli
+            .post("/")
+            .header("X-Signature", &signature)
+            .body("hello")
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+        resp.assert_text("hello").await;
+
+        cli.post("/")
+            .header("X-Signature", &signature)
+            .body("hello!")
+            .send()
+            .await
+            .assert_status(StatusCode::UNAUTHORIZED);
+
+        cli.post("/")
+            .header("X-Signature", "not hex")
+            .body("hello")
+            .send()
+            .await
+            .assert_status(StatusCode::UNAUTHORIZED);
+
+        cli.post("/")
+            .body("hello")
+            .send()
+            .await
+            .assert_status(StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn base64_sha1_signature() {
+        let cli = TestClient::new(
+            index.with(
+                WebhookSignature::new("X-Signature", "secret")
+                    .algorithm(HmacAlgorithm::Sha1)
+                    .encoding(SignatureEncoding::Base64)
+                    .prefix("sha1="),
+            ),
+        );
+        let signature = format!(
+            "sha1={}",
+            STANDARD.encode(sign(HmacAlgorithm::Sha1, "secret", "hello"))
+        );
+
+        cli.post("/")
+            .header("X-Signature", signature)
+            .body("hello")
+            .send()
+            .await
+            .assert_status_is_ok();
+
+        cli.post("/")
+            .header(
+                "X-Signature",
+                STANDARD.encode(sign(HmacAlgorithm::Sha1, "secret", "hello")),
+            )
+            .body("hello")
+            .send()
+            .await
+            .assert_status(StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn timestamp() {
+        let cli = TestClient::new(
+            index.with(
+                WebhookSignature::new("X-Signature", "secret")
+                    .timestamp("X-Timestamp", Duration::from_secs(300)),
+            ),
+        );
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        let ts = now.to_string();
+        let signature = hex::encode(sign(
+            HmacAlgorithm::Sha256,
+            "secret",
+            &format!("{ts}.hello"),
+        ));
+        cli.post("/")
+            .header("X-Signature", &signature)
+            .header("X-Timestamp", &ts)
+            .body("hello")
+            .send()
+            .await
+            .assert_status_is_ok();
+
+        // missing timestamp
+        cli.post("/")
+            .header("X-Signature", &signature)
+            .body("hello")
+            .send()
+            .await
+            .assert_status(StatusCode::UNAUTHORIZED);
+
+        // expired timestamp
+        let ts = (now - 600).to_string();
+        let signature = hex::encode(sign(
+            HmacAlgorithm::Sha256,
+            "secret",
+            &format!("{ts}.hello"),
+        ));
+        cli.post("/")
+            .header("X-Signature", &signature)
+            .header("X-Timestamp", &ts)
+            .body("hello")
+            .send()
+            .await
+            .assert_status(StatusCode::UNAUTHORIZED);
+    }
+}
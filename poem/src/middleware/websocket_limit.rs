@@ -0,0 +1,286 @@
+use std::{collections::HashMap, sync::Arc};
+
+use http::header;
+use parking_lot::Mutex;
+
+use crate::{error::WebSocketLimitError, Endpoint, Middleware, Request, Result};
+
+type KeyFn = Arc<dyn Fn(&Request) -> Option<String> + Send + Sync>;
+
+/// Middleware for limiting the number of concurrent websocket connections,
+/// globally and per key, such as per IP address.
+///
+/// The upgrade requests are rejected with `SERVICE UNAVAILABLE` when there
+/// are too many connections, and with `TOO MANY REQUESTS` when there are too
+/// many connections of the same key. A connection is counted from the
+/// upgrade request until the callback of
+/// [`WebSocket::on_upgrade`](crate::web::websocket::WebSocket::on_upgrade)
+/// returns, or the upgrade fails. The other requests are not limited.
+///
+/// # Errors
+///
+/// - [`WebSocketLimitError`]
+///
+/// # Example
+///
+/// ```
+/// use futures_util::StreamExt;
+/// use poem::{
+///     get, handler, middleware::WebSocketLimit, web::websocket::WebSocket, EndpointExt,
+///     IntoResponse, Route,
+/// };
+///
+/// #[handler]
+/// async fn index(ws: WebSocket) -> impl IntoResponse {
+///     ws.on_upgrade(|mut socket| async move { while let Some(Ok(_)) = socket.next().await {} })
+/// }
+///
+/// let app = Route::new().at(
+///     "/ws",
+///     get(index).with(
+///         WebSocketLimit::new()
+///             .max_connections(10000)
+///             .max_connections_per_key(10, |req| Some(req.remote_addr().to_string())),
+///     ),
+/// );
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "websocket")))]
+#[derive(Default)]
+pub struct WebSocketLimit {
+    max_connections: Option<usize>,
+    max_connections_per_key: Option<(usize, KeyFn)>,
+}
+
+impl WebSocketLimit {
+    /// Create `WebSocketLimit` middleware without any limits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum number of concurrent connections.
+    #[must_use]
+    pub fn max_connections(self, max_connections: usize) -> Self {
+        Self {
+            max_connections: Some(max_connections),
+            ..self
+        }
+    }
+
+    /// Sets the maximum number of concurrent connections of each key, which
+    /// is returned by the specified function.
+    ///
+    /// The requests whose key is `None` are only limited by
+    /// [`WebSocketLimit::max_connections`].
+    #[must_use]
+    pub fn max_connections_per_key<F>(self, max_connections: usize, f: F) -> Self
+    where
+        F: Fn(&Request) -> Option<String> + Send + Sync + 'static,
+    {
+        Self {
+            max_connections_per_key: Some((max_connections, Arc::new(f))),
+            ..self
+        }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for WebSocketLimit {
+    type Output = WebSocketLimitEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        WebSocketLimitEndpoint {
+            inner: ep,
+            max_connections: self.max_connections,
+            max_connections_per_key: self.max_connections_per_key.clone(),
+            state: Default::default(),
+        }
+    }
+}
+
+/// Endpoint for WebSocketLimit middleware.
+#[cfg_attr(docsrs, doc(cfg(feature = "websocket")))]
+pub struct WebSocketLimitEndpoint<E> {
+    inner: E,
+    max_connections: Option<usize>,
+    max_connections_per_key: Option<(usize, KeyFn)>,
+    state: Arc<Mutex<LimitState>>,
+}
+
+#[derive(Default)]
+struct LimitState {
+    connections: usize,
+    connections_per_key: HashMap<String, usize>,
+}
+
+/// Holds a connection of the [`WebSocketLimit`] middleware until it is
+/// dropped.
+///
+/// It is put in the extensions of the upgrade request, and moved into the
+/// task of the websocket connection by the `WebSocket` extractor.
+pub(crate) struct WebSocketConnectionGuard {
+    state: Arc<Mutex<LimitState>>,
+    key: Option<String>,
+}
+
+impl Drop for WebSocketConnectionGuard {
+    fn drop(&mut self) {
+        let mut state = self.state.lock();
+        state.connections -= 1;
+        if let Some(key) = &self.key {
+            if let Some(connections) = state.connections_per_key.get_mut(key) {
+                *connections -= 1;
+                if *connections == 0 {
+                    state.connections_per_key.remove(key);
+                }
+            }
+        }
+    }
+}
+
+impl<E: Endpoint> WebSocketLimitEndpoint<E> {
+    fn acquire(&self, req: &Request) -> Result<WebSocketConnectionGuard, WebSocketLimitError> {
+        let key = self
+            .max_connections_per_key
+            .as_ref()
+            .and_then(|(max, f)| Some((*max, f(req)?)));
+
+        let mut state = self.state.lock();
+        if self
+            .max_connections
+            .is_some_and(|max| state.connections >= max)
+        {
+            return Err(WebSocketLimitError::TooManyConnections);
+        }
+        if let Some((max, key)) = &key {
+            if state
+                .connections_per_key
+                .get(key)
+                .copied()
+                .unwrap_or_default()
+                >= *max
+            {
+                return Err(WebSocketLimitError::TooManyConnectionsPerKey);
+            }
+            *state.connections_per_key.entry(key.clone()).or_default() += 1;
+        }
+        state.connections += 1;
+
+        Ok(WebSocketConnectionGuard {
+            state: self.state.clone(),
+            key: key.map(|(_, key)| key),
+        })
+    }
+}
+
+impl<E: Endpoint> Endpoint for WebSocketLimitEndpoint<E> {
+    type Output = E::Output;
+
+    async fn call(&self, mut req: Request) -> Result<Self::Output> {
+        let is_upgrade = req
+            .headers()
+            .get(header::UPGRADE)
+            .is_some_and(|value| value.as_bytes().eq_ignore_ascii_case(b"websocket"));
+        if is_upgrade {
+            let guard = self.acquire(&req)?;
+            req.extensions_mut().insert(Arc::new(guard));
+        }
+        self.inner.call(req).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::SocketAddr, time::Duration};
+
+    use futures_util::StreamExt;
+    use http::StatusCode;
+    use tokio_tungstenite::tungstenite::{handshake::client::generate_key, Error as WsError};
+
+    use super::*;
+    use crate::{
+        get, handler,
+        listener::{Acceptor, Listener, TcpListener},
+        web::websocket::WebSocket,
+        EndpointExt, IntoResponse, Route, Server,
+    };
+
+    #[handler(internal)]
+    async fn index(ws: WebSocket) -> impl IntoResponse {
+        ws.on_upgrade(|mut socket| async move { while let Some(Ok(_)) = socket.next().await {} })
+    }
+
+    async fn connect(
+        addr: SocketAddr,
+        key: &str,
+    ) -> Result<
+        tokio_tungstenite::WebSocketStream<
+            tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+        >,
+        StatusCode,
+    > {
+        let req = http::Request::builder()
+            .uri(format!("ws://{addr}/ws"))
+            .header("x-key", key)
+            .header(header::SEC_WEBSOCKET_KEY, generate_key())
+            .header(header::UPGRADE, "websocket")
+            .header(header::HOST, "localhost")
+            .header(header::CONNECTION, "upgrade")
+            .header(header::SEC_WEBSOCKET_VERSION, "13")
+            .body(())
+            .unwrap();
+        match tokio_tungstenite::connect_async(req).await {
+            Ok((stream, _)) => Ok(stream),
+            Err(WsError::Http(resp)) => Err(resp.status()),
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn websocket_limit() {
+        let app = Route::new().at(
+            "/ws",
+            get(index).with(
+                WebSocketLimit::new()
+                    .max_connections(2)
+                    .max_connections_per_key(1, |req| req.header("x-key").map(ToString::to_string)),
+            ),
+        );
+        let acceptor = TcpListener::bind("127.0.0.1:0")
+            .into_acceptor()
+            .await
+            .unwrap();
+        let addr = acceptor
+            .local_addr()
+            .remove(0)
+            .as_socket_addr()
+            .cloned()
+            .unwrap();
+        let handle = tokio::spawn(async move {
+            let _ = Server::new_with_acceptor(acceptor).run(app).await;
+        });
+
+        let a = connect(addr, "a").await.unwrap();
+        assert_eq!(
+            connect(addr, "a").await.unwrap_err(),
+            StatusCode::TOO_MANY_REQUESTS
+        );
+        let _b = connect(addr, "b").await.unwrap();
+        assert_eq!(
+            connect(addr, "c").await.unwrap_err(),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+
+        // the connection is released when the socket is closed abnormally
+        drop(a);
+        let mut connected = false;
+        for _ in 0..100 {
+            if connect(addr, "a").await.is_ok() {
+                connected = true;
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(connected);
+
+        handle.abort();
+    }
+}
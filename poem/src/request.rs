@@ -7,6 +7,8 @@ use std::{
     task::{Context, Poll},
 };
 
+use bytes::Bytes;
+use futures_util::Stream;
 use http::uri::Scheme;
 use http_body_util::BodyExt;
 use hyper::{body::Incoming, rt::Write as _};
@@ -416,6 +418,32 @@ impl Request {
         self.body
     }
 
+    /// Consume this request and return its body as a bytes stream.
+    ///
+    /// The stream can still be read after the handler has returned, so a
+    /// streaming response built from it is written while the request body is
+    /// being received (full-duplex). This is supported by HTTP/2 and by
+    /// HTTP/1.1 with a chunked or sized request body, but many HTTP/1.1
+    /// clients do not read the response until the whole request has been
+    /// sent, so they only see the response at the end.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use futures_util::TryStreamExt;
+    /// use poem::{endpoint::make, Body};
+    ///
+    /// let uppercase = make(|req| async move {
+    ///     Body::from_bytes_stream(
+    ///         req.into_body_stream()
+    ///             .map_ok(|data| data.to_ascii_uppercase()),
+    ///     )
+    /// });
+    /// ```
+    pub fn into_body_stream(self) -> impl Stream<Item = Result<Bytes, Error>> + Send + 'static {
+        self.body.into_bytes_stream()
+    }
+
     #[inline]
     pub(crate) fn state(&self) -> &RequestState {
         &self.state
@@ -609,12 +637,15 @@ impl RequestBuilder {
     pub fn body(self, body: impl Into<Body>) -> Request {
         Request {
             method: self.method,
-            uri: self.uri,
+            uri: self.uri.clone(),
             version: self.version,
             headers: self.headers,
             extensions: self.extensions,
             body: body.into(),
-            state: Default::default(),
+            state: RequestState {
+                original_uri: self.uri,
+                ..Default::default()
+            },
         }
     }
 
@@ -1,4 +1,7 @@
-use std::fmt::{self, Debug, Formatter};
+use std::{
+    fmt::{self, Debug, Formatter},
+    future::Future,
+};
 
 use bytes::Bytes;
 use headers::HeaderMapExt;
@@ -193,6 +196,26 @@ impl Response {
             .and_then(|value| value.to_str().ok())
     }
 
+    /// Extends the headers of this response with the specified headers.
+    ///
+    /// The existing values of a header are replaced by the new ones, except
+    /// `Set-Cookie` whose values are appended, because each of them sets a
+    /// different cookie.
+    pub fn extend_headers(&mut self, headers: HeaderMap) {
+        let mut name = None;
+        for (key, value) in headers {
+            if let Some(key) = key {
+                if key != header::SET_COOKIE {
+                    self.headers.remove(&key);
+                }
+                name = Some(key);
+            }
+            if let Some(name) = &name {
+                self.headers.append(name, value);
+            }
+        }
+    }
+
     /// Returns the associated version.
     #[inline]
     pub fn version(&self) -> Version {
@@ -247,6 +270,19 @@ impl Response {
         self.body
     }
 
+    /// Consumes this response to return a new response that sends the
+    /// trailers resolved by `trailers` after the body.
+    ///
+    /// See [`Body::with_trailers`] for the protocols that support trailers.
+    #[must_use]
+    pub fn with_trailers<F>(mut self, trailers: F) -> Self
+    where
+        F: Future<Output = HeaderMap> + Send + 'static,
+    {
+        self.body = self.body.with_trailers(trailers);
+        self
+    }
+
     /// Consumes the response returning the head and body parts.
     pub fn into_parts(self) -> (ResponseParts, Body) {
         (
@@ -364,4 +400,27 @@ mod tests {
         assert_eq!(resp.status(), StatusCode::BAD_GATEWAY);
         assert_eq!(resp.body.into_string().await.unwrap(), "abc");
     }
+
+    #[test]
+    fn extend_headers() {
+        let mut resp = Response::builder()
+            .header(header::CONTENT_TYPE, "text/plain")
+            .header(header::SET_COOKIE, "a=1")
+            .finish();
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::CONTENT_TYPE, "text/html".parse().unwrap());
+        headers.append(header::SET_COOKIE, "b=2".parse().unwrap());
+        headers.append(header::SET_COOKIE, "c=3".parse().unwrap());
+        resp.extend_headers(headers);
+
+        assert_eq!(resp.content_type(), Some("text/html"));
+        assert_eq!(
+            resp.headers()
+                .get_all(header::SET_COOKIE)
+                .iter()
+                .collect::<Vec<_>>(),
+            ["a=1", "b=2", "c=3"]
+        );
+    }
 }
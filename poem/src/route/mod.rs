@@ -7,7 +7,8 @@ mod router_method;
 mod router_scheme;
 
 pub(crate) use internal::radix_tree::PathParams;
-pub use router::{PathPattern, Route};
+pub(crate) use router::MethodFallbacks;
+pub use router::{PathPattern, Route, TrailingSlashPolicy};
 #[allow(unreachable_pub)]
 pub use router_domain::RouteDomain;
 #[allow(unreachable_pub)]
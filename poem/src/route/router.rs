@@ -1,18 +1,64 @@
-use std::{str::FromStr, sync::Arc};
+use std::{collections::HashMap, str::FromStr, sync::Arc};
 
 use regex::Regex;
 
 use crate::{
     endpoint::BoxEndpoint,
-    error::{NotFoundError, ParsePathError, RouteError},
-    http::{uri::PathAndQuery, Uri},
+    error::{ErrorFormat, NotFoundError, ParsePathError, RouteError},
+    http::{uri::PathAndQuery, Method, Uri},
     route::{check_result, internal::radix_tree::RadixTree},
-    Endpoint, EndpointExt, IntoEndpoint, IntoResponse, Request, Response, Result,
+    web::Redirect,
+    Endpoint, EndpointExt, Error, IntoEndpoint, IntoResponse, Request, Response, Result,
 };
 
 #[derive(Debug, Clone, Copy)]
 struct PathPrefix(usize);
 
+type MethodFallbackMap = HashMap<Method, Arc<BoxEndpoint<'static>>>;
+
+/// The method fallbacks of the routes that the request has passed through,
+/// the innermost one is the last.
+#[derive(Clone, Default)]
+pub(crate) struct MethodFallbacks(Vec<Arc<MethodFallbackMap>>);
+
+impl MethodFallbacks {
+    /// Calls the method fallback for the request, or returns the specified
+    /// error if there is none.
+    pub(crate) async fn call(req: Request, err: impl FnOnce() -> Error) -> Result<Response> {
+        let method = req.method().clone();
+        let fallbacks = req.data::<MethodFallbacks>().and_then(|fallbacks| {
+            fallbacks
+                .0
+                .iter()
+                .rev()
+                .find(|map| map.contains_key(&method))
+                .cloned()
+        });
+        match fallbacks {
+            Some(map) => map[&method].call(req).await,
+            None => Err(err()),
+        }
+    }
+}
+
+/// Determines how a [`Route`] handles the requests whose paths only differ
+/// from a registered path by a trailing slash.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum TrailingSlashPolicy {
+    /// `/a` and `/a/` are different paths, the requests of the path that is
+    /// not registered are not found.
+    #[default]
+    Strict,
+
+    /// The requests of `/a/` are handled by the endpoint of `/a` if only `/a`
+    /// is registered, and vice versa.
+    MergeBoth,
+
+    /// The requests of `/a/` are redirected to `/a` with `308 Permanent
+    /// Redirect` if only `/a` is registered, and vice versa.
+    RedirectToCanonical,
+}
+
 /// Routing object
 ///
 /// You can match the full path or wildcard path, and use the
@@ -131,6 +177,9 @@ struct PathPrefix(usize);
 #[derive(Default)]
 pub struct Route {
     tree: RadixTree<BoxEndpoint<'static>>,
+    error_format: Option<ErrorFormat>,
+    method_fallbacks: Arc<MethodFallbackMap>,
+    trailing_slash: TrailingSlashPolicy,
 }
 
 impl Route {
@@ -226,6 +275,127 @@ impl Route {
         self.internal_nest(&normalize_path(path.as_ref()), ep, false)
     }
 
+    /// Sets the format of the errors that occurred while parsing the
+    /// requests in this route and all nested routes.
+    ///
+    /// By default, these errors are rendered as plain text. See
+    /// [`ErrorFormat`] for more details.
+    #[must_use]
+    pub fn error_format(self, error_format: ErrorFormat) -> Self {
+        Self {
+            error_format: Some(error_format),
+            ..self
+        }
+    }
+
+    /// Sets how the trailing slashes of the paths in this route are
+    /// handled, the default is [`TrailingSlashPolicy::Strict`].
+    ///
+    /// It only applies to the paths that are registered in this route, the
+    /// nested routes have their own policies, so that different subtrees can
+    /// behave differently.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::{
+    ///     get, handler,
+    ///     http::{header, StatusCode},
+    ///     test::TestClient,
+    ///     Route, TrailingSlashPolicy,
+    /// };
+    ///
+    /// #[handler]
+    /// fn index() -> &'static str {
+    ///     "hello"
+    /// }
+    ///
+    /// let app = Route::new().at("/a", get(index)).nest(
+    ///     "/api",
+    ///     Route::new()
+    ///         .at("/users", get(index))
+    ///         .trailing_slash(TrailingSlashPolicy::RedirectToCanonical),
+    /// );
+    /// let cli = TestClient::new(app);
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// cli.get("/a/")
+    ///     .send()
+    ///     .await
+    ///     .assert_status(StatusCode::NOT_FOUND);
+    ///
+    /// let resp = cli.get("/api/users/").query("page", &2).send().await;
+    /// resp.assert_status(StatusCode::PERMANENT_REDIRECT);
+    /// resp.assert_header(header::LOCATION, "/api/users?page=2");
+    /// # });
+    /// ```
+    #[must_use]
+    pub fn trailing_slash(self, policy: TrailingSlashPolicy) -> Self {
+        Self {
+            trailing_slash: policy,
+            ..self
+        }
+    }
+
+    /// Sets the endpoint for the requests with the specified method that do
+    /// not match any path, or match a path that does not allow the method.
+    ///
+    /// It replaces the `NOT FOUND` and `METHOD NOT ALLOWED` responses of this
+    /// route and all nested routes, which is useful for answering all
+    /// unmatched `OPTIONS` requests for example. The method fallbacks of a
+    /// nested route take precedence.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::{
+    ///     get, handler,
+    ///     http::{Method, StatusCode},
+    ///     test::TestClient,
+    ///     Route,
+    /// };
+    ///
+    /// #[handler]
+    /// fn index() -> &'static str {
+    ///     "hello"
+    /// }
+    ///
+    /// #[handler]
+    /// fn options() -> StatusCode {
+    ///     StatusCode::NO_CONTENT
+    /// }
+    ///
+    /// let app = Route::new()
+    ///     .at("/", get(index))
+    ///     .method_fallback(Method::OPTIONS, options);
+    /// let cli = TestClient::new(app);
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// cli.options("/")
+    ///     .send()
+    ///     .await
+    ///     .assert_status(StatusCode::NO_CONTENT);
+    /// cli.options("/a")
+    ///     .send()
+    ///     .await
+    ///     .assert_status(StatusCode::NO_CONTENT);
+    /// cli.post("/")
+    ///     .send()
+    ///     .await
+    ///     .assert_status(StatusCode::METHOD_NOT_ALLOWED);
+    /// # });
+    /// ```
+    #[must_use]
+    pub fn method_fallback<E>(mut self, method: Method, ep: E) -> Self
+    where
+        E: IntoEndpoint,
+        E::Endpoint: 'static,
+    {
+        Arc::make_mut(&mut self.method_fallbacks)
+            .insert(method, Arc::new(ep.map_to_response().boxed()));
+        self
+    }
+
     fn internal_nest<E>(mut self, path: &str, ep: E, strip: bool) -> Result<Self, RouteError>
     where
         E: IntoEndpoint,
@@ -332,7 +502,35 @@ impl Endpoint for Route {
     type Output = Response;
 
     async fn call(&self, mut req: Request) -> Result<Self::Output> {
-        match self.tree.matches(req.uri().path()) {
+        if !self.method_fallbacks.is_empty() {
+            let mut fallbacks = req.data::<MethodFallbacks>().cloned().unwrap_or_default();
+            fallbacks.0.push(self.method_fallbacks.clone());
+            req.set_data(fallbacks);
+        }
+
+        let path = req.uri().path();
+        let matches = match self.tree.matches(path) {
+            Some(matches) => Some(matches),
+            None => match (self.trailing_slash, toggle_trailing_slash(path)) {
+                (TrailingSlashPolicy::MergeBoth, Some(path)) => self.tree.matches(&path),
+                (TrailingSlashPolicy::RedirectToCanonical, Some(path))
+                    if self.tree.matches(&path).is_some() =>
+                {
+                    // the original path has the same trailing slash as the path of this route
+                    let original_uri = req.original_uri();
+                    let mut location = toggle_trailing_slash(original_uri.path())
+                        .unwrap_or_else(|| original_uri.path().to_string());
+                    if let Some(query) = original_uri.query() {
+                        location.push('?');
+                        location.push_str(query);
+                    }
+                    return Ok(Redirect::permanent(location).into_response());
+                }
+                _ => None,
+            },
+        };
+
+        match matches {
             Some(matches) => {
                 req.state_mut().match_params.extend(matches.params);
 
@@ -363,6 +561,9 @@ impl Endpoint for Route {
                         Ok(res)
                     }
                     Err(mut err) => {
+                        if let Some(error_format) = &self.error_format {
+                            err = error_format.apply(err);
+                        }
                         if err.data::<PathPattern>().is_none() {
                             err.set_data(pattern);
                         }
@@ -370,7 +571,7 @@ impl Endpoint for Route {
                     }
                 }
             }
-            None => Err(NotFoundError.into()),
+            None => MethodFallbacks::call(req, || NotFoundError.into()).await,
         }
     }
 }
@@ -384,10 +585,19 @@ fn normalize_path(path: &str) -> String {
     path
 }
 
+/// Adds the trailing slash to the path, or removes it.
+fn toggle_trailing_slash(path: &str) -> Option<String> {
+    match path.strip_suffix('/') {
+        Some("") => None,
+        Some(path) => Some(path.to_string()),
+        None => Some(format!("{path}/")),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use futures_util::lock::Mutex;
-    use http::StatusCode;
+    use http::{header, StatusCode};
 
     use super::*;
     use crate::{endpoint::make_sync, handler, test::TestClient, Error};
@@ -415,6 +625,52 @@ mod tests {
             .unwrap()
     }
 
+    #[tokio::test]
+    async fn trailing_slash() {
+        let r = Route::new()
+            .at("/a", h)
+            .at("/b/", h)
+            .nest(
+                "/merge",
+                Route::new()
+                    .at("/c", h)
+                    .at("/d/:id/", h)
+                    .trailing_slash(TrailingSlashPolicy::MergeBoth),
+            )
+            .nest(
+                "/redirect",
+                Route::new()
+                    .at("/e", h)
+                    .trailing_slash(TrailingSlashPolicy::RedirectToCanonical),
+            );
+        let cli = TestClient::new(r);
+
+        cli.get("/a/")
+            .send()
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+        cli.get("/b")
+            .send()
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+
+        cli.get("/merge/c/").send().await.assert_text("/c/").await;
+        cli.get("/merge/d/1").send().await.assert_text("/d/1").await;
+        cli.get("/merge/x/")
+            .send()
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+
+        cli.get("/redirect/e").send().await.assert_text("/e").await;
+        let resp = cli.get("/redirect/e/").query("a", &1).send().await;
+        resp.assert_status(StatusCode::PERMANENT_REDIRECT);
+        resp.assert_header(header::LOCATION, "/redirect/e?a=1");
+        cli.get("/redirect/x/")
+            .send()
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+    }
+
     #[tokio::test]
     async fn nested() {
         let r = Route::new().nest(
@@ -784,4 +1040,97 @@ mod tests {
             "/nest_no_strip1/nest_no_strip2/:id"
         );
     }
+
+    #[tokio::test]
+    async fn error_format() {
+        #[derive(serde::Deserialize)]
+        struct Params {
+            n: i32,
+        }
+
+        #[handler(internal)]
+        fn index(crate::web::Query(params): crate::web::Query<Params>) -> String {
+            params.n.to_string()
+        }
+
+        let app = Route::new()
+            .nest("/a", Route::new().at("/b", index))
+            .at("/c", index)
+            .error_format(ErrorFormat::new(|details| {
+                format!(
+                    "{}: {}",
+                    details.code,
+                    details.field.as_deref().unwrap_or("-")
+                )
+                .with_status(StatusCode::UNPROCESSABLE_ENTITY)
+                .into_response()
+            }));
+        let cli = TestClient::new(app);
+
+        let resp = cli.get("/a/b").query("m", &1).send().await;
+        resp.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+        resp.assert_text("invalid_query: n").await;
+
+        cli.get("/c")
+            .query("n", &1)
+            .send()
+            .await
+            .assert_text("1")
+            .await;
+
+        cli.get("/d")
+            .send()
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn method_fallback() {
+        let app = Route::new()
+            .at("/a", crate::get(make_sync(|_| "a")))
+            .nest(
+                "/b",
+                Route::new()
+                    .at("/c", crate::get(make_sync(|_| "c")))
+                    .method_fallback(Method::DELETE, make_sync(|_| "inner delete")),
+            )
+            .method_fallback(Method::OPTIONS, make_sync(|_| "options"))
+            .method_fallback(Method::DELETE, make_sync(|_| "delete"));
+        let cli = TestClient::new(app);
+
+        cli.get("/a").send().await.assert_text("a").await;
+        cli.options("/a").send().await.assert_text("options").await;
+        cli.options("/x").send().await.assert_text("options").await;
+        cli.options("/b/c")
+            .send()
+            .await
+            .assert_text("options")
+            .await;
+        cli.delete("/a").send().await.assert_text("delete").await;
+        cli.delete("/b/c")
+            .send()
+            .await
+            .assert_text("inner delete")
+            .await;
+        cli.delete("/b/x")
+            .send()
+            .await
+            .assert_text("inner delete")
+            .await;
+        cli.post("/a")
+            .send()
+            .await
+            .assert_status(StatusCode::METHOD_NOT_ALLOWED);
+        cli.get("/x")
+            .send()
+            .await
+            .assert_status(StatusCode::NOT_FOUND);
+
+        // the fallbacks can be added while they are shared
+        let route = Route::new().method_fallback(Method::OPTIONS, make_sync(|_| "options"));
+        let shared = route.method_fallbacks.clone();
+        let cli = TestClient::new(route.method_fallback(Method::DELETE, make_sync(|_| "delete")));
+        cli.delete("/").send().await.assert_text("delete").await;
+        assert_eq!(shared.len(), 1);
+    }
 }
@@ -3,8 +3,8 @@ use std::future::Future;
 use futures_util::{future::Either, FutureExt};
 
 use crate::{
-    endpoint::BoxEndpoint, error::MethodNotAllowedError, http::Method, Endpoint, EndpointExt,
-    IntoEndpoint, Request, Response, Result,
+    endpoint::BoxEndpoint, error::MethodNotAllowedError, http::Method, route::MethodFallbacks,
+    Endpoint, EndpointExt, IntoEndpoint, Request, Response, Result,
 };
 
 /// Routing object for HTTP methods
@@ -190,7 +190,9 @@ impl Endpoint for RouteMethod {
                         .boxed(),
                     ))
                 } else {
-                    Either::Right(Either::Right(async { Err(MethodNotAllowedError.into()) }))
+                    Either::Right(Either::Right(MethodFallbacks::call(req, || {
+                        MethodNotAllowedError.into()
+                    })))
                 }
             }
         }
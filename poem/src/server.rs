@@ -11,21 +11,23 @@ use std::{
     task::{Context, Poll},
 };
 
-use http::uri::Scheme;
-use hyper::body::Incoming;
+use bytes::Bytes;
+use http::{header, uri::Scheme, HeaderValue, Version};
+use hyper::body::{Frame, Incoming, SizeHint};
 use hyper_util::server::conn::auto;
 use pin_project_lite::pin_project;
 use tokio::{
     io::{AsyncRead, AsyncWrite, ReadBuf, Result as IoResult},
     sync::{oneshot, Notify},
-    time::Duration,
+    time::{Duration, Instant, Sleep},
 };
 use tokio_util::sync::CancellationToken;
 
 use crate::{
+    body::BoxBody,
     endpoint::{DynEndpoint, ToDynEndpoint},
     listener::{Acceptor, AcceptorExt, Listener},
-    web::{LocalAddr, RemoteAddr},
+    web::{supports_trailers, LocalAddr, RemoteAddr},
     Endpoint, EndpointExt, IntoEndpoint, Response,
 };
 
@@ -40,6 +42,8 @@ pub struct Server<L, A> {
     listener: Either<L, A>,
     name: Option<String>,
     idle_timeout: Option<Duration>,
+    request_timeout: Option<Duration>,
+    http10_keep_alive: bool,
 }
 
 impl<L: Listener> Server<L, Infallible> {
@@ -49,6 +53,8 @@ impl<L: Listener> Server<L, Infallible> {
             listener: Either::Listener(listener),
             name: None,
             idle_timeout: None,
+            request_timeout: None,
+            http10_keep_alive: true,
         }
     }
 }
@@ -60,6 +66,8 @@ impl<A: Acceptor> Server<Infallible, A> {
             listener: Either::Acceptor(acceptor),
             name: None,
             idle_timeout: None,
+            request_timeout: None,
+            http10_keep_alive: true,
         }
     }
 }
@@ -89,6 +97,44 @@ where
         }
     }
 
+    /// Specify the maximum duration of a request, including reading the
+    /// request body, calling the endpoint and writing the response body.
+    ///
+    /// The deadline starts when the request headers have been received. If it
+    /// is exceeded, the connection will be aborted, which protects the server
+    /// from clients that upload the body very slowly.
+    ///
+    /// NOTE: Long-lived responses such as Server-Sent Events are also
+    /// terminated at the deadline, upgraded connections are not affected.
+    #[must_use]
+    pub fn request_timeout(self, timeout: Duration) -> Self {
+        Self {
+            request_timeout: Some(timeout),
+            ..self
+        }
+    }
+
+    /// Specify whether the `HTTP/1.0` connections can be kept alive.
+    ///
+    /// An `HTTP/1.0` connection is kept alive only if the request has the
+    /// `Connection: keep-alive` header, and the response has it too,
+    /// otherwise it is closed after the response. An `HTTP/1.1` connection is
+    /// kept alive unless the request or the response has the `Connection:
+    /// close` header.
+    ///
+    /// If it is `false`, the `HTTP/1.0` connections are always closed after
+    /// the response, which is useful for the legacy clients that do not
+    /// handle keep-alive correctly.
+    ///
+    /// Default is `true`.
+    #[must_use]
+    pub fn http10_keep_alive(self, enable: bool) -> Self {
+        Self {
+            http10_keep_alive: enable,
+            ..self
+        }
+    }
+
     /// Run this server.
     pub async fn run<E>(self, ep: E) -> IoResult<()>
     where
@@ -115,8 +161,15 @@ where
             listener,
             name,
             idle_timeout,
+            request_timeout,
+            http10_keep_alive,
         } = self;
         let name = name.as_deref();
+        let config = ConnectionConfig {
+            idle_timeout,
+            request_timeout,
+            http10_keep_alive,
+        };
         let alive_connections = Arc::new(AtomicUsize::new(0));
         let notify = Arc::new(Notify::new());
         let timeout_token = CancellationToken::new();
@@ -166,7 +219,7 @@ where
                         let server_graceful_shutdown_token = server_graceful_shutdown_token.clone();
 
                         tokio::spawn(async move {
-                            let serve_connection = serve_connection(socket, local_addr, remote_addr, scheme, ep, server_graceful_shutdown_token.clone(), idle_timeout);
+                            let serve_connection = serve_connection(socket, local_addr, remote_addr, scheme, ep, server_graceful_shutdown_token.clone(), config);
 
                             if timeout.is_some() {
                                 tokio::select! {
@@ -312,6 +365,44 @@ impl<T> ClosingInactiveConnection<T> {
     }
 }
 
+#[derive(Clone, Copy)]
+struct ConnectionConfig {
+    idle_timeout: Option<Duration>,
+    request_timeout: Option<Duration>,
+    http10_keep_alive: bool,
+}
+
+struct DeadlineBody {
+    body: BoxBody,
+    sleep: Pin<Box<Sleep>>,
+}
+
+impl hyper::body::Body for DeadlineBody {
+    type Data = Bytes;
+    type Error = io::Error;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        if self.sleep.as_mut().poll(cx).is_ready() {
+            return Poll::Ready(Some(Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "request timeout",
+            ))));
+        }
+        Pin::new(&mut self.body).poll_frame(cx)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.body.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.body.size_hint()
+    }
+}
+
 async fn serve_connection(
     socket: impl AsyncRead + AsyncWrite + Send + Unpin + 'static,
     local_addr: LocalAddr,
@@ -319,7 +410,7 @@ async fn serve_connection(
     scheme: Scheme,
     ep: Arc<dyn DynEndpoint<Output = Response>>,
     server_graceful_shutdown_token: CancellationToken,
-    idle_connection_close_timeout: Option<Duration>,
+    config: ConnectionConfig,
 ) {
     let connection_shutdown_token = CancellationToken::new();
 
@@ -332,16 +423,44 @@ async fn serve_connection(
             let remote_addr = remote_addr.clone();
             let scheme = scheme.clone();
             async move {
-                Ok::<http::Response<_>, Infallible>(
-                    ep.get_response((req, local_addr, remote_addr, scheme).into())
-                        .await
-                        .into(),
-                )
+                let accept_trailers = supports_trailers(req.version(), req.headers());
+                let force_close = !config.http10_keep_alive && req.version() == Version::HTTP_10;
+                let deadline = config
+                    .request_timeout
+                    .map(|timeout| Instant::now() + timeout);
+                let fut = ep.get_response((req, local_addr, remote_addr.clone(), scheme).into());
+                let mut resp = match deadline {
+                    Some(deadline) => match tokio::time::timeout_at(deadline, fut).await {
+                        Ok(resp) => resp,
+                        Err(_) => {
+                            tracing::debug!(remote_addr = %remote_addr, "request timeout");
+                            return Err(io::Error::new(io::ErrorKind::TimedOut, "request timeout"));
+                        }
+                    },
+                    None => fut.await,
+                };
+                if force_close {
+                    // hyper closes the connection after the response
+                    resp.headers_mut()
+                        .insert(header::CONNECTION, HeaderValue::from_static("close"));
+                }
+                if !accept_trailers {
+                    let body = resp.take_body().without_trailers();
+                    resp.set_body(body);
+                }
+                if let Some(deadline) = deadline {
+                    let body = resp.take_body();
+                    resp.set_body(BoxBody::new(DeadlineBody {
+                        body: body.into(),
+                        sleep: Box::pin(tokio::time::sleep_until(deadline)),
+                    }));
+                }
+                Ok::<http::Response<_>, io::Error>(resp.into())
             }
         }
     });
 
-    let socket = match idle_connection_close_timeout {
+    let socket = match config.idle_timeout {
         Some(timeout) => {
             tokio_util::either::Either::Left(ClosingInactiveConnection::new(socket, timeout, {
                 let connection_shutdown_token = connection_shutdown_token.clone();
@@ -377,3 +496,244 @@ async fn serve_connection(
     // Continue awaiting after graceful-shutdown is initiated to handle existed requests.
     let _ = conn.await;
 }
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpStream,
+    };
+
+    use super::*;
+    use crate::{
+        handler,
+        listener::{Acceptor, TcpListener},
+        web::Trailers,
+        Body,
+    };
+
+    #[tokio::test]
+    async fn request_timeout() {
+        #[handler(internal)]
+        fn index(body: String) -> String {
+            body
+        }
+
+        let acceptor = TcpListener::bind("127.0.0.1:0")
+            .into_acceptor()
+            .await
+            .unwrap();
+        let addr = *acceptor.local_addr()[0].as_socket_addr().unwrap();
+        tokio::spawn(
+            Server::new_with_acceptor(acceptor)
+                .request_timeout(Duration::from_millis(300))
+                .run(index),
+        );
+
+        // a fast request is not affected
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(b"POST / HTTP/1.1\r\nhost: localhost\r\ncontent-length: 3\r\nconnection: close\r\n\r\nabc")
+            .await
+            .unwrap();
+        let mut resp = String::new();
+        stream.read_to_string(&mut resp).await.unwrap();
+        assert!(resp.starts_with("HTTP/1.1 200 OK"));
+        assert!(resp.ends_with("abc"));
+
+        // a trickling upload is aborted at the deadline
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(b"POST / HTTP/1.1\r\nhost: localhost\r\ncontent-length: 100\r\n\r\n")
+            .await
+            .unwrap();
+        let start = Instant::now();
+        let (mut reader, mut writer) = stream.split();
+        let trickle = async {
+            loop {
+                if writer.write_all(b"a").await.is_err() {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        };
+        let mut resp = Vec::new();
+        tokio::select! {
+            _ = trickle => {}
+            _ = reader.read_to_end(&mut resp) => {}
+        }
+        assert!(resp.is_empty());
+        assert!(start.elapsed() < Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn full_duplex() {
+        use futures_util::TryStreamExt;
+
+        let uppercase = crate::endpoint::make(|req| async move {
+            Body::from_bytes_stream(
+                req.into_body_stream()
+                    .map_ok(|data| data.to_ascii_uppercase()),
+            )
+        });
+
+        let acceptor = TcpListener::bind("127.0.0.1:0")
+            .into_acceptor()
+            .await
+            .unwrap();
+        let addr = *acceptor.local_addr()[0].as_socket_addr().unwrap();
+        tokio::spawn(Server::new_with_acceptor(acceptor).run(uppercase));
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(b"POST / HTTP/1.1\r\nhost: localhost\r\ntransfer-encoding: chunked\r\n\r\n3\r\nabc\r\n")
+            .await
+            .unwrap();
+
+        // the first chunk is echoed before the request body is finished
+        let mut resp = Vec::new();
+        let mut buf = [0; 1024];
+        while !resp.windows(3).any(|w| w == b"ABC") {
+            let n = tokio::time::timeout(Duration::from_secs(5), stream.read(&mut buf))
+                .await
+                .unwrap()
+                .unwrap();
+            assert!(n > 0);
+            resp.extend_from_slice(&buf[..n]);
+        }
+        assert!(resp.starts_with(b"HTTP/1.1 200 OK"));
+
+        stream.write_all(b"3\r\ndef\r\n0\r\n\r\n").await.unwrap();
+        let mut resp = Vec::new();
+        while !resp.ends_with(b"0\r\n\r\n") {
+            let n = tokio::time::timeout(Duration::from_secs(5), stream.read(&mut buf))
+                .await
+                .unwrap()
+                .unwrap();
+            assert!(n > 0);
+            resp.extend_from_slice(&buf[..n]);
+        }
+        assert!(resp.windows(3).any(|w| w == b"DEF"));
+    }
+
+    #[tokio::test]
+    async fn chunked_trailers() {
+        #[handler(internal)]
+        fn index(trailers: Trailers, body: String) -> String {
+            let checksum = trailers.get("checksum").unwrap();
+            format!("{body}:{}", checksum.to_str().unwrap())
+        }
+
+        let acceptor = TcpListener::bind("127.0.0.1:0")
+            .into_acceptor()
+            .await
+            .unwrap();
+        let addr = *acceptor.local_addr()[0].as_socket_addr().unwrap();
+        tokio::spawn(Server::new_with_acceptor(acceptor).run(index));
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(b"POST / HTTP/1.1\r\nhost: localhost\r\ntransfer-encoding: chunked\r\ntrailer: checksum\r\nconnection: close\r\n\r\n3\r\nabc\r\n3\r\ndef\r\n0\r\nchecksum: 123\r\n\r\n")
+            .await
+            .unwrap();
+        let mut resp = String::new();
+        tokio::time::timeout(Duration::from_secs(5), stream.read_to_string(&mut resp))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(resp.starts_with("HTTP/1.1 200 OK"));
+        assert!(resp.ends_with("abcdef:123"));
+    }
+
+    #[tokio::test]
+    async fn keep_alive() {
+        #[handler(internal)]
+        fn index() -> &'static str {
+            "hello"
+        }
+
+        async fn start(http10_keep_alive: bool) -> SocketAddr {
+            let acceptor = TcpListener::bind("127.0.0.1:0")
+                .into_acceptor()
+                .await
+                .unwrap();
+            let addr = *acceptor.local_addr()[0].as_socket_addr().unwrap();
+            tokio::spawn(
+                Server::new_with_acceptor(acceptor)
+                    .http10_keep_alive(http10_keep_alive)
+                    .run(index),
+            );
+            addr
+        }
+
+        async fn request(stream: &mut TcpStream, req: &str) -> String {
+            stream.write_all(req.as_bytes()).await.unwrap();
+            let mut resp = Vec::new();
+            let mut buf = [0; 1024];
+            while !resp.ends_with(b"hello") {
+                let n = tokio::time::timeout(Duration::from_secs(5), stream.read(&mut buf))
+                    .await
+                    .unwrap()
+                    .unwrap();
+                assert!(n > 0);
+                resp.extend_from_slice(&buf[..n]);
+            }
+            String::from_utf8(resp).unwrap().to_ascii_lowercase()
+        }
+
+        async fn is_closed(stream: &mut TcpStream) -> bool {
+            let mut buf = [0; 1];
+            matches!(
+                tokio::time::timeout(Duration::from_millis(200), stream.read(&mut buf)).await,
+                Ok(Ok(0))
+            )
+        }
+
+        let addr = start(true).await;
+
+        // HTTP/1.0 is closed by default
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let resp = request(&mut stream, "GET / HTTP/1.0\r\n\r\n").await;
+        assert!(resp.starts_with("http/1.0 200 ok"));
+        assert!(is_closed(&mut stream).await);
+
+        // HTTP/1.0 is kept alive if requested
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        for _ in 0..2 {
+            let resp = request(
+                &mut stream,
+                "GET / HTTP/1.0\r\nconnection: keep-alive\r\n\r\n",
+            )
+            .await;
+            assert!(resp.contains("connection: keep-alive\r\n"));
+        }
+        assert!(!is_closed(&mut stream).await);
+
+        // HTTP/1.1 is kept alive unless requested to close
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        request(&mut stream, "GET / HTTP/1.1\r\nhost: localhost\r\n\r\n").await;
+        assert!(!is_closed(&mut stream).await);
+        request(
+            &mut stream,
+            "GET / HTTP/1.1\r\nhost: localhost\r\nconnection: close\r\n\r\n",
+        )
+        .await;
+        assert!(is_closed(&mut stream).await);
+
+        // HTTP/1.0 is always closed if keep-alive is disabled
+        let addr = start(false).await;
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let resp = request(
+            &mut stream,
+            "GET / HTTP/1.0\r\nconnection: keep-alive\r\n\r\n",
+        )
+        .await;
+        assert!(!resp.contains("connection: keep-alive"));
+        assert!(is_closed(&mut stream).await);
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        request(&mut stream, "GET / HTTP/1.1\r\nhost: localhost\r\n\r\n").await;
+        assert!(!is_closed(&mut stream).await);
+    }
+}
@@ -0,0 +1,376 @@
+use http::{header, HeaderMap};
+use mime::Mime;
+
+use crate::{
+    web::prefer::{is_token_char, split_unquoted},
+    FromRequest, Request, RequestBody, Result,
+};
+
+/// A media range of the [`AcceptMedia`] extractor.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MediaRange {
+    /// The media range with its parameters, such as `text/*` or
+    /// `text/html;level=1`, without the weight and the extensions.
+    pub mime: Mime,
+    /// The weight in thousandths, `1000` is `q=1` and `0` means not
+    /// acceptable.
+    pub quality: u16,
+}
+
+impl MediaRange {
+    /// Returns how specific the range is, a range that matches fewer media
+    /// types is more specific.
+    fn specificity(&self) -> usize {
+        if self.mime.type_() == mime::STAR {
+            0
+        } else if self.mime.subtype() == mime::STAR {
+            1
+        } else {
+            2 + self.mime.params().count()
+        }
+    }
+
+    fn matches(&self, mime: &Mime) -> bool {
+        (self.mime.type_() == mime::STAR || self.mime.type_() == mime.type_())
+            && (self.mime.subtype() == mime::STAR || self.mime.subtype() == mime.subtype())
+            && self.mime.params().all(|(name, value)| {
+                mime.get_param(name)
+                    .is_some_and(|v| v.as_str().eq_ignore_ascii_case(value.as_str()))
+            })
+    }
+}
+
+/// An extractor that parses the media ranges of the `Accept` header with
+/// their weights, as defined in
+/// [RFC9110](https://datatracker.ietf.org/doc/html/rfc9110#section-12.5.1),
+/// and picks the best representation for the request.
+///
+/// The malformed media ranges are ignored. If the header is missing or has no
+/// valid media ranges, any media type is acceptable.
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     get, handler, http::StatusCode, test::TestClient, web::AcceptMedia, IntoResponse, Response,
+///     Route,
+/// };
+///
+/// #[handler]
+/// fn index(accept: AcceptMedia) -> Response {
+///     match accept.best_match(&["application/json", "text/html"]) {
+///         Some("application/json") => {
+///             r#"{"message":"hello"}"#.with_content_type("application/json").into_response()
+///         }
+///         Some(_) => "<p>hello</p>"
+///             .with_content_type("text/html")
+///             .into_response(),
+///         None => StatusCode::NOT_ACCEPTABLE.into_response(),
+///     }
+/// }
+///
+/// let app = Route::new().at("/", get(index));
+/// let cli = TestClient::new(app);
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// cli.get("/")
+///     .header("Accept", "text/*, application/json;q=0.5")
+///     .send()
+///     .await
+///     .assert_text("<p>hello</p>")
+///     .await;
+/// cli.get("/")
+///     .header("Accept", "image/png")
+///     .send()
+///     .await
+///     .assert_status(StatusCode::NOT_ACCEPTABLE);
+/// # });
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct AcceptMedia(Vec<MediaRange>);
+
+impl AcceptMedia {
+    /// Parses the `Accept` headers.
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let mut ranges = headers
+            .get_all(header::ACCEPT)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| split_unquoted(value, ','))
+            .map(str::trim)
+            .filter_map(parse_media_range)
+            .collect::<Vec<_>>();
+        // the sort is stable, so the ranges of the same weight and
+        // specificity are kept in the order of the header
+        ranges.sort_by(|a, b| {
+            b.quality
+                .cmp(&a.quality)
+                .then_with(|| b.specificity().cmp(&a.specificity()))
+        });
+        Self(ranges)
+    }
+
+    /// Returns the media ranges, in the descending order of the weight and
+    /// then the specificity.
+    pub fn ranges(&self) -> &[MediaRange] {
+        &self.0
+    }
+
+    /// Returns the weight in thousandths of the specified media type, which
+    /// is the one of the most specific media range that matches it, or `0` if
+    /// it is not acceptable.
+    pub fn quality(&self, media_type: &str) -> u16 {
+        media_type
+            .parse()
+            .ok()
+            .and_then(|mime| self.find(&mime))
+            .map_or(0, |(quality, _)| quality)
+    }
+
+    /// Returns the best of the specified media types for the request, or
+    /// `None` if none of them is acceptable.
+    ///
+    /// The media type with the highest weight is picked. If several have the
+    /// same weight, the one that matches the most specific media range is
+    /// picked, and then the first one in the specified order, so they should
+    /// be in the order of the preference of the server.
+    pub fn best_match<'b>(&self, media_types: &[&'b str]) -> Option<&'b str> {
+        let mut best = None;
+        for media_type in media_types {
+            let Some((quality, specificity)) = media_type
+                .parse()
+                .ok()
+                .and_then(|mime: Mime| self.find(&mime))
+            else {
+                continue;
+            };
+            if quality == 0 {
+                continue;
+            }
+            if best.map_or(true, |(_, best_quality, best_specificity)| {
+                (quality, specificity) > (best_quality, best_specificity)
+            }) {
+                best = Some((*media_type, quality, specificity));
+            }
+        }
+        best.map(|(media_type, _, _)| media_type)
+    }
+
+    /// Returns the weight and the specificity of the most specific media
+    /// range that matches the media type.
+    fn find(&self, mime: &Mime) -> Option<(u16, usize)> {
+        if self.0.is_empty() {
+            return Some((1000, 0));
+        }
+        self.0
+            .iter()
+            .filter(|range| range.matches(mime))
+            .map(|range| (range.quality, range.specificity()))
+            .reduce(|a, b| if b.1 > a.1 { b } else { a })
+    }
+}
+
+/// Parses a `qvalue`, which has at most three digits after the decimal point
+/// and is not greater than `1`.
+fn parse_quality(value: &str) -> Option<u16> {
+    let (int, frac) = value.split_once('.').unwrap_or((value, ""));
+    if frac.len() > 3 || !frac.bytes().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let frac = format!("{frac:0<3}").parse::<u16>().ok()?;
+    match int {
+        "0" => Some(frac),
+        "1" if frac == 0 => Some(1000),
+        _ => None,
+    }
+}
+
+fn parse_media_range(element: &str) -> Option<MediaRange> {
+    let mime: Mime = element.parse().ok()?;
+    if mime.type_() == mime::STAR && mime.subtype() != mime::STAR {
+        return None;
+    }
+
+    // the parameters after the weight are the extensions, which are ignored
+    let mut quality = 1000;
+    let mut params = Vec::new();
+    for (name, value) in mime.params() {
+        if name.as_str().eq_ignore_ascii_case("q") {
+            quality = parse_quality(value.as_str())?;
+            break;
+        }
+        params.push((name, value));
+    }
+
+    let mut range = mime.essence_str().to_string();
+    for (name, value) in params {
+        let value = value.as_str();
+        if !value.is_empty() && value.bytes().all(is_token_char) {
+            range.push_str(&format!(";{name}={value}"));
+        } else {
+            let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+            range.push_str(&format!(";{name}=\"{value}\""));
+        }
+    }
+    Some(MediaRange {
+        mime: range.parse().ok()?,
+        quality,
+    })
+}
+
+impl<'a> FromRequest<'a> for AcceptMedia {
+    async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self> {
+        Ok(Self::from_headers(req.headers()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_header(value: &str) -> AcceptMedia {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::ACCEPT, value.parse().unwrap());
+        AcceptMedia::from_headers(&headers)
+    }
+
+    #[test]
+    fn parse() {
+        let accept = from_header(
+            "text/*;q=0.3, text/plain;q=0.7, text/plain;format=flowed, text/plain;format=fixed;q=0.4, */*;q=0.5, */html, text/html;q=2, text/html;q=0.1234",
+        );
+        let ranges = accept
+            .ranges()
+            .iter()
+            .map(|range| (range.mime.to_string(), range.quality))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ranges,
+            [
+                ("text/plain;format=flowed".to_string(), 1000),
+                ("text/plain".to_string(), 700),
+                ("*/*".to_string(), 500),
+                ("text/plain;format=fixed".to_string(), 400),
+                ("text/*".to_string(), 300),
+            ]
+        );
+
+        // the extensions are ignored, and the commas in the quoted strings are
+        // not separators
+        let accept = from_header(r#"text/html;level="a,b";q=0.5;ext=1, application/json"#);
+        let ranges = accept
+            .ranges()
+            .iter()
+            .map(|range| (range.mime.to_string(), range.quality))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ranges,
+            [
+                ("application/json".to_string(), 1000),
+                (r#"text/html;level="a,b""#.to_string(), 500),
+            ]
+        );
+    }
+
+    #[test]
+    fn quality() {
+        // the example of RFC9110
+        let accept = from_header(
+            "text/*;q=0.3, text/plain;q=0.7, text/plain;format=flowed, text/plain;format=fixed;q=0.4, */*;q=0.5",
+        );
+        for (media_type, quality) in [
+            ("text/plain;format=flowed", 1000),
+            ("text/plain", 700),
+            ("text/html", 300),
+            ("image/jpeg", 500),
+            ("text/plain;format=fixed", 400),
+            ("text/html;level=3", 300),
+            ("invalid", 0),
+        ] {
+            assert_eq!(accept.quality(media_type), quality, "{media_type}");
+        }
+    }
+
+    #[test]
+    fn best_match() {
+        let cases: &[(Option<&str>, &[&str], Option<&str>)] = &[
+            // no header or no valid ranges
+            (
+                None,
+                &["application/json", "text/html"],
+                Some("application/json"),
+            ),
+            (Some("*/html, text"), &["text/html"], Some("text/html")),
+            // the weight
+            (
+                Some("application/json;q=0.5, text/html"),
+                &["application/json", "text/html"],
+                Some("text/html"),
+            ),
+            (
+                Some("text/*"),
+                &["application/json", "text/html"],
+                Some("text/html"),
+            ),
+            (Some("image/png"), &["application/json", "text/html"], None),
+            // the exclusions
+            (
+                Some("*/*, text/html;q=0"),
+                &["text/html", "text/plain"],
+                Some("text/plain"),
+            ),
+            (Some("text/html;q=0, */*"), &["text/html"], None),
+            (Some("*/*;q=0"), &["text/html"], None),
+            // the tie is broken by the specificity, and then by the order
+            (
+                Some("*/*, application/json"),
+                &["text/html", "application/json"],
+                Some("application/json"),
+            ),
+            (
+                Some("*/*"),
+                &["text/html", "application/json"],
+                Some("text/html"),
+            ),
+            (
+                Some("text/*, application/*"),
+                &["text/html", "application/json"],
+                Some("text/html"),
+            ),
+            // the parameters must match
+            (
+                Some("text/html;level=1, */*;q=0.1"),
+                &["text/html", "text/html;level=1"],
+                Some("text/html;level=1"),
+            ),
+            // the case is ignored, and the invalid media types are skipped
+            (
+                Some("TEXT/HTML"),
+                &["invalid", "text/html"],
+                Some("text/html"),
+            ),
+        ];
+        for (header, media_types, expected) in cases {
+            let accept = match header {
+                Some(header) => from_header(header),
+                None => AcceptMedia::default(),
+            };
+            assert_eq!(
+                accept.best_match(media_types),
+                *expected,
+                "{header:?} {media_types:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn extractor() {
+        let req = Request::builder()
+            .header(header::ACCEPT, "text/html")
+            .header(header::ACCEPT, "application/json;q=0.5")
+            .finish();
+        let accept = AcceptMedia::from_request_without_body(&req).await.unwrap();
+        assert_eq!(accept.ranges().len(), 2);
+        assert_eq!(accept.quality("application/json"), 500);
+    }
+}
@@ -0,0 +1,141 @@
+use http::header;
+
+use crate::{error::ParseAuthorizationError, FromRequest, Request, RequestBody, Result};
+
+/// An extractor that parses the `Authorization` header of any scheme into
+/// the scheme and the credentials, as defined in
+/// [RFC9110](https://datatracker.ietf.org/doc/html/rfc9110#section-11.6.2).
+///
+/// The credentials are not decoded, so that the handler can match on the
+/// scheme and parse them, which is useful for the custom schemes. Use
+/// [`TypedHeader`](crate::web::TypedHeader) with
+/// [`headers::Authorization`](crate::web::headers::Authorization) for the
+/// `Basic` and `Bearer` schemes.
+///
+/// A missing header is rejected with `401 Unauthorized`, but without the
+/// `WWW-Authenticate` header, because the challenge depends on the scheme.
+/// The application must add it, for example with
+/// [`EndpointExt::catch_error`](crate::EndpointExt::catch_error).
+///
+/// # Errors
+///
+/// - [`ParseAuthorizationError`]
+///
+/// # Example
+///
+/// ```
+/// use poem::{get, handler, http::StatusCode, test::TestClient, web::Authorization, Route};
+///
+/// #[handler]
+/// fn index(auth: Authorization) -> Result<String, StatusCode> {
+///     if !auth.is_scheme("ApiKey") {
+///         return Err(StatusCode::UNAUTHORIZED);
+///     }
+///     Ok(auth.credentials)
+/// }
+///
+/// let app = Route::new().at("/", get(index));
+/// let cli = TestClient::new(app);
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// cli.get("/")
+///     .header("Authorization", "apikey abc")
+///     .send()
+///     .await
+///     .assert_text("abc")
+///     .await;
+/// cli.get("/")
+///     .send()
+///     .await
+///     .assert_status(StatusCode::UNAUTHORIZED);
+/// # });
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Authorization {
+    /// The authentication scheme, as it is in the header.
+    pub scheme: String,
+    /// The credentials after the scheme, may be empty.
+    pub credentials: String,
+}
+
+impl Authorization {
+    /// Returns `true` if the scheme is the specified one, case-insensitively.
+    pub fn is_scheme(&self, scheme: &str) -> bool {
+        self.scheme.eq_ignore_ascii_case(scheme)
+    }
+}
+
+fn is_token(s: &str) -> bool {
+    !s.is_empty()
+        && s.bytes()
+            .all(|c| c.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&c))
+}
+
+fn parse_authorization(value: &str) -> Option<Authorization> {
+    let value = value.trim_matches([' ', '\t']);
+    let (scheme, credentials) = value.split_once(' ').unwrap_or((value, ""));
+    if !is_token(scheme) {
+        return None;
+    }
+    Some(Authorization {
+        scheme: scheme.to_string(),
+        credentials: credentials.trim_start_matches(' ').to_string(),
+    })
+}
+
+impl<'a> FromRequest<'a> for Authorization {
+    async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self> {
+        let mut values = req.headers().get_all(header::AUTHORIZATION).iter();
+        let value = values.next().ok_or(ParseAuthorizationError::Missing)?;
+        // the credentials are ambiguous if there are multiple headers
+        if values.next().is_some() {
+            return Err(ParseAuthorizationError::Invalid.into());
+        }
+        value
+            .to_str()
+            .ok()
+            .and_then(parse_authorization)
+            .ok_or_else(|| ParseAuthorizationError::Invalid.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn authorization() {
+        let extract = |values: &[&str]| {
+            let mut req = Request::builder();
+            for value in values {
+                req = req.header(header::AUTHORIZATION, *value);
+            }
+            let req = req.finish();
+            async move { Authorization::from_request_without_body(&req).await }
+        };
+
+        let auth = extract(&["Digest  username=\"a\", realm=\"b\""])
+            .await
+            .unwrap();
+        assert!(auth.is_scheme("digest"));
+        assert_eq!(auth.scheme, "Digest");
+        assert_eq!(auth.credentials, "username=\"a\", realm=\"b\"");
+
+        let auth = extract(&["Negotiate"]).await.unwrap();
+        assert_eq!(auth.scheme, "Negotiate");
+        assert_eq!(auth.credentials, "");
+
+        assert_eq!(
+            extract(&[]).await.unwrap_err().status(),
+            StatusCode::UNAUTHORIZED
+        );
+        for values in [&["Bearer a", "Bearer b"][..], &[""], &["(a) b"]] {
+            assert_eq!(
+                extract(values).await.unwrap_err().status(),
+                StatusCode::BAD_REQUEST
+            );
+        }
+    }
+}
@@ -1,10 +1,14 @@
 use std::{
     fmt::{self, Display, Formatter},
+    io::Result as IoResult,
     pin::Pin,
     str::FromStr,
 };
 
-use tokio::io::{AsyncRead, BufReader};
+use async_compression::tokio::write::{BrotliEncoder, DeflateEncoder, GzipEncoder};
+use bytes::Bytes;
+use futures_util::{stream, StreamExt};
+use tokio::io::{AsyncRead, AsyncWriteExt, BufReader};
 
 use crate::{
     http::{header, HeaderValue},
@@ -92,6 +96,86 @@ impl CompressionAlgo {
     }
 }
 
+/// An encoder that flushes the compressed data of each chunk, so that the
+/// streaming responses such as SSE are not delayed by the compression.
+enum FlushEncoder {
+    Brotli(Box<BrotliEncoder<Vec<u8>>>),
+    Deflate(DeflateEncoder<Vec<u8>>),
+    Gzip(GzipEncoder<Vec<u8>>),
+}
+
+macro_rules! with_encoder {
+    ($encoder:expr, $e:ident => $body:expr) => {
+        match $encoder {
+            FlushEncoder::Brotli($e) => $body,
+            FlushEncoder::Deflate($e) => $body,
+            FlushEncoder::Gzip($e) => $body,
+        }
+    };
+}
+
+impl FlushEncoder {
+    fn new(algo: CompressionAlgo, level: Option<CompressionLevel>) -> Self {
+        match algo {
+            CompressionAlgo::BR => FlushEncoder::Brotli(Box::new(BrotliEncoder::with_quality(
+                Vec::new(),
+                level.unwrap_or(CompressionLevel::Fastest),
+            ))),
+            CompressionAlgo::DEFLATE => FlushEncoder::Deflate(DeflateEncoder::with_quality(
+                Vec::new(),
+                level.unwrap_or(CompressionLevel::Default),
+            )),
+            CompressionAlgo::GZIP => FlushEncoder::Gzip(GzipEncoder::with_quality(
+                Vec::new(),
+                level.unwrap_or(CompressionLevel::Default),
+            )),
+        }
+    }
+
+    async fn encode(&mut self, data: &[u8]) -> IoResult<Bytes> {
+        with_encoder!(self, encoder => {
+            encoder.write_all(data).await?;
+            encoder.flush().await?;
+            Ok(std::mem::take(encoder.get_mut()).into())
+        })
+    }
+
+    async fn finish(&mut self) -> IoResult<Bytes> {
+        with_encoder!(self, encoder => {
+            encoder.shutdown().await?;
+            Ok(std::mem::take(encoder.get_mut()).into())
+        })
+    }
+}
+
+/// Compresses the body so that each chunk is sent as soon as it is
+/// produced.
+fn compress_flushed(body: Body, algo: CompressionAlgo, level: Option<CompressionLevel>) -> Body {
+    let chunks = body
+        .into_bytes_stream()
+        .map(Some)
+        .chain(stream::once(async { None }));
+    let encoder = FlushEncoder::new(algo, level);
+
+    Body::from_bytes_stream(
+        stream::unfold(
+            (Box::pin(chunks), encoder),
+            |(mut chunks, mut encoder)| async move {
+                let res = match chunks.next().await? {
+                    Some(Ok(data)) => encoder.encode(&data).await,
+                    Some(Err(err)) => Err(err),
+                    None => encoder.finish().await,
+                };
+                Some((res, (chunks, encoder)))
+            },
+        )
+        .filter(|res| {
+            let is_empty = matches!(res, Ok(data) if data.is_empty());
+            async move { !is_empty }
+        }),
+    )
+}
+
 impl Display for CompressionAlgo {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.as_str())
@@ -101,6 +185,9 @@ impl Display for CompressionAlgo {
 /// Compress the response body with the specified algorithm and set the
 /// `Content-Encoding` header.
 ///
+/// The `text/event-stream` responses are compressed event by event, and each
+/// event is flushed to the client as soon as it is produced.
+///
 /// # Example
 ///
 /// ```
@@ -153,9 +240,16 @@ impl<T: IntoResponse> IntoResponse for Compress<T> {
         );
         resp.headers_mut().remove(header::CONTENT_LENGTH);
 
-        resp.set_body(Body::from_async_read(
-            self.algo.compress(body.into_async_read(), self.level),
-        ));
+        let is_event_stream = resp
+            .content_type()
+            .is_some_and(|content_type| content_type.starts_with("text/event-stream"));
+        if is_event_stream {
+            resp.set_body(compress_flushed(body, self.algo, self.level));
+        } else {
+            resp.set_body(Body::from_async_read(
+                self.algo.compress(body.into_async_read(), self.level),
+            ));
+        }
         resp
     }
 }
@@ -204,4 +298,54 @@ mod tests {
         test_algo(CompressionAlgo::DEFLATE).await;
         test_algo(CompressionAlgo::GZIP).await;
     }
+
+    #[cfg(feature = "sse")]
+    async fn test_event_stream_algo(algo: CompressionAlgo) {
+        use std::{io::Error as IoError, time::Duration};
+
+        use futures_util::{stream, StreamExt};
+
+        use crate::web::sse::{Event, SSE};
+
+        // every event is sent without waiting for the end of the stream
+        let resp = Compress::new(
+            SSE::new(stream::iter([Event::message("hello")]).chain(stream::pending())),
+            algo,
+        )
+        .into_response();
+        let mut chunks = Box::pin(resp.into_body().into_bytes_stream());
+        let chunk = tokio::time::timeout(Duration::from_secs(5), chunks.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        let mut reader = algo.decompress(tokio_util::io::StreamReader::new(
+            stream::iter([Ok::<_, IoError>(chunk)]).chain(stream::pending()),
+        ));
+        let mut data = vec![0; 64];
+        let n = tokio::time::timeout(Duration::from_secs(5), reader.read(&mut data))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(&data[..n], b"data: hello\n\n");
+
+        // the compressed stream is terminated at the end of the events
+        let resp = Compress::new(
+            SSE::new(stream::iter([Event::message("a"), Event::message("b")])),
+            algo,
+        )
+        .into_response();
+        assert_eq!(
+            decompress_data(algo, &resp.into_body().into_bytes().await.unwrap()).await,
+            "data: a\n\ndata: b\n\n"
+        );
+    }
+
+    #[cfg(feature = "sse")]
+    #[tokio::test]
+    async fn test_compress_event_stream() {
+        test_event_stream_algo(CompressionAlgo::BR).await;
+        test_event_stream_algo(CompressionAlgo::DEFLATE).await;
+        test_event_stream_algo(CompressionAlgo::GZIP).await;
+    }
 }
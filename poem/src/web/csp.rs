@@ -0,0 +1,28 @@
+use std::ops::Deref;
+
+use crate::{FromRequest, Request, RequestBody, Result};
+
+/// The nonce of the `Content-Security-Policy` header of the response, which
+/// should be set to the `nonce` attribute of the inline scripts and styles.
+///
+/// See also [`CspNonce`](crate::middleware::CspNonce)
+#[cfg_attr(docsrs, doc(cfg(feature = "csp")))]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CspNonceToken(pub String);
+
+impl Deref for CspNonceToken {
+    type Target = String;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'a> FromRequest<'a> for &'a CspNonceToken {
+    async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self> {
+        Ok(req
+            .extensions()
+            .get::<CspNonceToken>()
+            .expect("To use the `CspNonceToken` extractor, the `CspNonce` middleware is required."))
+    }
+}
@@ -0,0 +1,76 @@
+use crate::{FromRequest, Request, RequestBody, Result};
+
+/// An extractor that indicates whether the request was received in TLS 1.3
+/// early data (0-RTT), which can be replayed by an attacker.
+///
+/// The TLS listeners of Poem never accept early data, so the requests that
+/// are received directly are never sent in early data. If the TLS connection
+/// is terminated by a proxy that accepts early data, the proxy marks these
+/// requests with the `Early-Data: 1` header, as defined in
+/// [RFC8470](https://datatracker.ietf.org/doc/html/rfc8470).
+///
+/// The handlers of non-idempotent operations should reject these requests
+/// with `425 Too Early`, so that the client retries after the handshake is
+/// complete.
+///
+/// # Example
+///
+/// ```
+/// use poem::{handler, http::StatusCode, post, test::TestClient, web::EarlyData, Route};
+///
+/// #[handler]
+/// fn transfer(EarlyData(early_data): EarlyData) -> StatusCode {
+///     if early_data {
+///         return StatusCode::TOO_EARLY;
+///     }
+///     StatusCode::OK
+/// }
+///
+/// let app = Route::new().at("/transfer", post(transfer));
+/// let cli = TestClient::new(app);
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// cli.post("/transfer").send().await.assert_status_is_ok();
+/// cli.post("/transfer")
+///     .header("Early-Data", "1")
+///     .send()
+///     .await
+///     .assert_status(StatusCode::TOO_EARLY);
+/// # });
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct EarlyData(pub bool);
+
+impl<'a> FromRequest<'a> for EarlyData {
+    async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self> {
+        Ok(EarlyData(req.headers().get_all("early-data").iter().any(
+            |value| value.to_str().ok().map(str::trim) == Some("1"),
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn early_data() {
+        let req = Request::builder().finish();
+        assert_eq!(
+            EarlyData::from_request_without_body(&req).await.unwrap(),
+            EarlyData(false)
+        );
+
+        let req = Request::builder().header("Early-Data", "1").finish();
+        assert_eq!(
+            EarlyData::from_request_without_body(&req).await.unwrap(),
+            EarlyData(true)
+        );
+
+        let req = Request::builder().header("Early-Data", "0").finish();
+        assert_eq!(
+            EarlyData::from_request_without_body(&req).await.unwrap(),
+            EarlyData(false)
+        );
+    }
+}
@@ -0,0 +1,223 @@
+use std::{
+    io::Error as IoError,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use http::{header, HeaderMap, HeaderValue};
+use hyper::body::{Frame, SizeHint};
+use ring::digest;
+
+use crate::{body::BoxBody, middleware::DigestAlgorithm, Body, IntoResponse, Response};
+
+/// A response that computes the SHA-256 digest of the body while it is being
+/// sent, and sends it in the `ETag` and `Content-Digest`
+/// ([RFC9530](https://datatracker.ietf.org/doc/html/rfc9530)) trailers.
+///
+/// It is useful for the large streaming bodies whose digest cannot be
+/// computed in advance. The trailers are declared in the `Trailer` header,
+/// and are only received by the clients that support trailers. The `ETag`
+/// header of the inner response is removed, since it is replaced by the
+/// trailer.
+///
+/// # Example
+///
+/// ```
+/// use futures_util::stream;
+/// use poem::{handler, test::TestClient, web::HashingBody, Body};
+///
+/// #[handler]
+/// fn download() -> HashingBody<Body> {
+///     HashingBody::new(Body::from_bytes_stream(stream::iter([
+///         Ok::<_, std::io::Error>("hel"),
+///         Ok("lo"),
+///     ])))
+/// }
+///
+/// let cli = TestClient::new(download);
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = cli.get("/").send().await;
+/// resp.assert_header("Trailer", "etag, content-digest");
+/// resp.assert_text("hello").await;
+/// # });
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "digest")))]
+pub struct HashingBody<T> {
+    inner: T,
+}
+
+impl<T> HashingBody<T> {
+    /// Create a `HashingBody` that computes the digest of the body of the
+    /// specified response.
+    pub fn new(inner: T) -> Self {
+        Self { inner }
+    }
+}
+
+impl<T: IntoResponse> IntoResponse for HashingBody<T> {
+    fn into_response(self) -> Response {
+        let mut resp = self.inner.into_response();
+        let body = resp.take_body();
+
+        // the trailers cannot be sent with `Content-Length` over HTTP/1.1
+        resp.headers_mut().remove(header::CONTENT_LENGTH);
+        // a field must not be sent in both the header and the trailer
+        resp.headers_mut().remove(header::ETAG);
+        resp.headers_mut().append(
+            header::TRAILER,
+            HeaderValue::from_static("etag, content-digest"),
+        );
+        resp.set_body(DigestBody::wrap(body, DigestAlgorithm::Sha256, true));
+        resp
+    }
+}
+
+/// A body that sends the digest of the data in the `Content-Digest` trailer,
+/// and optionally in the `ETag` trailer.
+pub(crate) struct DigestBody {
+    inner: BoxBody,
+    algorithm: DigestAlgorithm,
+    etag: bool,
+    context: Option<digest::Context>,
+}
+
+impl DigestBody {
+    pub(crate) fn wrap(body: Body, algorithm: DigestAlgorithm, etag: bool) -> Body {
+        Body(BoxBody::new(Self {
+            inner: body.0,
+            algorithm,
+            etag,
+            context: Some(digest::Context::new(algorithm.ring_algorithm())),
+        }))
+    }
+
+    fn digest_trailers(&mut self) -> Option<HeaderMap> {
+        let digest = self.context.take()?.finish();
+        let mut trailers = HeaderMap::new();
+        if self.etag {
+            trailers.insert(
+                header::ETAG,
+                HeaderValue::from_str(&format!("\"{}\"", hex::encode(digest))).ok()?,
+            );
+        }
+        trailers.insert(
+            "content-digest",
+            self.algorithm.content_digest(digest.as_ref()),
+        );
+        Some(trailers)
+    }
+}
+
+impl hyper::body::Body for DigestBody {
+    type Data = Bytes;
+    type Error = IoError;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        if self.context.is_none() {
+            return Poll::Ready(None);
+        }
+
+        match futures_util::ready!(Pin::new(&mut self.inner).poll_frame(cx)) {
+            Some(Ok(frame)) => match frame.into_data() {
+                Ok(data) => {
+                    if let Some(context) = &mut self.context {
+                        context.update(&data);
+                    }
+                    Poll::Ready(Some(Ok(Frame::data(data))))
+                }
+                Err(frame) => {
+                    // merge the digest into the trailers of the inner body
+                    let mut trailers = frame.into_trailers().unwrap_or_default();
+                    trailers.extend(self.digest_trailers().unwrap_or_default());
+                    Poll::Ready(Some(Ok(Frame::trailers(trailers))))
+                }
+            },
+            Some(Err(err)) => {
+                self.context = None;
+                Poll::Ready(Some(Err(err)))
+            }
+            None => Poll::Ready(
+                self.digest_trailers()
+                    .map(|trailers| Ok(Frame::trailers(trailers))),
+            ),
+        }
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.context.is_none()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        // never report an exact size, otherwise the response will be sent with
+        // `Content-Length` and the trailers cannot be sent over HTTP/1.1
+        let mut size_hint = SizeHint::new();
+        size_hint.set_lower(self.inner.size_hint().lower());
+        size_hint
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http_body_util::BodyExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn hashing_body() {
+        let resp = HashingBody::new("hello").into_response();
+        assert_eq!(
+            resp.headers().get(header::TRAILER),
+            Some(&HeaderValue::from_static("etag, content-digest"))
+        );
+        assert!(resp.headers().get(header::CONTENT_LENGTH).is_none());
+
+        let collected = resp.into_body().0.collect().await.unwrap();
+        let trailers = collected.trailers().cloned().unwrap();
+        assert_eq!(collected.to_bytes(), "hello");
+        assert_eq!(
+            trailers.get(header::ETAG).unwrap(),
+            "\"2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824\""
+        );
+        assert_eq!(
+            trailers.get("content-digest").unwrap(),
+            "sha-256=:LPJNul+wow4m6DsqxbninhsWHlwfp0JecwQzYpOLmCQ=:"
+        );
+    }
+
+    #[tokio::test]
+    async fn remove_etag_header() {
+        let resp = HashingBody::new(
+            Response::builder()
+                .header(header::ETAG, "\"abc\"")
+                .body("hello"),
+        )
+        .into_response();
+        assert!(resp.headers().get(header::ETAG).is_none());
+
+        let collected = resp.into_body().0.collect().await.unwrap();
+        assert_ne!(
+            collected.trailers().unwrap().get(header::ETAG).unwrap(),
+            "\"abc\""
+        );
+    }
+
+    #[cfg(feature = "server")]
+    #[tokio::test]
+    async fn merge_trailers() {
+        let body = Body::from("hello").with_trailers(async move {
+            let mut trailers = HeaderMap::new();
+            trailers.insert("checksum", "123".parse().unwrap());
+            trailers
+        });
+        let resp = HashingBody::new(body).into_response();
+        let collected = resp.into_body().0.collect().await.unwrap();
+        let trailers = collected.trailers().unwrap();
+        assert_eq!(trailers.get("checksum").unwrap(), "123");
+        assert!(trailers.contains_key(header::ETAG));
+    }
+}
@@ -0,0 +1,132 @@
+use std::ops::Deref;
+
+use http::{header, HeaderMap, Version};
+
+use crate::{FromRequest, Request, RequestBody, Result};
+
+/// An extractor that can extracts the negotiated HTTP version from request.
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     handler,
+///     http::{header, StatusCode, Version},
+///     web::HttpVersion,
+///     Endpoint, Request,
+/// };
+///
+/// #[handler]
+/// fn index(version: HttpVersion) -> String {
+///     format!("{:?} {}", *version, version.supports_trailers())
+/// }
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = index
+///     .call(Request::builder().version(Version::HTTP_2).finish())
+///     .await
+///     .unwrap();
+/// assert_eq!(
+///     resp.into_body().into_string().await.unwrap(),
+///     "HTTP/2.0 true"
+/// );
+///
+/// let resp = index.call(Request::builder().finish()).await.unwrap();
+/// assert_eq!(
+///     resp.into_body().into_string().await.unwrap(),
+///     "HTTP/1.1 false"
+/// );
+///
+/// let resp = index
+///     .call(Request::builder().header(header::TE, "trailers").finish())
+///     .await
+///     .unwrap();
+/// assert_eq!(
+///     resp.into_body().into_string().await.unwrap(),
+///     "HTTP/1.1 true"
+/// );
+/// # });
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct HttpVersion {
+    version: Version,
+    trailers: bool,
+}
+
+impl Deref for HttpVersion {
+    type Target = Version;
+
+    fn deref(&self) -> &Self::Target {
+        &self.version
+    }
+}
+
+impl HttpVersion {
+    /// Returns the HTTP version.
+    #[inline]
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// Returns `true` if the response trailers can be sent to the client.
+    ///
+    /// Trailers are always supported by HTTP/2 and HTTP/3, HTTP/1.1 clients
+    /// must send the `TE: trailers` header.
+    pub fn supports_trailers(&self) -> bool {
+        self.trailers
+    }
+}
+
+impl<'a> FromRequest<'a> for HttpVersion {
+    async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self> {
+        Ok(HttpVersion {
+            version: req.version(),
+            trailers: supports_trailers(req.version(), req.headers()),
+        })
+    }
+}
+
+/// Returns `true` if the trailers of the response can be sent to the client.
+pub(crate) fn supports_trailers(version: Version, headers: &HeaderMap) -> bool {
+    if version >= Version::HTTP_2 {
+        return true;
+    }
+    version == Version::HTTP_11
+        && headers
+            .get_all(header::TE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .any(|value| value.trim().eq_ignore_ascii_case("trailers"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn http_version() {
+        let req = Request::builder()
+            .version(Version::HTTP_10)
+            .header(header::TE, "trailers")
+            .finish();
+        let version = HttpVersion::from_request_without_body(&req).await.unwrap();
+        assert_eq!(version.version(), Version::HTTP_10);
+        assert!(!version.supports_trailers());
+
+        let req = Request::builder()
+            .version(Version::HTTP_11)
+            .header(header::TE, "gzip, trailers")
+            .finish();
+        let version = HttpVersion::from_request_without_body(&req).await.unwrap();
+        assert!(version.supports_trailers());
+
+        let req = Request::builder().version(Version::HTTP_2).finish();
+        let version = HttpVersion::from_request_without_body(&req).await.unwrap();
+        assert!(version.supports_trailers());
+
+        let req = Request::builder().version(Version::HTTP_3).finish();
+        let version = HttpVersion::from_request_without_body(&req).await.unwrap();
+        assert!(version.supports_trailers());
+    }
+}
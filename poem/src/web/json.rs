@@ -1,11 +1,15 @@
-use std::ops::{Deref, DerefMut};
+use std::{
+    io::Error as IoError,
+    ops::{Deref, DerefMut},
+};
 
+use bytes::Bytes;
 use http::StatusCode;
 use serde::{de::DeserializeOwned, Serialize};
 
 use crate::{
-    error::ParseJsonError, http::header, web::RequestBody, FromRequest, IntoResponse, Request,
-    Response, Result,
+    error::ParseJsonError, http::header, web::RequestBody, Body, FromRequest, IntoResponse,
+    Request, Response, Result,
 };
 
 /// JSON extractor and response.
@@ -121,7 +125,7 @@ impl<'a, T: DeserializeOwned> FromRequest<'a> for Json<T> {
     }
 }
 
-fn is_json_content_type(content_type: &str) -> bool {
+pub(crate) fn is_json_content_type(content_type: &str) -> bool {
     matches!(content_type.parse::<mime::Mime>(), 
         Ok(content_type) if content_type.type_() == "application" 
         && (content_type.subtype() == "json"
@@ -146,13 +150,159 @@ impl<T: Serialize + Send> IntoResponse for Json<T> {
     }
 }
 
+/// A JSON array response whose elements are serialized while the body is
+/// being sent, instead of being buffered in memory.
+///
+/// The elements are read from an iterator and serialized in chunks of about
+/// `8KB` when the body is polled, so each element is serialized at once, and
+/// the serialization waits for the client to read the previous chunks.
+///
+/// By default, the body is sent with chunked encoding. If the length of the
+/// serialized array is known, it can be specified with
+/// [`StreamingJson::content_length`] or [`StreamingJson::length_with`], then
+/// the response is sent with `Content-Length`.
+///
+/// # Example
+///
+/// ```
+/// use poem::{handler, test::TestClient, web::StreamingJson};
+///
+/// #[handler]
+/// fn index() -> StreamingJson<Vec<i32>> {
+///     StreamingJson::new(vec![1, 2, 3]).length_with(|values| {
+///         // `[` + `]` + digits + commas
+///         Some(2 + values.len() as u64 * 2 - 1)
+///     })
+/// }
+///
+/// let cli = TestClient::new(index);
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = cli.get("/").send().await;
+/// resp.assert_status_is_ok();
+/// resp.assert_text("[1,2,3]").await;
+/// # });
+/// ```
+pub struct StreamingJson<I> {
+    items: I,
+    length: Option<u64>,
+}
+
+impl<I> StreamingJson<I> {
+    /// Create a `StreamingJson` response with the elements of the array.
+    pub fn new(items: I) -> Self {
+        Self {
+            items,
+            length: None,
+        }
+    }
+
+    /// Sets the exact length of the serialized array.
+    ///
+    /// If the length does not match, the connection will be closed with an
+    /// error.
+    #[must_use]
+    pub fn content_length(self, len: u64) -> Self {
+        Self {
+            length: Some(len),
+            ..self
+        }
+    }
+
+    /// Computes the exact length of the serialized array with the specified
+    /// function, if it returns `None`, the body is sent with chunked encoding.
+    #[must_use]
+    pub fn length_with(self, f: impl FnOnce(&I) -> Option<u64>) -> Self {
+        let length = f(&self.items);
+        Self { length, ..self }
+    }
+}
+
+const STREAMING_JSON_CHUNK_SIZE: usize = 8 * 1024;
+
+struct StreamingJsonState<I> {
+    items: I,
+    started: bool,
+    empty: bool,
+}
+
+impl<I> StreamingJsonState<I>
+where
+    I: Iterator,
+    I::Item: Serialize,
+{
+    /// Serializes the elements until the chunk is full, returns `true` if the
+    /// array is finished.
+    fn next_chunk(&mut self, buf: &mut Vec<u8>) -> Result<bool, IoError> {
+        if !self.started {
+            self.started = true;
+            buf.push(b'[');
+        }
+        while buf.len() < STREAMING_JSON_CHUNK_SIZE {
+            let Some(item) = self.items.next() else {
+                buf.push(b']');
+                return Ok(true);
+            };
+            if !self.empty {
+                buf.push(b',');
+            }
+            self.empty = false;
+            serde_json::to_writer(&mut *buf, &item)?;
+        }
+        Ok(false)
+    }
+}
+
+impl<I> IntoResponse for StreamingJson<I>
+where
+    I: IntoIterator + Send + 'static,
+    I::IntoIter: Send + 'static,
+    I::Item: Serialize,
+{
+    fn into_response(self) -> Response {
+        let state = StreamingJsonState {
+            items: self.items.into_iter(),
+            started: false,
+            empty: true,
+        };
+        let mut body = Body::from_bytes_stream(futures_util::stream::unfold(
+            Some(state),
+            |state| async move {
+                let mut state = state?;
+                let mut buf = Vec::with_capacity(STREAMING_JSON_CHUNK_SIZE);
+                match state.next_chunk(&mut buf) {
+                    Ok(true) => Some((Ok(Bytes::from(buf)), None)),
+                    Ok(false) => Some((Ok(Bytes::from(buf)), Some(state))),
+                    Err(err) => Some((Err(err), None)),
+                }
+            },
+        ));
+        if let Some(len) = self.length {
+            body = body.with_content_length(len);
+        }
+
+        Response::builder()
+            .header(header::CONTENT_TYPE, "application/json; charset=utf-8")
+            .body(body)
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use std::{
+        io::ErrorKind,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    };
+
+    use http_body_util::BodyExt;
     use serde::{Deserialize, Serialize};
     use serde_json::json;
 
     use super::*;
-    use crate::{handler, test::TestClient};
+    use crate::{error::ReadBodyError, handler, test::TestClient};
 
     #[derive(Deserialize, Serialize, Debug, Eq, PartialEq)]
     struct CreateResource {
@@ -214,4 +364,89 @@ mod tests {
         })
         .await;
     }
+
+    #[tokio::test]
+    async fn streaming_json() {
+        #[handler(internal)]
+        fn chunked() -> StreamingJson<Vec<String>> {
+            StreamingJson::new(vec!["a".repeat(STREAMING_JSON_CHUNK_SIZE); 3])
+        }
+
+        let resp = TestClient::new(chunked).get("/").send().await;
+        resp.assert_status_is_ok();
+        resp.assert_content_type("application/json; charset=utf-8");
+        let body = resp.0.into_body();
+        assert_eq!(hyper::body::Body::size_hint(&body.0).exact(), None);
+        let value: Vec<String> = body.into_json().await.unwrap();
+        assert_eq!(value, vec!["a".repeat(STREAMING_JSON_CHUNK_SIZE); 3]);
+
+        #[handler(internal)]
+        fn sized() -> StreamingJson<Vec<CreateResource>> {
+            StreamingJson::new(vec![CreateResource {
+                name: "abc".to_string(),
+                value: 100,
+            }])
+            .content_length(28)
+        }
+
+        let resp = TestClient::new(sized).get("/").send().await;
+        let body = resp.0.into_body();
+        assert_eq!(hyper::body::Body::size_hint(&body.0).exact(), Some(28));
+        assert_eq!(
+            body.into_string().await.unwrap(),
+            r#"[{"name":"abc","value":100}]"#
+        );
+
+        #[handler(internal)]
+        fn empty() -> StreamingJson<std::iter::Empty<i32>> {
+            StreamingJson::new(std::iter::empty())
+        }
+
+        TestClient::new(empty)
+            .get("/")
+            .send()
+            .await
+            .assert_text("[]")
+            .await;
+    }
+
+    #[tokio::test]
+    async fn streaming_json_chunks() {
+        // the elements are serialized in chunks as the body is polled
+        let polled = Arc::new(AtomicUsize::new(0));
+        let items = {
+            let polled = polled.clone();
+            (0..3).map(move |_| {
+                polled.fetch_add(1, Ordering::SeqCst);
+                "a".repeat(STREAMING_JSON_CHUNK_SIZE)
+            })
+        };
+        let mut body = StreamingJson::new(items).into_response().into_body().0;
+        assert_eq!(polled.load(Ordering::SeqCst), 0);
+
+        let chunk = body.frame().await.unwrap().unwrap().into_data().unwrap();
+        assert_eq!(polled.load(Ordering::SeqCst), 1);
+        assert!(chunk.starts_with(b"[\"aaa"));
+
+        let mut data = chunk.to_vec();
+        while let Some(frame) = body.frame().await {
+            data.extend_from_slice(&frame.unwrap().into_data().unwrap());
+        }
+        assert_eq!(polled.load(Ordering::SeqCst), 3);
+        let value: Vec<String> = serde_json::from_slice(&data).unwrap();
+        assert_eq!(value, vec!["a".repeat(STREAMING_JSON_CHUNK_SIZE); 3]);
+    }
+
+    #[test]
+    fn streaming_json_lazy() {
+        // the response can be created outside of the runtime
+        let resp = StreamingJson::new(vec![1, 2, 3])
+            .content_length(6)
+            .into_response();
+        let res = tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(resp.into_body().into_bytes());
+        // the declared length is checked
+        assert!(matches!(res, Err(ReadBodyError::Io(err)) if err.kind() == ErrorKind::InvalidData));
+    }
 }
@@ -1,21 +1,32 @@
 //! Commonly used as the type of extractor or response.
 
 mod accept;
+mod accept_media;
 mod addr;
+mod authorization;
 #[cfg(feature = "compression")]
 mod compress;
 #[cfg(feature = "cookie")]
 #[cfg_attr(docsrs, doc(cfg(feature = "cookie")))]
 pub mod cookie;
 mod data;
+mod early_data;
 mod form;
+#[cfg(feature = "digest")]
+mod hashing_body;
+mod http_version;
 mod json;
 #[cfg(feature = "multipart")]
 mod multipart;
+mod pagination;
 mod path;
+mod prefer;
+#[cfg(feature = "protobuf")]
+mod protobuf;
 mod query;
 mod real_ip;
 mod redirect;
+mod secure;
 #[cfg(feature = "sse")]
 #[cfg_attr(docsrs, doc(cfg(feature = "sse")))]
 pub mod sse;
@@ -23,12 +34,15 @@ pub mod sse;
 mod static_file;
 #[cfg(feature = "tempfile")]
 mod tempfile;
+mod trailers;
 #[cfg(feature = "xml")]
 mod xml;
 #[cfg(feature = "yaml")]
 mod yaml;
 #[doc(inline)]
 pub use headers;
+#[cfg(feature = "csp")]
+mod csp;
 #[cfg(feature = "csrf")]
 mod csrf;
 mod typed_header;
@@ -46,11 +60,22 @@ use http::header;
 
 #[cfg(feature = "compression")]
 pub use self::compress::{Compress, CompressionAlgo};
+#[cfg(feature = "csp")]
+pub use self::csp::CspNonceToken;
 #[cfg(feature = "csrf")]
 pub use self::csrf::{CsrfToken, CsrfVerifier};
+#[cfg(feature = "digest")]
+pub(crate) use self::hashing_body::DigestBody;
+#[cfg(feature = "digest")]
+pub use self::hashing_body::HashingBody;
+#[cfg(feature = "server")]
+pub(crate) use self::http_version::supports_trailers;
 #[cfg(feature = "multipart")]
-pub use self::multipart::{Field, Multipart};
+pub use self::multipart::{Field, Multipart, TypedMultipart, TypedMultipartConfig, UploadedFile};
 pub(crate) use self::path::PathDeserializer;
+#[cfg(feature = "protobuf")]
+pub use self::protobuf::{JsonOrProtobuf, Protobuf};
+pub(crate) use self::secure::is_secure;
 #[cfg(feature = "static-files")]
 pub use self::static_file::{StaticFileRequest, StaticFileResponse};
 #[cfg(feature = "tempfile")]
@@ -61,14 +86,22 @@ pub use self::xml::Xml;
 pub use self::yaml::Yaml;
 pub use self::{
     accept::Accept,
+    accept_media::{AcceptMedia, MediaRange},
     addr::{LocalAddr, RemoteAddr},
+    authorization::Authorization,
     data::Data,
+    early_data::EarlyData,
     form::Form,
-    json::Json,
+    http_version::HttpVersion,
+    json::{Json, StreamingJson},
+    pagination::{Paginated, Pagination, PaginationConfig},
     path::Path,
+    prefer::{Prefer, Preference},
     query::Query,
     real_ip::RealIp,
     redirect::Redirect,
+    secure::{Secure, TrustedProxies},
+    trailers::Trailers,
     typed_header::TypedHeader,
 };
 use crate::{
@@ -136,6 +169,16 @@ impl RequestBody {
 ///
 ///    Extracts the remote peer's real ip address from request.
 ///
+/// - **Secure**
+///
+///    Extracts whether the request was received over a secure transport,
+///   trusting the forwarding headers of the [`TrustedProxies`] only.
+///
+/// - **EarlyData**
+///
+///    Extracts whether the request was received in TLS 1.3 early data, which
+///   can be replayed.
+///
 /// - **Method**
 ///
 ///    Extracts the [`Method`] from the incoming request.
@@ -144,6 +187,11 @@ impl RequestBody {
 ///
 ///    Extracts the [`Version`] from the incoming request.
 ///
+/// - **HttpVersion**
+///
+///    Extracts the [`HttpVersion`] from the incoming request, which can be
+///   used to check the capabilities of the protocol.
+///
 /// - **&Uri**
 ///
 ///    Extracts the [`Uri`] from the incoming request.
@@ -160,6 +208,21 @@ impl RequestBody {
 ///
 ///    Extracts the [`TypedHeader`] from the incoming request.
 ///
+/// - **Authorization**
+///
+///    Extracts the scheme and the credentials of the `Authorization` header
+///   of any scheme from the incoming request.
+///
+/// - **Prefer**
+///
+///    Extracts the preferences of the `Prefer` header from the incoming
+///   request.
+///
+/// - **Trailers**
+///
+///    Captures the [`Trailers`] of the request body, which are available
+///   after the body has been read.
+///
 /// - **Path&lt;T>**
 ///
 ///    Extracts the [`Path`] from the incoming request.
@@ -168,6 +231,11 @@ impl RequestBody {
 ///
 ///    Extracts the [`Query`] from the incoming request.
 ///
+/// - **Pagination**
+///
+///    Extracts the [`Pagination`] from the `page` and `per_page` query
+///   parameters.
+///
 /// - **Form&lt;T>**
 ///
 ///    Extracts the [`Form`] from the incoming request.
@@ -186,6 +254,21 @@ impl RequestBody {
 ///    _This extractor will take over the requested body, so you should avoid
 /// using multiple extractors of this type in one handler._
 ///
+/// - **Protobuf&lt;T>**
+///
+///    Extracts the [`Protobuf`] from the incoming request.
+///
+///    _This extractor will take over the requested body, so you should avoid
+///   using multiple extractors of this type in one handler._
+///
+/// - **JsonOrProtobuf&lt;T>**
+///
+///    Extracts the [`JsonOrProtobuf`] from the incoming request, according to
+///   the `Content-Type` header.
+///
+///    _This extractor will take over the requested body, so you should avoid
+///   using multiple extractors of this type in one handler._
+///
 /// - **TempFile**
 ///
 ///    Extracts the [`TempFile`] from the incoming request.
@@ -263,6 +346,11 @@ impl RequestBody {
 ///
 ///     Extracts the `Accept` header from the incoming request.
 ///
+/// - **AcceptMedia**
+///
+///     Extracts the weighted media ranges of the `Accept` header, and picks
+///   the best representation for the incoming request.
+///
 /// - **PathPattern**
 ///
 ///     Extracts the matched path pattern from the incoming request.
@@ -375,6 +463,11 @@ pub trait FromRequest<'a>: Sized {
 ///    Sets the status to `OK` and the `Content-Type` to `application/xml`. Use
 /// [`quick-xml`](https://crates.io/crates/quick-xml) to serialize `T` into a xml string.
 ///
+/// - **Protobuf&lt;T>**
+///
+///    Sets the status to `OK` and the `Content-Type` to
+///   `application/x-protobuf`. Use [`prost`](https://crates.io/crates/prost) to encode `T`.
+///
 /// - **Bytes**
 ///
 ///    Sets the status to `OK` and the `Content-Type` to
@@ -711,7 +804,7 @@ impl<T: IntoResponse> IntoResponse for (StatusCode, HeaderMap, T) {
     fn into_response(self) -> Response {
         let mut resp = self.2.into_response();
         resp.set_status(self.0);
-        resp.headers_mut().extend(self.1);
+        resp.extend_headers(self.1);
         resp
     }
 }
@@ -719,7 +812,7 @@ impl<T: IntoResponse> IntoResponse for (StatusCode, HeaderMap, T) {
 impl<T: IntoResponse> IntoResponse for (HeaderMap, T) {
     fn into_response(self) -> Response {
         let mut resp = self.1.into_response();
-        resp.headers_mut().extend(self.0);
+        resp.extend_headers(self.0);
         resp
     }
 }
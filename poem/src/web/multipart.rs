@@ -1,15 +1,27 @@
 use std::{
+    cell::RefCell,
     fmt::{self, Debug, Formatter},
+    io::Cursor,
+    ops::{Deref, DerefMut},
     str::FromStr,
 };
 
+use bytes::Bytes;
 use futures_util::TryStreamExt;
 use mime::Mime;
+use serde::{
+    de::{
+        self, value::Error as DeError, DeserializeOwned, DeserializeSeed, Error as _,
+        IntoDeserializer, MapAccess, SeqAccess, Unexpected, Visitor,
+    },
+    forward_to_deserialize_any, Deserialize, Deserializer,
+};
 #[cfg(feature = "tempfile")]
 use tokio::fs::File;
 use tokio::io::{AsyncRead, AsyncReadExt};
 #[cfg(feature = "tempfile")]
 use tokio::io::{AsyncSeekExt, SeekFrom};
+use tokio_util::either::Either;
 
 use crate::{error::ParseMultipartError, http::header, FromRequest, Request, RequestBody, Result};
 
@@ -166,6 +178,565 @@ impl Multipart {
     }
 }
 
+#[cfg(feature = "tempfile")]
+type FileStorage = File;
+#[cfg(not(feature = "tempfile"))]
+type FileStorage = tokio::io::Empty;
+
+/// A file field of a [`TypedMultipart`] request.
+///
+/// If the `tempfile` feature is enabled, the content is streamed to a
+/// temporary file while the request is being parsed, otherwise it is kept in
+/// memory.
+#[cfg_attr(docsrs, doc(cfg(feature = "multipart")))]
+pub struct UploadedFile {
+    file_name: Option<String>,
+    content_type: Option<String>,
+    size: u64,
+    content: Either<Cursor<Bytes>, FileStorage>,
+}
+
+impl Debug for UploadedFile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UploadedFile")
+            .field("file_name", &self.file_name)
+            .field("content_type", &self.content_type)
+            .field("size", &self.size)
+            .finish()
+    }
+}
+
+impl UploadedFile {
+    async fn from_field(field: Field, max_size: u64) -> Result<Self, ParseMultipartError> {
+        let file_name = field.file_name().map(ToString::to_string);
+        let content_type = field.content_type().map(ToString::to_string);
+        // read one more byte to know if the file is larger than the limit
+        let mut reader = field.into_async_read().take(max_size.saturating_add(1));
+
+        #[cfg(feature = "tempfile")]
+        let (size, content) = {
+            let mut file = File::from_std(::libtempfile::tempfile()?);
+            let size = tokio::io::copy(&mut reader, &mut file).await?;
+            file.seek(SeekFrom::Start(0)).await?;
+            (size, Either::Right(file))
+        };
+        #[cfg(not(feature = "tempfile"))]
+        let (size, content) = {
+            let mut data = Vec::new();
+            reader.read_to_end(&mut data).await?;
+            (
+                data.len() as u64,
+                Either::Left(Cursor::new(Bytes::from(data))),
+            )
+        };
+        if size > max_size {
+            return Err(ParseMultipartError::FileTooLarge(max_size));
+        }
+
+        Ok(Self {
+            file_name,
+            content_type,
+            size,
+            content,
+        })
+    }
+
+    /// The file name found in the `Content-Disposition` header.
+    #[inline]
+    pub fn file_name(&self) -> Option<&str> {
+        self.file_name.as_deref()
+    }
+
+    /// Get the content type of the file.
+    #[inline]
+    pub fn content_type(&self) -> Option<&str> {
+        self.content_type.as_deref()
+    }
+
+    /// Returns the size of the file in bytes.
+    #[inline]
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Get the full content of the file as bytes.
+    pub async fn bytes(self) -> Result<Vec<u8>, ParseMultipartError> {
+        let mut data = Vec::with_capacity(self.size as usize);
+        self.into_async_read().read_to_end(&mut data).await?;
+        Ok(data)
+    }
+
+    /// Consume this file to return a reader.
+    pub fn into_async_read(self) -> impl AsyncRead + Unpin + Send + 'static {
+        self.content
+    }
+}
+
+/// The name of the newtype struct that [`UploadedFile`] is deserialized from,
+/// which is only accepted by the file parts of [`TypedMultipart`].
+const UPLOADED_FILE: &str = "$poem::UploadedFile";
+
+thread_local! {
+    // serde cannot pass a value outside of its data model to a visitor, so the
+    // file is handed over through this slot, which is only filled while the
+    // deserializer of a file part is calling the visitor of `UploadedFile`
+    static CURRENT_FILE: RefCell<Option<UploadedFile>> = const { RefCell::new(None) };
+}
+
+impl<'de> Deserialize<'de> for UploadedFile {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FileVisitor;
+
+        impl<'de> Visitor<'de> for FileVisitor {
+            type Value = UploadedFile;
+
+            fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+                f.write_str("a file field of `TypedMultipart`")
+            }
+
+            fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+                CURRENT_FILE
+                    .with(|file| file.borrow_mut().take())
+                    .ok_or_else(|| E::invalid_type(Unexpected::Unit, &self))
+            }
+        }
+
+        deserializer.deserialize_newtype_struct(UPLOADED_FILE, FileVisitor)
+    }
+}
+
+enum Part {
+    Text(String),
+    File(UploadedFile),
+}
+
+/// Deserializes the collected parts as a map, the parts with the same name
+/// are a sequence.
+struct PartsDeserializer(std::vec::IntoIter<(String, Vec<Part>)>);
+
+impl<'de> Deserializer<'de> for PartsDeserializer {
+    type Error = DeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_map(PartsMapAccess {
+            parts: self.0,
+            value: None,
+        })
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+struct PartsMapAccess {
+    parts: std::vec::IntoIter<(String, Vec<Part>)>,
+    value: Option<Vec<Part>>,
+}
+
+impl<'de> MapAccess<'de> for PartsMapAccess {
+    type Error = DeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        match self.parts.next() {
+            Some((name, parts)) => {
+                self.value = Some(parts);
+                seed.deserialize(name.into_deserializer()).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        let parts = self
+            .value
+            .take()
+            .ok_or_else(|| DeError::custom("value is missing"))?;
+        seed.deserialize(ValuesDeserializer(parts))
+    }
+}
+
+/// Deserializes the parts with the same name, which are a sequence, or a
+/// single value if there is only one part.
+struct ValuesDeserializer(Vec<Part>);
+
+impl ValuesDeserializer {
+    fn single(self) -> Result<PartDeserializer, DeError> {
+        let mut parts = self.0;
+        match parts.len() {
+            1 => Ok(PartDeserializer(parts.remove(0))),
+            _ => Err(DeError::custom("expected a single value, found multiple")),
+        }
+    }
+}
+
+macro_rules! forward_to_single_part {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                self.single()?.$method(visitor)
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for ValuesDeserializer {
+    type Error = DeError;
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_seq(PartsSeqAccess(self.0.into_iter()))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.single()?.deserialize_newtype_struct(name, visitor)
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.single()?.deserialize_unit_struct(name, visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.single()?.deserialize_struct(name, fields, visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.single()?.deserialize_enum(name, variants, visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    forward_to_single_part! {
+        deserialize_any deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32
+        deserialize_i64 deserialize_i128 deserialize_u8 deserialize_u16 deserialize_u32
+        deserialize_u64 deserialize_u128 deserialize_f32 deserialize_f64 deserialize_char
+        deserialize_str deserialize_string deserialize_bytes deserialize_byte_buf
+        deserialize_unit deserialize_map deserialize_identifier
+    }
+}
+
+struct PartsSeqAccess(std::vec::IntoIter<Part>);
+
+impl<'de> SeqAccess<'de> for PartsSeqAccess {
+    type Error = DeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        match self.0.next() {
+            Some(part) => seed.deserialize(PartDeserializer(part)).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.0.len())
+    }
+}
+
+/// Deserializes a single part, the text parts are parsed like the values of
+/// [`Form`](crate::web::Form), and only [`UploadedFile`] can be deserialized
+/// from the file parts.
+struct PartDeserializer(Part);
+
+impl PartDeserializer {
+    fn into_text(self) -> Result<String, DeError> {
+        match self.0 {
+            Part::Text(text) => Ok(text),
+            Part::File(_) => Err(DeError::custom("expected a text field, found a file")),
+        }
+    }
+}
+
+macro_rules! forward_parsed_value {
+    ($($ty:ident => $method:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                match self.into_text()?.parse::<$ty>() {
+                    Ok(value) => value.into_deserializer().$method(visitor),
+                    Err(err) => Err(DeError::custom(err)),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for PartDeserializer {
+    type Error = DeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.0 {
+            Part::Text(text) => visitor.visit_string(text),
+            // the buffering deserializers of serde, which are used by
+            // `#[serde(flatten)]` and the untagged or internally tagged enums,
+            // cannot keep a file
+            Part::File(_) => Err(DeError::custom(
+                "a file can only be deserialized into `UploadedFile` directly, not through \
+                 `#[serde(flatten)]` or an untagged or internally tagged enum",
+            )),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        match (name, self.0) {
+            (UPLOADED_FILE, Part::File(file)) => {
+                CURRENT_FILE.with(|current| *current.borrow_mut() = Some(file));
+                let res = visitor.visit_unit();
+                CURRENT_FILE.with(|current| current.borrow_mut().take());
+                res
+            }
+            (UPLOADED_FILE, Part::Text(_)) => {
+                Err(DeError::custom("expected a file, found a text field"))
+            }
+            (_, part) => visitor.visit_newtype_struct(PartDeserializer(part)),
+        }
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.into_text()?
+            .into_deserializer()
+            .deserialize_enum(name, variants, visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        char str string unit bytes byte_buf unit_struct tuple_struct
+        identifier tuple seq map struct
+    }
+
+    forward_parsed_value! {
+        bool => deserialize_bool,
+        u8 => deserialize_u8,
+        u16 => deserialize_u16,
+        u32 => deserialize_u32,
+        u64 => deserialize_u64,
+        u128 => deserialize_u128,
+        i8 => deserialize_i8,
+        i16 => deserialize_i16,
+        i32 => deserialize_i32,
+        i64 => deserialize_i64,
+        i128 => deserialize_i128,
+        f32 => deserialize_f32,
+        f64 => deserialize_f64,
+    }
+}
+
+/// The configuration of the [`TypedMultipart`] extractor.
+///
+/// It is added to the application with
+/// [`EndpointExt::data`](crate::EndpointExt::data), otherwise the default
+/// configuration is used.
+#[cfg_attr(docsrs, doc(cfg(feature = "multipart")))]
+#[derive(Debug, Clone, Copy)]
+pub struct TypedMultipartConfig {
+    max_file_size: u64,
+}
+
+impl Default for TypedMultipartConfig {
+    fn default() -> Self {
+        Self {
+            max_file_size: 8 * 1024 * 1024,
+        }
+    }
+}
+
+impl TypedMultipartConfig {
+    /// Create a `TypedMultipartConfig` with the default configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum size of each file, whether it is kept in memory or
+    /// in a temporary file. The requests with larger files are rejected with
+    /// `413 Payload Too Large`.
+    ///
+    /// Default is `8MB`.
+    #[must_use]
+    pub fn max_file_size(self, size: u64) -> Self {
+        Self {
+            max_file_size: size,
+        }
+    }
+}
+
+/// An extractor that deserializes a `multipart/form-data` request into a
+/// typed struct in one pass.
+///
+/// The text fields are deserialized like [`Form`](crate::web::Form), and the
+/// file fields (the parts with a file name) are deserialized into
+/// [`UploadedFile`]. The fields that are repeated can be deserialized into a
+/// `Vec`, such as `Vec<String>` or `Vec<UploadedFile>`. The files are streamed
+/// to storage as they arrive, and the required fields are validated when the
+/// whole request has been read.
+///
+/// The files are kept in memory unless the `tempfile` feature is enabled, and
+/// their size is limited by [`TypedMultipartConfig`]. They can only be
+/// deserialized into the fields of the type `UploadedFile`, `Option` or `Vec`
+/// directly, not through `#[serde(flatten)]` or the untagged and internally
+/// tagged enums, which buffer the values.
+///
+/// # Errors
+///
+/// - [`ReadBodyError`](crate::error::ReadBodyError)
+/// - [`ParseMultipartError`]
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     handler,
+///     test::{TestClient, TestForm, TestFormField},
+///     web::{TypedMultipart, UploadedFile},
+///     Result,
+/// };
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Upload {
+///     title: String,
+///     file: UploadedFile,
+///     thumbnail: Option<UploadedFile>,
+/// }
+///
+/// #[handler]
+/// async fn upload(TypedMultipart(upload): TypedMultipart<Upload>) -> Result<String> {
+///     let name = upload.file.file_name().unwrap_or_default().to_string();
+///     let data = upload.file.bytes().await?;
+///     Ok(format!("{}: {} ({} bytes)", upload.title, name, data.len()))
+/// }
+///
+/// let cli = TestClient::new(upload);
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = cli
+///     .post("/")
+///     .multipart(
+///         TestForm::new().text("title", "hello").field(
+///             TestFormField::bytes(b"abc".to_vec())
+///                 .name("file")
+///                 .filename("a.txt"),
+///         ),
+///     )
+///     .send()
+///     .await;
+/// resp.assert_status_is_ok();
+/// resp.assert_text("hello: a.txt (3 bytes)").await;
+/// # });
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "multipart")))]
+#[derive(Debug)]
+pub struct TypedMultipart<T>(pub T);
+
+impl<T> Deref for TypedMultipart<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for TypedMultipart<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<'a, T: DeserializeOwned> FromRequest<'a> for TypedMultipart<T> {
+    async fn from_request(req: &'a Request, body: &mut RequestBody) -> Result<Self> {
+        let config = req
+            .data::<TypedMultipartConfig>()
+            .copied()
+            .unwrap_or_default();
+        let mut multipart = Multipart::from_request(req, body).await?;
+        let mut parts: Vec<(String, Vec<Part>)> = Vec::new();
+
+        while let Some(field) = multipart.next_field().await? {
+            let Some(name) = field.name().map(ToString::to_string) else {
+                continue;
+            };
+            let part = if field.file_name().is_some() {
+                Part::File(UploadedFile::from_field(field, config.max_file_size).await?)
+            } else {
+                Part::Text(field.text().await?)
+            };
+            match parts.iter_mut().find(|(n, _)| *n == name) {
+                Some((_, values)) => values.push(part),
+                None => parts.push((name, vec![part])),
+            }
+        }
+
+        Ok(Self(
+            T::deserialize(PartsDeserializer(parts.into_iter()))
+                .map_err(ParseMultipartError::Deserialize)?,
+        ))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -217,4 +788,221 @@ mod tests {
             .await;
         resp.assert_status_is_ok();
     }
+
+    #[tokio::test]
+    async fn typed_multipart() {
+        use crate::test::{TestForm, TestFormField};
+
+        #[derive(Debug, Deserialize)]
+        struct Upload {
+            title: String,
+            count: i32,
+            file: UploadedFile,
+            other: Option<UploadedFile>,
+        }
+
+        #[handler(internal)]
+        async fn index(TypedMultipart(upload): TypedMultipart<Upload>) -> String {
+            assert!(upload.other.is_none());
+            let prefix = format!(
+                "{} {} {} {:?}",
+                upload.title,
+                upload.count,
+                upload.file.file_name().unwrap(),
+                upload.file.content_type(),
+            );
+            let data = upload.file.bytes().await.unwrap();
+            format!("{prefix} {}", String::from_utf8(data).unwrap())
+        }
+
+        let cli = TestClient::new(index);
+
+        let resp = cli
+            .post("/")
+            .multipart(
+                TestForm::new()
+                    .text("title", "hello")
+                    .field(
+                        TestFormField::bytes(b"abc".to_vec())
+                            .name("file")
+                            .filename("a.txt")
+                            .content_type("text/plain"),
+                    )
+                    .text("count", "3"),
+            )
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+        resp.assert_text(r#"hello 3 a.txt Some("text/plain") abc"#)
+            .await;
+
+        // missing file
+        cli.post("/")
+            .multipart(TestForm::new().text("title", "hello").text("count", "3"))
+            .send()
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+
+        // a text field cannot be a file, and a file cannot be a text field
+        cli.post("/")
+            .multipart(
+                TestForm::new()
+                    .text("title", "hello")
+                    .text("count", "3")
+                    .text("file", "a.txt"),
+            )
+            .send()
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+        cli.post("/")
+            .multipart(
+                TestForm::new()
+                    .field(TestFormField::text("hello").name("title").filename("a.txt"))
+                    .text("count", "3")
+                    .field(
+                        TestFormField::bytes(b"abc".to_vec())
+                            .name("file")
+                            .filename("a.txt"),
+                    ),
+            )
+            .send()
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+
+        // a field cannot be repeated if it is not a sequence
+        cli.post("/")
+            .multipart(
+                TestForm::new()
+                    .text("title", "hello")
+                    .text("title", "world")
+                    .text("count", "3")
+                    .field(
+                        TestFormField::bytes(b"abc".to_vec())
+                            .name("file")
+                            .filename("a.txt"),
+                    ),
+            )
+            .send()
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn typed_multipart_repeated() {
+        use crate::test::{TestForm, TestFormField};
+
+        #[derive(Debug, Deserialize)]
+        struct Upload {
+            tags: Vec<String>,
+            files: Vec<UploadedFile>,
+        }
+
+        #[handler(internal)]
+        async fn index(TypedMultipart(upload): TypedMultipart<Upload>) -> String {
+            let mut names = Vec::new();
+            for file in upload.files {
+                let name = file.file_name().unwrap().to_string();
+                let data = String::from_utf8(file.bytes().await.unwrap()).unwrap();
+                names.push(format!("{name}={data}"));
+            }
+            format!("{} {}", upload.tags.join(","), names.join(","))
+        }
+
+        let cli = TestClient::new(index);
+        let resp = cli
+            .post("/")
+            .multipart(
+                TestForm::new()
+                    .text("tags", "a")
+                    .field(
+                        TestFormField::bytes(b"1".to_vec())
+                            .name("files")
+                            .filename("1.txt"),
+                    )
+                    .text("tags", "b")
+                    .field(
+                        TestFormField::bytes(b"2".to_vec())
+                            .name("files")
+                            .filename("2.txt"),
+                    ),
+            )
+            .send()
+            .await;
+        resp.assert_status_is_ok();
+        resp.assert_text("a,b 1.txt=1,2.txt=2").await;
+    }
+
+    #[tokio::test]
+    async fn typed_multipart_file_size_limit() {
+        use crate::{
+            test::{TestForm, TestFormField},
+            EndpointExt,
+        };
+
+        #[derive(Debug, Deserialize)]
+        struct Upload {
+            #[allow(dead_code)]
+            file: UploadedFile,
+        }
+
+        #[handler(internal)]
+        async fn index(TypedMultipart(_upload): TypedMultipart<Upload>) {}
+
+        let cli = TestClient::new(index.data(TypedMultipartConfig::new().max_file_size(3)));
+        for (data, status) in [
+            (b"abc".to_vec(), StatusCode::OK),
+            (b"abcd".to_vec(), StatusCode::PAYLOAD_TOO_LARGE),
+        ] {
+            cli.post("/")
+                .multipart(
+                    TestForm::new()
+                        .field(TestFormField::bytes(data).name("file").filename("a.txt")),
+                )
+                .send()
+                .await
+                .assert_status(status);
+        }
+    }
+
+    #[tokio::test]
+    async fn typed_multipart_flatten() {
+        use crate::test::{TestForm, TestFormField};
+
+        #[derive(Debug, Deserialize)]
+        struct Files {
+            #[allow(dead_code)]
+            file: UploadedFile,
+        }
+
+        #[derive(Debug, Deserialize)]
+        struct Upload {
+            title: String,
+            #[serde(flatten)]
+            #[allow(dead_code)]
+            files: Files,
+        }
+
+        #[handler(internal)]
+        async fn index(TypedMultipart(upload): TypedMultipart<Upload>) -> String {
+            upload.title
+        }
+
+        let resp = TestClient::new(index)
+            .post("/")
+            .multipart(
+                TestForm::new().text("title", "hello").field(
+                    TestFormField::bytes(b"abc".to_vec())
+                        .name("file")
+                        .filename("a.txt"),
+                ),
+            )
+            .send()
+            .await;
+        resp.assert_status(StatusCode::BAD_REQUEST);
+        resp.assert_text(
+            "deserialize: a file can only be deserialized into `UploadedFile` directly, not \
+             through `#[serde(flatten)]` or an untagged or internally tagged enum",
+        )
+        .await;
+    }
 }
@@ -0,0 +1,329 @@
+use http::{header, HeaderValue, Uri};
+use serde::Deserialize;
+
+use crate::{
+    error::ParseQueryError, FromRequest, IntoResponse, Request, RequestBody, Response, Result,
+};
+
+/// An extractor for the `page` and `per_page` query parameters.
+///
+/// The page numbers start at `1`. If the parameters are missing, the first
+/// page with [`Pagination::DEFAULT_PER_PAGE`] items is used, and `per_page` is
+/// at most [`Pagination::MAX_PER_PAGE`].
+///
+/// It also keeps the original URI of the current request, before the prefixes
+/// of the nested routes are stripped, which is used by [`Paginated`] to build
+/// the links to the other pages. The links are relative unless a base URL is
+/// set with [`PaginationConfig`].
+///
+/// # Errors
+///
+/// - [`ParseQueryError`]
+#[derive(Debug, Clone)]
+pub struct Pagination {
+    /// The number of the requested page, starting at `1`.
+    pub page: u64,
+    /// The number of items per page.
+    pub per_page: u64,
+    uri: Uri,
+    base_url: Option<String>,
+}
+
+impl Pagination {
+    /// The number of items per page if the `per_page` query parameter is
+    /// missing.
+    pub const DEFAULT_PER_PAGE: u64 = 20;
+
+    /// The maximum number of items per page, the larger `per_page` query
+    /// parameters are capped to it.
+    pub const MAX_PER_PAGE: u64 = 100;
+
+    /// Returns the number of items to skip for the requested page.
+    pub fn offset(&self) -> u64 {
+        (self.page - 1).saturating_mul(self.per_page)
+    }
+
+    fn page_uri(&self, page: u64) -> String {
+        let mut params: Vec<(String, String)> =
+            serde_urlencoded::from_str(self.uri.query().unwrap_or_default()).unwrap_or_default();
+        params.retain(|(name, _)| name != "page" && name != "per_page");
+        params.push(("page".to_string(), page.to_string()));
+        params.push(("per_page".to_string(), self.per_page.to_string()));
+
+        let query = serde_urlencoded::to_string(params).unwrap_or_default();
+        format!(
+            "{}{}?{query}",
+            self.base_url.as_deref().unwrap_or_default(),
+            self.uri.path()
+        )
+    }
+}
+
+/// The configuration of the [`Pagination`] extractor.
+///
+/// It is added to the application with
+/// [`EndpointExt::data`](crate::EndpointExt::data), otherwise the default
+/// configuration is used.
+#[derive(Debug, Clone, Default)]
+pub struct PaginationConfig {
+    base_url: Option<String>,
+}
+
+impl PaginationConfig {
+    /// Create a `PaginationConfig` with the default configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the base URL of the links, such as `https://api.example.com`,
+    /// which is followed by the original path of the request.
+    ///
+    /// Default is `None`, which means that the links are relative to the
+    /// host, since the `Host` header of the request cannot be trusted.
+    #[must_use]
+    pub fn base_url(self, base_url: impl Into<String>) -> Self {
+        let mut base_url = base_url.into();
+        if base_url.ends_with('/') {
+            base_url.pop();
+        }
+        Self {
+            base_url: Some(base_url),
+        }
+    }
+}
+
+impl<'a> FromRequest<'a> for Pagination {
+    async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self> {
+        #[derive(Deserialize)]
+        struct Params {
+            page: Option<u64>,
+            per_page: Option<u64>,
+        }
+
+        let params: Params = serde_urlencoded::from_str(req.uri().query().unwrap_or_default())
+            .map_err(ParseQueryError)?;
+
+        Ok(Self {
+            page: params.page.unwrap_or(1).max(1),
+            per_page: params
+                .per_page
+                .unwrap_or(Self::DEFAULT_PER_PAGE)
+                .clamp(1, Self::MAX_PER_PAGE),
+            // the prefixes of the nested routes are stripped from `req.uri()`
+            uri: req.original_uri().clone(),
+            base_url: req
+                .data::<PaginationConfig>()
+                .and_then(|config| config.base_url.clone()),
+        })
+    }
+}
+
+/// A page of items, which sets the `Link` header
+/// ([RFC5988](https://datatracker.ietf.org/doc/html/rfc5988)) with the
+/// `first`, `prev`, `next` and `last` pages, and the `X-Total-Count` header.
+///
+/// The links are built from the path of the current request, keeping the
+/// other query parameters and replacing `page` and `per_page`.
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     get, handler,
+///     test::TestClient,
+///     web::{Json, Paginated, Pagination},
+///     Route,
+/// };
+///
+/// #[handler]
+/// fn index(pagination: Pagination) -> Paginated<Json<Vec<u64>>> {
+///     let total = 95;
+///     let items = (pagination.offset()..total)
+///         .take(pagination.per_page as usize)
+///         .collect();
+///     Paginated::new(Json(items), pagination, total)
+/// }
+///
+/// let app = Route::new().at("/items", get(index));
+/// let cli = TestClient::new(app);
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = cli
+///     .get("/items")
+///     .query("page", &2)
+///     .query("per_page", &50)
+///     .send()
+///     .await;
+/// resp.assert_header("X-Total-Count", "95");
+/// resp.assert_header(
+///     "Link",
+///     r#"</items?page=1&per_page=50>; rel="first", </items?page=1&per_page=50>; rel="prev", </items?page=2&per_page=50>; rel="last""#,
+/// );
+/// # });
+/// ```
+pub struct Paginated<T> {
+    items: T,
+    pagination: Pagination,
+    total: u64,
+}
+
+impl<T> Paginated<T> {
+    /// Create a `Paginated` with the items of the requested page and the total
+    /// number of items.
+    pub fn new(items: T, pagination: Pagination, total: u64) -> Self {
+        Self {
+            items,
+            pagination,
+            total,
+        }
+    }
+
+    fn link(&self) -> String {
+        let pagination = &self.pagination;
+        let last = self.total.div_ceil(pagination.per_page).max(1);
+
+        let mut links = vec![(1, "first")];
+        if pagination.page > 1 {
+            links.push(((pagination.page - 1).min(last), "prev"));
+        }
+        if pagination.page < last {
+            links.push((pagination.page + 1, "next"));
+        }
+        links.push((last, "last"));
+
+        links
+            .into_iter()
+            .map(|(page, rel)| format!("<{}>; rel=\"{rel}\"", pagination.page_uri(page)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+impl<T: IntoResponse> IntoResponse for Paginated<T> {
+    fn into_response(self) -> Response {
+        let link = self.link();
+        let mut resp = self.items.into_response();
+        if let Ok(link) = HeaderValue::from_str(&link) {
+            resp.headers_mut().insert(header::LINK, link);
+        }
+        resp.headers_mut()
+            .insert("x-total-count", HeaderValue::from(self.total));
+        resp
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{handler, test::TestClient};
+
+    #[handler(internal)]
+    fn index(pagination: Pagination) -> Paginated<String> {
+        Paginated::new(
+            format!("{}:{}", pagination.page, pagination.per_page),
+            pagination,
+            95,
+        )
+    }
+
+    #[tokio::test]
+    async fn paginated() {
+        let cli = TestClient::new(index);
+
+        let resp = cli
+            .get("/items")
+            .query("q", &"a b")
+            .query("page", &2)
+            .query("per_page", &10)
+            .send()
+            .await;
+        resp.assert_header("x-total-count", "95");
+        resp.assert_header(
+            header::LINK,
+            "</items?q=a+b&page=1&per_page=10>; rel=\"first\", \
+             </items?q=a+b&page=1&per_page=10>; rel=\"prev\", \
+             </items?q=a+b&page=3&per_page=10>; rel=\"next\", \
+             </items?q=a+b&page=10&per_page=10>; rel=\"last\"",
+        );
+        resp.assert_text("2:10").await;
+
+        let resp = cli.get("/items").send().await;
+        resp.assert_header(
+            header::LINK,
+            "</items?page=1&per_page=20>; rel=\"first\", \
+             </items?page=2&per_page=20>; rel=\"next\", \
+             </items?page=5&per_page=20>; rel=\"last\"",
+        );
+        resp.assert_text("1:20").await;
+
+        // the previous page of an out of range page is the last page
+        let resp = cli.get("/items").query("page", &20).send().await;
+        resp.assert_header(
+            header::LINK,
+            "</items?page=1&per_page=20>; rel=\"first\", \
+             </items?page=5&per_page=20>; rel=\"prev\", \
+             </items?page=5&per_page=20>; rel=\"last\"",
+        );
+    }
+
+    #[tokio::test]
+    async fn nested() {
+        let cli = TestClient::new(
+            crate::Route::new().nest("/api", crate::Route::new().at("/items", index)),
+        );
+        let resp = cli.get("/api/items").query("page", &2).send().await;
+        resp.assert_header(
+            header::LINK,
+            "</api/items?page=1&per_page=20>; rel=\"first\", \
+             </api/items?page=1&per_page=20>; rel=\"prev\", \
+             </api/items?page=3&per_page=20>; rel=\"next\", \
+             </api/items?page=5&per_page=20>; rel=\"last\"",
+        );
+    }
+
+    #[tokio::test]
+    async fn max_per_page() {
+        let cli = TestClient::new(index);
+        let resp = cli.get("/items").query("per_page", &u64::MAX).send().await;
+        resp.assert_text("1:100").await;
+    }
+
+    #[tokio::test]
+    async fn absolute_links() {
+        // the `Host` header and the authority of the URI are not used
+        for uri in ["/items?page=1", "http://evil.com/items?page=1"] {
+            let req = Request::builder()
+                .uri(Uri::from_static(uri))
+                .header(header::HOST, "evil.com")
+                .finish();
+            let pagination = Pagination::from_request_without_body(&req).await.unwrap();
+            assert_eq!(pagination.page_uri(2), "/items?page=2&per_page=20");
+        }
+
+        let cli = TestClient::new(crate::EndpointExt::data(
+            index,
+            PaginationConfig::new().base_url("https://api.example.com/"),
+        ));
+        let resp = cli
+            .get("/items")
+            .header(header::HOST, "evil.com")
+            .send()
+            .await;
+        resp.assert_header(
+            header::LINK,
+            "<https://api.example.com/items?page=1&per_page=20>; rel=\"first\", \
+             <https://api.example.com/items?page=2&per_page=20>; rel=\"next\", \
+             <https://api.example.com/items?page=5&per_page=20>; rel=\"last\"",
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_params() {
+        let cli = TestClient::new(index);
+        cli.get("/items")
+            .query("page", &"abc")
+            .send()
+            .await
+            .assert_status(http::StatusCode::BAD_REQUEST);
+    }
+}
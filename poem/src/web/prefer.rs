@@ -0,0 +1,304 @@
+use std::fmt::{self, Display, Formatter};
+
+use http::{HeaderMap, HeaderValue};
+
+use crate::{FromRequest, Request, RequestBody, Result};
+
+/// A preference of the [`Prefer`] header.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Preference {
+    /// The name of the preference, in lowercase.
+    pub name: String,
+    /// The value of the preference.
+    pub value: Option<String>,
+    /// The parameters of the preference.
+    pub params: Vec<(String, Option<String>)>,
+}
+
+impl Preference {
+    /// Returns the value of the parameter with the specified name.
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(param, _)| param.eq_ignore_ascii_case(name))
+            .and_then(|(_, value)| value.as_deref())
+    }
+}
+
+impl Display for Preference {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)?;
+        if let Some(value) = &self.value {
+            if !value.is_empty() && value.bytes().all(is_token_char) {
+                write!(f, "={value}")?;
+            } else {
+                write!(
+                    f,
+                    "=\"{}\"",
+                    value.replace('\\', "\\\\").replace('"', "\\\"")
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `Prefer` header, defined in [RFC7240](https://datatracker.ietf.org/doc/html/rfc7240)
+///
+/// If a preference is specified more than once, only the first one is kept.
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     get, handler,
+///     http::StatusCode,
+///     test::TestClient,
+///     web::{Json, Prefer},
+///     IntoResponse, Response, Route,
+/// };
+/// use serde_json::json;
+///
+/// #[handler]
+/// fn create(prefer: Prefer) -> Response {
+///     if prefer.return_minimal() {
+///         let mut resp = StatusCode::NO_CONTENT.into_response();
+///         if let Some(value) = prefer.applied(&["return"]) {
+///             resp.headers_mut().insert("preference-applied", value);
+///         }
+///         resp
+///     } else {
+///         Json(json!({ "id": 1 })).into_response()
+///     }
+/// }
+///
+/// let app = Route::new().at("/", get(create));
+/// let cli = TestClient::new(app);
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = cli
+///     .get("/")
+///     .header("Prefer", "return=minimal")
+///     .send()
+///     .await;
+/// resp.assert_status(StatusCode::NO_CONTENT);
+/// resp.assert_header("Preference-Applied", "return=minimal");
+/// # });
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Prefer(pub Vec<Preference>);
+
+impl Prefer {
+    /// Returns the preference with the specified name.
+    pub fn get(&self, name: &str) -> Option<&Preference> {
+        self.0
+            .iter()
+            .find(|preference| preference.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Returns `true` if the preference with the specified name exists.
+    pub fn contains(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    /// Returns `true` if the client prefers `return=minimal`.
+    pub fn return_minimal(&self) -> bool {
+        self.return_value() == Some("minimal")
+    }
+
+    /// Returns `true` if the client prefers `return=representation`.
+    pub fn return_representation(&self) -> bool {
+        self.return_value() == Some("representation")
+    }
+
+    /// Returns `true` if the client prefers `respond-async`.
+    pub fn respond_async(&self) -> bool {
+        self.contains("respond-async")
+    }
+
+    /// Returns the number of seconds of the `wait` preference.
+    pub fn wait(&self) -> Option<u64> {
+        self.get("wait")?.value.as_deref()?.parse().ok()
+    }
+
+    /// Returns the value of the `Preference-Applied` response header for the
+    /// preferences with the specified names.
+    ///
+    /// The preferences not requested by the client are ignored, and `None` is
+    /// returned if none of them were requested.
+    pub fn applied(&self, names: &[&str]) -> Option<HeaderValue> {
+        let value = names
+            .iter()
+            .filter_map(|name| self.get(name))
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        if value.is_empty() {
+            return None;
+        }
+        HeaderValue::from_str(&value).ok()
+    }
+
+    fn return_value(&self) -> Option<&str> {
+        self.get("return")?.value.as_deref()
+    }
+}
+
+pub(crate) fn is_token_char(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&c)
+}
+
+/// Splits the string by the separator outside the quoted strings.
+pub(crate) fn split_unquoted(s: &str, sep: char) -> Vec<&str> {
+    let mut items = Vec::new();
+    let mut start = 0;
+    let mut quoted = false;
+    let mut escaped = false;
+
+    for (idx, c) in s.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            c if c == sep && !quoted => {
+                items.push(&s[start..idx]);
+                start = idx + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    items.push(&s[start..]);
+    items
+}
+
+fn unquote(value: &str) -> String {
+    match value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+    {
+        Some(value) => {
+            let mut s = String::with_capacity(value.len());
+            let mut chars = value.chars();
+            while let Some(c) = chars.next() {
+                match c {
+                    '\\' => s.extend(chars.next()),
+                    c => s.push(c),
+                }
+            }
+            s
+        }
+        None => value.to_string(),
+    }
+}
+
+fn parse_pair(s: &str) -> Option<(String, Option<String>)> {
+    let (name, value) = match s.split_once('=') {
+        Some((name, value)) => (name.trim(), Some(value.trim())),
+        None => (s.trim(), None),
+    };
+    if name.is_empty() || !name.bytes().all(is_token_char) {
+        return None;
+    }
+    Some((
+        name.to_ascii_lowercase(),
+        value.filter(|value| !value.is_empty()).map(unquote),
+    ))
+}
+
+fn parse_prefer(headers: &HeaderMap) -> Vec<Preference> {
+    let mut preferences: Vec<Preference> = Vec::new();
+
+    for item in headers
+        .get_all("prefer")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| split_unquoted(value, ','))
+    {
+        let mut parts = split_unquoted(item, ';').into_iter();
+        let Some((name, value)) = parts.next().and_then(parse_pair) else {
+            continue;
+        };
+        if preferences.iter().any(|preference| preference.name == name) {
+            continue;
+        }
+        preferences.push(Preference {
+            name,
+            value,
+            params: parts.filter_map(parse_pair).collect(),
+        });
+    }
+
+    preferences
+}
+
+impl<'a> FromRequest<'a> for Prefer {
+    async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self> {
+        Ok(Self(parse_prefer(req.headers())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_prefer() {
+        let req = Request::builder()
+            .header(
+                "Prefer",
+                "return=representation; foo=\"a;b,c\", RESPOND-ASYNC, wait=10",
+            )
+            .header("Prefer", "return=minimal, handling=lenient; strict")
+            .finish();
+        let prefer = Prefer::from_request_without_body(&req).await.unwrap();
+
+        assert_eq!(
+            prefer.0,
+            vec![
+                Preference {
+                    name: "return".to_string(),
+                    value: Some("representation".to_string()),
+                    params: vec![("foo".to_string(), Some("a;b,c".to_string()))],
+                },
+                Preference {
+                    name: "respond-async".to_string(),
+                    value: None,
+                    params: vec![],
+                },
+                Preference {
+                    name: "wait".to_string(),
+                    value: Some("10".to_string()),
+                    params: vec![],
+                },
+                Preference {
+                    name: "handling".to_string(),
+                    value: Some("lenient".to_string()),
+                    params: vec![("strict".to_string(), None)],
+                },
+            ]
+        );
+        assert!(prefer.return_representation());
+        assert!(!prefer.return_minimal());
+        assert!(prefer.respond_async());
+        assert_eq!(prefer.wait(), Some(10));
+        assert_eq!(prefer.get("return").unwrap().param("FOO"), Some("a;b,c"));
+
+        assert_eq!(
+            prefer.applied(&["return", "unknown", "respond-async"]),
+            Some(HeaderValue::from_static(
+                "return=representation, respond-async"
+            ))
+        );
+        assert_eq!(prefer.applied(&["unknown"]), None);
+    }
+
+    #[test]
+    fn display_quoted() {
+        let preference = Preference {
+            name: "foo".to_string(),
+            value: Some("a \"b\"".to_string()),
+            params: vec![],
+        };
+        assert_eq!(preference.to_string(), "foo=\"a \\\"b\\\"\"");
+    }
+}
@@ -0,0 +1,340 @@
+use std::ops::{Deref, DerefMut};
+
+use prost::Message;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    error::ParseProtobufError,
+    http::header,
+    web::{json::is_json_content_type, AcceptMedia, Json, RequestBody},
+    FromRequest, IntoResponse, Request, Response, Result,
+};
+
+/// Protobuf extractor and response.
+///
+/// To extract the specified type of Protobuf from the body, `T` must
+/// implement [`prost::Message`] and [`Default`], the content type of the
+/// request must be `application/x-protobuf` or `application/protobuf`.
+///
+/// # Errors
+///
+/// - [`ReadBodyError`](crate::error::ReadBodyError)
+/// - [`ParseProtobufError`]
+///
+/// ```
+/// use poem::{handler, http::header, post, test::TestClient, web::Protobuf, Route};
+/// use prost::Message;
+///
+/// #[derive(Clone, PartialEq, Message)]
+/// struct User {
+///     #[prost(string, tag = "1")]
+///     name: String,
+/// }
+///
+/// #[handler]
+/// async fn index(Protobuf(user): Protobuf<User>) -> Protobuf<User> {
+///     Protobuf(User {
+///         name: format!("welcome {}!", user.name),
+///     })
+/// }
+///
+/// let app = Route::new().at("/", post(index));
+/// let cli = TestClient::new(app);
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = cli
+///     .post("/")
+///     .header(header::CONTENT_TYPE, "application/x-protobuf")
+///     .body(
+///         User {
+///             name: "foo".to_string(),
+///         }
+///         .encode_to_vec(),
+///     )
+///     .send()
+///     .await;
+/// resp.assert_status_is_ok();
+/// resp.assert_content_type("application/x-protobuf");
+/// resp.assert_bytes(
+///     User {
+///         name: "welcome foo!".to_string(),
+///     }
+///     .encode_to_vec(),
+/// )
+/// .await;
+/// # });
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "protobuf")))]
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct Protobuf<T>(pub T);
+
+impl<T> Deref for Protobuf<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Protobuf<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<'a, T: Message + Default> FromRequest<'a> for Protobuf<T> {
+    async fn from_request(req: &'a Request, body: &mut RequestBody) -> Result<Self> {
+        let content_type = req
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .ok_or(ParseProtobufError::ContentTypeRequired)?;
+        if !is_protobuf_content_type(content_type) {
+            return Err(ParseProtobufError::InvalidContentType(content_type.into()).into());
+        }
+
+        Ok(Self(
+            T::decode(body.take()?.into_bytes().await?).map_err(ParseProtobufError::Parse)?,
+        ))
+    }
+}
+
+fn is_protobuf_content_type(content_type: &str) -> bool {
+    matches!(content_type.parse::<mime::Mime>(),
+        Ok(content_type) if content_type.type_() == "application"
+        && (content_type.subtype() == "x-protobuf"
+        || content_type.subtype() == "protobuf"))
+}
+
+impl<T: Message + Send> IntoResponse for Protobuf<T> {
+    fn into_response(self) -> Response {
+        Response::builder()
+            .header(header::CONTENT_TYPE, "application/x-protobuf")
+            .body(self.0.encode_to_vec())
+    }
+}
+
+/// An extractor and response that speaks both JSON and Protobuf.
+///
+/// As an extractor, the body is parsed as JSON if the content type of the
+/// request is JSON, otherwise it is parsed as Protobuf like [`Protobuf`]. The
+/// variant records the format of the request.
+///
+/// As a response, the value is serialized in the format of the variant, and
+/// [`JsonOrProtobuf::negotiate`] chooses the format from the `Accept` header
+/// of the request.
+///
+/// # Errors
+///
+/// - [`ReadBodyError`](crate::error::ReadBodyError)
+/// - [`ParseJsonError`](crate::error::ParseJsonError)
+/// - [`ParseProtobufError`]
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     handler, http::header, post, test::TestClient, web::JsonOrProtobuf, Request, Route,
+/// };
+/// use prost::Message;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Clone, PartialEq, Message, Serialize, Deserialize)]
+/// struct User {
+///     #[prost(string, tag = "1")]
+///     name: String,
+/// }
+///
+/// #[handler]
+/// async fn index(req: &Request, user: JsonOrProtobuf<User>) -> JsonOrProtobuf<User> {
+///     JsonOrProtobuf::negotiate(req, user.into_inner())
+/// }
+///
+/// let app = Route::new().at("/", post(index));
+/// let cli = TestClient::new(app);
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = cli
+///     .post("/")
+///     .header(header::ACCEPT, "application/json")
+///     .header(header::CONTENT_TYPE, "application/x-protobuf")
+///     .body(
+///         User {
+///             name: "foo".to_string(),
+///         }
+///         .encode_to_vec(),
+///     )
+///     .send()
+///     .await;
+/// resp.assert_status_is_ok();
+/// resp.assert_text(r#"{"name":"foo"}"#).await;
+/// # });
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "protobuf")))]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum JsonOrProtobuf<T> {
+    /// JSON
+    Json(T),
+    /// Protobuf
+    Protobuf(T),
+}
+
+impl<T> JsonOrProtobuf<T> {
+    /// Create a `JsonOrProtobuf` in the format preferred by the `Accept`
+    /// header of the request, which is JSON unless Protobuf is preferred.
+    pub fn negotiate(req: &Request, value: T) -> Self {
+        let prefers_protobuf = AcceptMedia::from_headers(req.headers())
+            .best_match(&[
+                "application/json",
+                "application/x-protobuf",
+                "application/protobuf",
+            ])
+            .is_some_and(is_protobuf_content_type);
+        if prefers_protobuf {
+            Self::Protobuf(value)
+        } else {
+            Self::Json(value)
+        }
+    }
+
+    /// Consumes this object and returns the inner value.
+    pub fn into_inner(self) -> T {
+        match self {
+            Self::Json(value) | Self::Protobuf(value) => value,
+        }
+    }
+}
+
+impl<T> Deref for JsonOrProtobuf<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Self::Json(value) | Self::Protobuf(value) => value,
+        }
+    }
+}
+
+impl<T> DerefMut for JsonOrProtobuf<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match self {
+            Self::Json(value) | Self::Protobuf(value) => value,
+        }
+    }
+}
+
+impl<'a, T: DeserializeOwned + Message + Default> FromRequest<'a> for JsonOrProtobuf<T> {
+    async fn from_request(req: &'a Request, body: &mut RequestBody) -> Result<Self> {
+        let is_json = req
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .is_some_and(is_json_content_type);
+        if is_json {
+            Ok(Self::Json(Json::from_request(req, body).await?.0))
+        } else {
+            Ok(Self::Protobuf(Protobuf::from_request(req, body).await?.0))
+        }
+    }
+}
+
+impl<T: Serialize + Message + Send> IntoResponse for JsonOrProtobuf<T> {
+    fn into_response(self) -> Response {
+        match self {
+            Self::Json(value) => Json(value).into_response(),
+            Self::Protobuf(value) => Protobuf(value).into_response(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::{handler, test::TestClient};
+
+    #[derive(Clone, PartialEq, Message, Serialize, Deserialize)]
+    struct CreateResource {
+        #[prost(string, tag = "1")]
+        name: String,
+        #[prost(int32, tag = "2")]
+        value: i32,
+    }
+
+    fn create_resource() -> CreateResource {
+        CreateResource {
+            name: "abc".to_string(),
+            value: 100,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_protobuf_extractor() {
+        #[handler(internal)]
+        async fn index(Protobuf(resource): Protobuf<CreateResource>) {
+            assert_eq!(resource.name, "abc");
+            assert_eq!(resource.value, 100);
+        }
+
+        let cli = TestClient::new(index);
+        for content_type in ["application/x-protobuf", "application/protobuf"] {
+            cli.post("/")
+                .content_type(content_type)
+                .body(create_resource().encode_to_vec())
+                .send()
+                .await
+                .assert_status_is_ok();
+        }
+
+        cli.post("/")
+            .content_type("application/json")
+            .body(create_resource().encode_to_vec())
+            .send()
+            .await
+            .assert_status(StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        cli.post("/")
+            .content_type("application/x-protobuf")
+            .body("\u{ff}")
+            .send()
+            .await
+            .assert_status(StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_json_or_protobuf() {
+        #[handler(internal)]
+        async fn index(
+            req: &Request,
+            resource: JsonOrProtobuf<CreateResource>,
+        ) -> JsonOrProtobuf<CreateResource> {
+            assert_eq!(*resource, create_resource());
+            JsonOrProtobuf::negotiate(req, resource.into_inner())
+        }
+
+        let cli = TestClient::new(index);
+
+        let resp = cli
+            .post("/")
+            .header(
+                header::ACCEPT,
+                "application/json;q=0.5, application/x-protobuf",
+            )
+            .body_json(&create_resource())
+            .send()
+            .await;
+        resp.assert_content_type("application/x-protobuf");
+        resp.assert_bytes(create_resource().encode_to_vec()).await;
+
+        let resp = cli
+            .post("/")
+            .header(header::ACCEPT, "*/*")
+            .content_type("application/protobuf")
+            .body(create_resource().encode_to_vec())
+            .send()
+            .await;
+        resp.assert_content_type("application/json; charset=utf-8");
+        resp.assert_json(&create_resource()).await;
+    }
+}
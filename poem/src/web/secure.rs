@@ -0,0 +1,256 @@
+use std::net::IpAddr;
+
+use http::uri::Scheme;
+
+use crate::{Addr, FromRequest, Request, RequestBody, Result};
+
+/// The proxies whose forwarding headers are trusted by the [`Secure`]
+/// extractor and the [`SameOrigin`](crate::middleware::SameOrigin)
+/// middleware.
+///
+/// It is added to the application with
+/// [`EndpointExt::data`](crate::EndpointExt::data). Without it, the forwarding
+/// headers are never trusted.
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies {
+    any: bool,
+    networks: Vec<(IpAddr, u8)>,
+}
+
+impl TrustedProxies {
+    /// Create an empty `TrustedProxies`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Trusts the proxy with the specified address.
+    #[must_use]
+    pub fn proxy(self, addr: IpAddr) -> Self {
+        let prefix_len = if addr.is_ipv4() { 32 } else { 128 };
+        self.network(addr, prefix_len)
+    }
+
+    /// Trusts the proxies in the network with the specified address and prefix
+    /// length, such as `10.0.0.0/8`.
+    #[must_use]
+    pub fn network(mut self, addr: IpAddr, prefix_len: u8) -> Self {
+        self.networks.push((addr, prefix_len));
+        self
+    }
+
+    /// Trusts all peers.
+    ///
+    /// This is only safe if the server cannot be reached without passing
+    /// through a proxy that overwrites these headers.
+    #[must_use]
+    pub fn any(self) -> Self {
+        Self { any: true, ..self }
+    }
+
+    /// Returns `true` if the peer with the specified address is trusted.
+    pub fn is_trusted(&self, addr: IpAddr) -> bool {
+        self.any
+            || self
+                .networks
+                .iter()
+                .any(|(network, prefix_len)| in_network(addr, *network, *prefix_len))
+    }
+}
+
+fn in_network(addr: IpAddr, network: IpAddr, prefix_len: u8) -> bool {
+    fn matches(addr: u128, network: u128, bits: u32, prefix_len: u8) -> bool {
+        let prefix_len = u32::from(prefix_len).min(bits);
+        if prefix_len == 0 {
+            return true;
+        }
+        let shift = bits - prefix_len;
+        (addr >> shift) == (network >> shift)
+    }
+
+    match (addr, network) {
+        (IpAddr::V4(addr), IpAddr::V4(network)) => matches(
+            u32::from(addr).into(),
+            u32::from(network).into(),
+            32,
+            prefix_len,
+        ),
+        (IpAddr::V6(addr), IpAddr::V6(network)) => {
+            matches(addr.into(), network.into(), 128, prefix_len)
+        }
+        (IpAddr::V6(addr), IpAddr::V4(_)) => match addr.to_ipv4_mapped() {
+            Some(addr) => in_network(IpAddr::V4(addr), network, prefix_len),
+            None => false,
+        },
+        (IpAddr::V4(_), IpAddr::V6(_)) => false,
+    }
+}
+
+/// An extractor that reports whether the request was received over a secure
+/// transport.
+///
+/// It is `true` if the connection itself uses TLS, or if the peer is one of
+/// the [`TrustedProxies`] and the proxy forwarded the request with `https`
+/// in the `Forwarded` or `X-Forwarded-Proto` header.
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     handler,
+///     test::TestClient,
+///     web::{Secure, TrustedProxies},
+///     EndpointExt,
+/// };
+///
+/// #[handler]
+/// fn index(Secure(secure): Secure) -> String {
+///     secure.to_string()
+/// }
+///
+/// let app = index.data(TrustedProxies::new().proxy([127, 0, 0, 1].into()));
+/// let cli = TestClient::new(app);
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// // the headers of untrusted peers are ignored
+/// let resp = cli
+///     .get("/")
+///     .header("X-Forwarded-Proto", "https")
+///     .send()
+///     .await;
+/// resp.assert_text("false").await;
+/// # });
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Secure(pub bool);
+
+fn forwarded_proto(req: &Request) -> Option<&str> {
+    // the proxy closest to the server appends the last value
+    if let Some(forwarded) = req
+        .headers()
+        .get_all("forwarded")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .next_back()
+    {
+        return rfc7239::parse(forwarded)
+            .next_back()
+            .and_then(Result::ok)
+            .and_then(|item| item.protocol);
+    }
+
+    req.headers()
+        .get_all("x-forwarded-proto")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .next_back()
+        .map(str::trim)
+}
+
+/// Returns whether the request was received over a secure transport, either
+/// directly or through a trusted proxy.
+pub(crate) fn is_secure(req: &Request) -> bool {
+    if req.scheme() == &Scheme::HTTPS {
+        return true;
+    }
+
+    let trusted = match (req.data::<TrustedProxies>(), &req.remote_addr().0) {
+        (Some(proxies), Addr::SocketAddr(addr)) => proxies.is_trusted(addr.ip()),
+        (Some(proxies), _) => proxies.any,
+        (None, _) => false,
+    };
+    if trusted {
+        if let Some(proto) = forwarded_proto(req) {
+            return proto.eq_ignore_ascii_case("https");
+        }
+    }
+
+    false
+}
+
+impl<'a> FromRequest<'a> for Secure {
+    async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self> {
+        Ok(Secure(is_secure(req)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use super::*;
+    use crate::web::RemoteAddr;
+
+    fn request(remote_addr: &str, proxies: TrustedProxies, header: (&str, &str)) -> Request {
+        let mut req = Request::builder().header(header.0, header.1).finish();
+        req.state_mut().remote_addr =
+            RemoteAddr(Addr::SocketAddr(remote_addr.parse::<SocketAddr>().unwrap()));
+        req.extensions_mut().insert(proxies);
+        req
+    }
+
+    async fn is_secure(req: &Request) -> bool {
+        Secure::from_request_without_body(req).await.unwrap().0
+    }
+
+    #[tokio::test]
+    async fn secure() {
+        let proxies = TrustedProxies::new().network([10, 0, 0, 0].into(), 8);
+
+        let req = request(
+            "10.1.2.3:80",
+            proxies.clone(),
+            ("x-forwarded-proto", "https"),
+        );
+        assert!(is_secure(&req).await);
+
+        let req = request(
+            "10.1.2.3:80",
+            proxies.clone(),
+            ("x-forwarded-proto", "https, http"),
+        );
+        assert!(!is_secure(&req).await);
+
+        let req = request(
+            "10.1.2.3:80",
+            proxies.clone(),
+            (
+                "forwarded",
+                "for=192.0.2.43;proto=http, for=10.0.0.1;proto=https",
+            ),
+        );
+        assert!(is_secure(&req).await);
+
+        // untrusted peer
+        let req = request(
+            "192.0.2.1:80",
+            proxies.clone(),
+            ("x-forwarded-proto", "https"),
+        );
+        assert!(!is_secure(&req).await);
+
+        // no trusted proxies
+        let mut req = Request::builder()
+            .header("x-forwarded-proto", "https")
+            .finish();
+        assert!(!is_secure(&req).await);
+
+        req.state_mut().scheme = Scheme::HTTPS;
+        assert!(is_secure(&req).await);
+    }
+
+    #[test]
+    fn trusted_proxies() {
+        let proxies = TrustedProxies::new()
+            .proxy("192.0.2.1".parse().unwrap())
+            .network("2001:db8::".parse().unwrap(), 32);
+        assert!(proxies.is_trusted("192.0.2.1".parse().unwrap()));
+        assert!(proxies.is_trusted("::ffff:192.0.2.1".parse().unwrap()));
+        assert!(!proxies.is_trusted("192.0.2.2".parse().unwrap()));
+        assert!(proxies.is_trusted("2001:db8:1::1".parse().unwrap()));
+        assert!(!proxies.is_trusted("2001:db9::1".parse().unwrap()));
+        assert!(TrustedProxies::new()
+            .any()
+            .is_trusted("192.0.2.2".parse().unwrap()));
+    }
+}
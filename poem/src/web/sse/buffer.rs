@@ -0,0 +1,303 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Weak},
+};
+
+use futures_util::Stream;
+use parking_lot::Mutex;
+use tokio::{
+    sync::broadcast::{self, error::RecvError},
+    time::{Duration, Instant},
+};
+
+use super::Event;
+use crate::{FromRequest, Request, RequestBody, Result};
+
+/// An extractor that extracts the value of the `Last-Event-ID` header, which
+/// is sent by the clients when they reconnect to an event stream.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(docsrs, doc(cfg(feature = "sse")))]
+pub struct LastEventId(pub Option<String>);
+
+impl<'a> FromRequest<'a> for LastEventId {
+    async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self> {
+        Ok(LastEventId(
+            req.header("last-event-id").map(ToString::to_string),
+        ))
+    }
+}
+
+struct State {
+    events: VecDeque<(u64, Instant, Event)>,
+    next_seq: u64,
+    tx: broadcast::Sender<(u64, Event)>,
+}
+
+impl State {
+    fn prune(&mut self, capacity: usize, retention: Option<Duration>) {
+        while self.events.len() > capacity {
+            self.events.pop_front();
+        }
+        if let Some(retention) = retention {
+            let now = Instant::now();
+            while matches!(self.events.front(), Some((_, time, _)) if now - *time > retention) {
+                self.events.pop_front();
+            }
+        }
+    }
+
+    fn events_from(&self, seq: u64) -> VecDeque<(u64, Event)> {
+        self.events
+            .iter()
+            .filter(|(event_seq, _, _)| *event_seq >= seq)
+            .map(|(event_seq, _, event)| (*event_seq, event.clone()))
+            .collect()
+    }
+}
+
+/// A bounded buffer of recent events that allows the clients to resume an
+/// event stream.
+///
+/// Events published with [`EventBuffer::push`] are kept in a ring buffer and
+/// sent to all subscribers. When a client reconnects with the
+/// [`Last-Event-ID`](LastEventId) header, the events after that id are
+/// replayed before resuming the live stream. If the id is no longer in the
+/// buffer, all buffered events are replayed.
+///
+/// A subscriber that falls behind the live stream also catches up from the
+/// buffer, so no events are lost as long as they are still buffered.
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     get, handler,
+///     web::{
+///         sse::{Event, EventBuffer, LastEventId, SSE},
+///         Data,
+///     },
+///     EndpointExt, Route,
+/// };
+///
+/// #[handler]
+/// fn events(buffer: Data<&EventBuffer>, last_event_id: LastEventId) -> SSE {
+///     SSE::from_buffer(&buffer, last_event_id.0.as_deref())
+/// }
+///
+/// let buffer = EventBuffer::new(100);
+/// buffer.push(Event::message("hello"));
+///
+/// let app = Route::new().at("/events", get(events)).data(buffer);
+/// ```
+#[derive(Clone)]
+#[cfg_attr(docsrs, doc(cfg(feature = "sse")))]
+pub struct EventBuffer {
+    capacity: usize,
+    retention: Option<Duration>,
+    state: Arc<Mutex<State>>,
+}
+
+impl EventBuffer {
+    /// Create an `EventBuffer` that keeps at most `capacity` events.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "capacity must be greater than zero");
+        Self {
+            capacity,
+            retention: None,
+            state: Arc::new(Mutex::new(State {
+                events: VecDeque::with_capacity(capacity),
+                next_seq: 0,
+                tx: broadcast::channel(capacity).0,
+            })),
+        }
+    }
+
+    /// Sets the maximum duration that the events are kept in the buffer.
+    ///
+    /// By default, the events are kept until they are evicted by newer ones.
+    #[must_use]
+    pub fn retention(self, retention: Duration) -> Self {
+        Self {
+            retention: Some(retention),
+            ..self
+        }
+    }
+
+    /// Publish an event to all subscribers.
+    ///
+    /// If the event is a message without an id, the sequence number of the
+    /// event is used as the id.
+    pub fn push(&self, event: impl Into<Event>) {
+        let mut event = event.into();
+        let mut state = self.state.lock();
+        let seq = state.next_seq;
+        state.next_seq += 1;
+
+        if let Event::Message { id, .. } = &mut event {
+            if id.is_empty() {
+                *id = seq.to_string();
+            }
+        }
+
+        state.events.push_back((seq, Instant::now(), event.clone()));
+        state.prune(self.capacity, self.retention);
+        let _ = state.tx.send((seq, event));
+    }
+
+    /// Subscribe to the buffer, replaying the events after `last_event_id`.
+    ///
+    /// If `last_event_id` is `None`, only the new events are returned. The
+    /// stream ends when all `EventBuffer` instances are dropped.
+    pub fn subscribe(&self, last_event_id: Option<&str>) -> impl Stream<Item = Event> + Send {
+        let (rx, next, pending) = {
+            let mut state = self.state.lock();
+            state.prune(self.capacity, self.retention);
+            let rx = state.tx.subscribe();
+            let next = match last_event_id {
+                Some(last_event_id) => match state.events.iter().find(|(_, _, event)| {
+                    matches!(event, Event::Message { id, .. } if id == last_event_id)
+                }) {
+                    Some((seq, _, _)) => seq + 1,
+                    None => state
+                        .events
+                        .front()
+                        .map(|(seq, _, _)| *seq)
+                        .unwrap_or(state.next_seq),
+                },
+                None => state.next_seq,
+            };
+            (rx, next, state.events_from(next))
+        };
+
+        let subscription = Subscription {
+            capacity: self.capacity,
+            retention: self.retention,
+            state: Arc::downgrade(&self.state),
+            rx,
+            next,
+            pending,
+        };
+
+        futures_util::stream::unfold(subscription, |mut subscription| async move {
+            let event = subscription.next_event().await?;
+            Some((event, subscription))
+        })
+    }
+}
+
+struct Subscription {
+    capacity: usize,
+    retention: Option<Duration>,
+    state: Weak<Mutex<State>>,
+    rx: broadcast::Receiver<(u64, Event)>,
+    next: u64,
+    pending: VecDeque<(u64, Event)>,
+}
+
+impl Subscription {
+    async fn next_event(&mut self) -> Option<Event> {
+        loop {
+            if let Some((seq, event)) = self.pending.pop_front() {
+                self.next = seq + 1;
+                return Some(event);
+            }
+
+            match self.rx.recv().await {
+                Ok((seq, _)) if seq < self.next => continue,
+                Ok((seq, event)) => {
+                    self.next = seq + 1;
+                    return Some(event);
+                }
+                Err(RecvError::Lagged(_)) => {
+                    if let Some(state) = self.state.upgrade() {
+                        let mut state = state.lock();
+                        state.prune(self.capacity, self.retention);
+                        self.pending = state.events_from(self.next);
+                    }
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::StreamExt;
+
+    use super::*;
+
+    async fn collect(stream: impl Stream<Item = Event>, n: usize) -> Vec<String> {
+        stream
+            .take(n)
+            .map(|event| match event {
+                Event::Message { id, data, .. } => format!("{id}:{data}"),
+                Event::Retry { retry } => format!("retry:{retry}"),
+            })
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn replay() {
+        let buffer = EventBuffer::new(3);
+        for i in 0..5 {
+            buffer.push(Event::message(i.to_string()));
+        }
+
+        let stream = buffer.subscribe(Some("3"));
+        buffer.push(Event::message("5").id("a"));
+        assert_eq!(collect(stream, 2).await, vec!["4:4", "a:5"]);
+
+        // unknown id, replay all buffered events
+        let stream = buffer.subscribe(Some("0"));
+        assert_eq!(collect(stream, 3).await, vec!["3:3", "4:4", "a:5"]);
+
+        let stream = buffer.subscribe(None);
+        buffer.push(Event::message("6"));
+        drop(buffer);
+        assert_eq!(collect(stream, 10).await, vec!["6:6"]);
+    }
+
+    #[tokio::test]
+    async fn lagged() {
+        let buffer = EventBuffer::new(2);
+        let stream = buffer.subscribe(None);
+        for i in 0..3 {
+            buffer.push(Event::message(i.to_string()));
+        }
+        drop(buffer);
+        assert_eq!(collect(stream, 10).await, vec!["1:1", "2:2"]);
+    }
+
+    #[tokio::test]
+    async fn retention() {
+        let buffer = EventBuffer::new(10).retention(Duration::from_millis(100));
+        buffer.push(Event::message("0"));
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        buffer.push(Event::message("1"));
+
+        let stream = buffer.subscribe(Some("unknown"));
+        drop(buffer);
+        assert_eq!(collect(stream, 10).await, vec!["1:1"]);
+    }
+
+    #[tokio::test]
+    async fn last_event_id() {
+        let req = Request::builder().header("Last-Event-ID", "12").finish();
+        assert_eq!(
+            LastEventId::from_request_without_body(&req).await.unwrap(),
+            LastEventId(Some("12".to_string()))
+        );
+
+        let req = Request::builder().finish();
+        assert_eq!(
+            LastEventId::from_request_without_body(&req).await.unwrap(),
+            LastEventId(None)
+        );
+    }
+}
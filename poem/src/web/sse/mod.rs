@@ -1,10 +1,12 @@
 //! Server-Sent Events (SSE) types.
 
+mod buffer;
 mod event;
 mod response;
 
+pub use buffer::{EventBuffer, LastEventId};
 pub use event::Event;
-pub use response::SSE;
+pub use response::{LagPolicy, SSE};
 
 #[cfg(test)]
 mod tests {
@@ -53,6 +55,50 @@ data: 5
         );
     }
 
+    #[tokio::test]
+    async fn from_broadcast() {
+        let (tx, rx) = tokio::sync::broadcast::channel(2);
+        for i in 0..4 {
+            tx.send(Event::message(i.to_string())).unwrap();
+        }
+        drop(tx);
+
+        let data = SSE::from_broadcast(rx, LagPolicy::Notify)
+            .into_response()
+            .into_body()
+            .into_string()
+            .await
+            .unwrap();
+        assert_eq!(data, "event: lagged\ndata: 2\n\ndata: 2\n\ndata: 3\n\n");
+
+        let (tx, rx) = tokio::sync::broadcast::channel(2);
+        for i in 0..4 {
+            tx.send(Event::message(i.to_string())).unwrap();
+        }
+        drop(tx);
+
+        let data = SSE::from_broadcast(rx, LagPolicy::Skip)
+            .into_response()
+            .into_body()
+            .into_string()
+            .await
+            .unwrap();
+        assert_eq!(data, "data: 2\n\ndata: 3\n\n");
+
+        let (tx, rx) = tokio::sync::broadcast::channel(2);
+        for i in 0..4 {
+            tx.send(Event::message(i.to_string())).unwrap();
+        }
+
+        let data = SSE::from_broadcast(rx, LagPolicy::Disconnect)
+            .into_response()
+            .into_body()
+            .into_string()
+            .await
+            .unwrap();
+        assert!(data.is_empty());
+    }
+
     #[tokio::test]
     async fn keep_alive() {
         let sse = SSE::new(futures_util::stream::pending()).keep_alive(Duration::from_secs(1));
@@ -2,11 +2,27 @@ use std::task::Poll;
 
 use bytes::Bytes;
 use futures_util::{stream::BoxStream, Stream, StreamExt};
-use tokio::time::Duration;
+use tokio::{
+    sync::broadcast::{error::RecvError, Receiver},
+    time::Duration,
+};
 
-use super::Event;
+use super::{Event, EventBuffer};
 use crate::{Body, IntoResponse, Response};
 
+/// What to do when a client subscribed with [`SSE::from_broadcast`] falls
+/// behind and misses messages.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum LagPolicy {
+    /// Skip the missed messages silently.
+    Skip,
+    /// Skip the missed messages and send an event with type `lagged` whose
+    /// data is the number of missed messages.
+    Notify,
+    /// Close the event stream.
+    Disconnect,
+}
+
 /// An SSE response.
 ///
 /// # Example
@@ -52,6 +68,58 @@ impl SSE {
         }
     }
 
+    /// Create an SSE response from a broadcast [`Receiver`].
+    ///
+    /// Each client should subscribe its own receiver. The `lag_policy`
+    /// determines what to do when the client cannot keep up with the
+    /// sender, and the stream ends when the sender is dropped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::{
+    ///     handler,
+    ///     web::{
+    ///         sse::{Event, LagPolicy, SSE},
+    ///         Data,
+    ///     },
+    /// };
+    /// use tokio::sync::broadcast::Sender;
+    ///
+    /// #[handler]
+    /// fn index(tx: Data<&Sender<Event>>) -> SSE {
+    ///     SSE::from_broadcast(tx.subscribe(), LagPolicy::Notify)
+    /// }
+    /// ```
+    pub fn from_broadcast<T>(rx: Receiver<T>, lag_policy: LagPolicy) -> Self
+    where
+        T: Into<Event> + Clone + Send + 'static,
+    {
+        Self::new(futures_util::stream::unfold(rx, move |mut rx| async move {
+            loop {
+                match rx.recv().await {
+                    Ok(item) => return Some((item.into(), rx)),
+                    Err(RecvError::Lagged(n)) => match lag_policy {
+                        LagPolicy::Skip => continue,
+                        LagPolicy::Notify => {
+                            return Some((Event::message(n.to_string()).event_type("lagged"), rx))
+                        }
+                        LagPolicy::Disconnect => return None,
+                    },
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        }))
+    }
+
+    /// Create an SSE response that subscribes to an [`EventBuffer`].
+    ///
+    /// The events after `last_event_id` are replayed before the live events,
+    /// see [`EventBuffer::subscribe`].
+    pub fn from_buffer(buffer: &EventBuffer, last_event_id: Option<&str>) -> Self {
+        Self::new(buffer.subscribe(last_event_id))
+    }
+
     /// Set the keep alive interval.
     #[must_use]
     pub fn keep_alive(self, duration: Duration) -> Self {
@@ -38,6 +38,9 @@ pub enum StaticFileResponse {
         last_modified: Option<String>,
         /// `Content-Range` header value
         content_range: Option<(std::ops::Range<u64>, u64)>,
+        /// Whether range requests are supported, `Accept-Ranges: none` is
+        /// sent if it is `false`
+        accept_ranges: bool,
     },
     /// 304 NOT MODIFIED
     NotModified,
@@ -63,9 +66,13 @@ impl IntoResponse for StaticFileResponse {
                 etag,
                 last_modified,
                 content_range,
+                accept_ranges,
             } => {
                 let mut builder = Response::builder()
-                    .header(header::ACCEPT_RANGES, "bytes")
+                    .header(
+                        header::ACCEPT_RANGES,
+                        if accept_ranges { "bytes" } else { "none" },
+                    )
                     .header(header::CONTENT_LENGTH, content_length);
 
                 if let Some(content_type) = content_type {
@@ -99,6 +106,7 @@ pub struct StaticFileRequest {
     if_none_match: Option<IfNoneMatch>,
     if_modified_since: Option<IfModifiedSince>,
     range: Option<Range>,
+    accept_ranges: bool,
 }
 
 impl<'a> FromRequest<'a> for StaticFileRequest {
@@ -109,11 +117,28 @@ impl<'a> FromRequest<'a> for StaticFileRequest {
             if_none_match: req.headers().typed_get::<IfNoneMatch>(),
             if_modified_since: req.headers().typed_get::<IfModifiedSince>(),
             range: req.headers().typed_get::<Range>(),
+            accept_ranges: true,
         })
     }
 }
 
 impl StaticFileRequest {
+    /// Specifies whether range requests are supported.
+    ///
+    /// If it is `false`, the `Range` header is ignored, the full content is
+    /// returned and the response contains `Accept-Ranges: none`. This is
+    /// useful for the content that cannot be read efficiently from an offset.
+    ///
+    /// Default is `true`.
+    #[must_use]
+    pub fn accept_ranges(self, accept_ranges: bool) -> Self {
+        Self {
+            range: self.range.filter(|_| accept_ranges),
+            accept_ranges,
+            ..self
+        }
+    }
+
     /// Create static file response.
     ///
     /// `prefer_utf8` - Specifies whether text responses should signal a UTF-8
@@ -167,6 +192,7 @@ impl StaticFileRequest {
             etag: None,
             last_modified: None,
             content_range,
+            accept_ranges: self.accept_ranges,
         })
     }
 
@@ -280,6 +306,7 @@ impl StaticFileRequest {
                 None
             },
             content_range,
+            accept_ranges: self.accept_ranges,
         })
     }
 }
@@ -560,4 +587,36 @@ mod tests {
             _ => panic!(),
         }
     }
+
+    #[tokio::test]
+    async fn test_disable_ranges() {
+        let md = std::fs::metadata("Cargo.toml").unwrap();
+
+        let static_file = StaticFileRequest::from_request_without_body(
+            &Request::builder()
+                .typed_header(Range::bytes(0..10).unwrap())
+                .finish(),
+        )
+        .await
+        .unwrap()
+        .accept_ranges(false);
+        let resp = static_file
+            .create_response(Path::new("Cargo.toml"), false)
+            .unwrap();
+        match &resp {
+            StaticFileResponse::Ok {
+                content_range,
+                content_length,
+                ..
+            } => {
+                assert!(content_range.is_none());
+                assert_eq!(*content_length, md.len());
+            }
+            StaticFileResponse::NotModified => panic!(),
+        }
+
+        let resp = resp.into_response();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.header(header::ACCEPT_RANGES), Some("none"));
+    }
 }
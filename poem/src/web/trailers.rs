@@ -0,0 +1,180 @@
+use std::{
+    io::Error as IoError,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use http::{HeaderMap, HeaderValue};
+use hyper::body::{Frame, SizeHint};
+use parking_lot::Mutex;
+
+use crate::{body::BoxBody, Body, FromRequest, Request, RequestBody, Result};
+
+/// An extractor for the trailers of the request body.
+///
+/// The trailers are sent after the body, so this extractor does not read the
+/// body, it only captures the trailers while the body is read by the other
+/// extractors or the handler. It must be declared before the extractor that
+/// reads the body.
+///
+/// Trailers are supported by HTTP/2, and by HTTP/1.1 with
+/// `Transfer-Encoding: chunked`.
+///
+/// # Example
+///
+/// ```
+/// use poem::{handler, http::HeaderMap, test::TestClient, web::Trailers, Body};
+///
+/// #[handler]
+/// fn index(trailers: Trailers, data: String) -> String {
+///     let checksum = trailers
+///         .get("checksum")
+///         .and_then(|value| value.to_str().ok().map(ToString::to_string));
+///     format!("{} {}", data.len(), checksum.unwrap_or_default())
+/// }
+///
+/// let cli = TestClient::new(index);
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let body = Body::from("hello").with_trailers(async move {
+///     let mut trailers = HeaderMap::new();
+///     trailers.insert("checksum", "abc".parse().unwrap());
+///     trailers
+/// });
+/// let resp = cli.post("/").body(body).send().await;
+/// resp.assert_text("5 abc").await;
+/// # });
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Trailers(Arc<Mutex<Option<HeaderMap>>>);
+
+impl Trailers {
+    /// Returns `true` if the body has been read to the end and the trailers
+    /// have been received.
+    pub fn is_complete(&self) -> bool {
+        self.0.lock().is_some()
+    }
+
+    /// Returns the trailers, or `None` if the body has not been read to the
+    /// end.
+    ///
+    /// If the request has no trailers, an empty [`HeaderMap`] is returned
+    /// after the body has been read.
+    pub fn headers(&self) -> Option<HeaderMap> {
+        self.0.lock().clone()
+    }
+
+    /// Returns the value of the trailer with the specified name.
+    pub fn get(&self, name: &str) -> Option<HeaderValue> {
+        self.0.lock().as_ref()?.get(name).cloned()
+    }
+}
+
+impl<'a> FromRequest<'a> for Trailers {
+    async fn from_request(_req: &'a Request, body: &mut RequestBody) -> Result<Self> {
+        let trailers = Trailers::default();
+        let inner = body.take()?;
+        *body = RequestBody::new(Body(BoxBody::new(CaptureTrailersBody {
+            inner: inner.0,
+            trailers: trailers.clone(),
+        })));
+        Ok(trailers)
+    }
+}
+
+struct CaptureTrailersBody {
+    inner: BoxBody,
+    trailers: Trailers,
+}
+
+impl hyper::body::Body for CaptureTrailersBody {
+    type Data = Bytes;
+    type Error = IoError;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let res = futures_util::ready!(Pin::new(&mut self.inner).poll_frame(cx));
+        match &res {
+            Some(Ok(frame)) => {
+                if let Some(headers) = frame.trailers_ref() {
+                    let mut trailers = self.trailers.0.lock();
+                    match &mut *trailers {
+                        Some(trailers) => trailers.extend(headers.clone()),
+                        None => *trailers = Some(headers.clone()),
+                    }
+                }
+            }
+            Some(Err(_)) => {}
+            None => {
+                self.trailers.0.lock().get_or_insert_with(HeaderMap::new);
+            }
+        }
+        Poll::Ready(res)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{handler, test::TestClient};
+
+    #[handler(internal)]
+    async fn index(trailers: Trailers, data: String) -> String {
+        assert!(trailers.is_complete());
+        format!(
+            "{data} {}",
+            trailers
+                .get("checksum")
+                .map(|value| value.to_str().unwrap().to_string())
+                .unwrap_or_default()
+        )
+    }
+
+    #[tokio::test]
+    async fn trailers() {
+        let cli = TestClient::new(index);
+
+        let body = Body::from("abc").with_trailers(async move {
+            let mut trailers = HeaderMap::new();
+            trailers.insert("checksum", "123".parse().unwrap());
+            trailers
+        });
+        cli.post("/")
+            .body(body)
+            .send()
+            .await
+            .assert_text("abc 123")
+            .await;
+
+        cli.post("/")
+            .body("abc")
+            .send()
+            .await
+            .assert_text("abc ")
+            .await;
+    }
+
+    #[tokio::test]
+    async fn not_complete() {
+        let mut body = RequestBody::new(Body::from("abc"));
+        let trailers = Trailers::from_request(&Request::default(), &mut body)
+            .await
+            .unwrap();
+        assert!(trailers.headers().is_none());
+
+        body.take().unwrap().into_bytes().await.unwrap();
+        assert_eq!(trailers.headers(), Some(HeaderMap::new()));
+    }
+}
@@ -1,4 +1,4 @@
-use std::{borrow::Cow, future::Future};
+use std::{borrow::Cow, future::Future, sync::Arc};
 
 use futures_util::{future::BoxFuture, FutureExt};
 use headers::HeaderMapExt;
@@ -11,6 +11,7 @@ use crate::{
         header::{self, HeaderValue},
         Method, StatusCode,
     },
+    middleware::WebSocketConnectionGuard,
     Body, FromRequest, IntoResponse, OnUpgrade, Request, RequestBody, Response, Result,
 };
 
@@ -24,6 +25,7 @@ pub struct WebSocket {
     on_upgrade: OnUpgrade,
     protocols: Option<Box<[Cow<'static, str>]>>,
     sec_websocket_protocol: Option<HeaderValue>,
+    connection_guard: Option<Arc<WebSocketConnectionGuard>>,
 }
 
 impl WebSocket {
@@ -62,6 +64,10 @@ impl WebSocket {
             on_upgrade: req.take_upgrade()?,
             protocols: None,
             sec_websocket_protocol,
+            connection_guard: req
+                .extensions()
+                .get::<Arc<WebSocketConnectionGuard>>()
+                .cloned(),
         })
     }
 }
@@ -192,7 +198,11 @@ where
 
         let resp = builder.body(Body::empty());
 
+        let connection_guard = self.websocket.connection_guard;
         tokio::spawn(async move {
+            // released when the connection is closed or failed to upgrade
+            let _connection_guard = connection_guard;
+
             let upgraded = match self.websocket.on_upgrade.await {
                 Ok(upgraded) => upgraded,
                 Err(_) => return,
@@ -29,7 +29,7 @@ mod utils;
 
 pub use extractor::{BoxWebSocketUpgraded, WebSocket, WebSocketUpgraded};
 pub use message::{CloseCode, Message};
-pub use stream::WebSocketStream;
+pub use stream::{WebSocketReceiver, WebSocketSender, WebSocketStream};
 
 #[cfg(test)]
 mod tests {
@@ -160,4 +160,64 @@ mod tests {
 
         handle.abort();
     }
+
+    #[tokio::test]
+    async fn test_forward_from() {
+        #[handler(internal)]
+        async fn index(ws: WebSocket) -> impl IntoResponse {
+            ws.on_upgrade(|stream| async move {
+                let (mut sender, mut receiver) = stream.split();
+                let Some(Ok(Message::Text(text))) = receiver.next().await else {
+                    return;
+                };
+                sender.send(Message::text(text)).await.unwrap();
+                let stream = sender.reunite(receiver).unwrap();
+                let messages = futures_util::stream::iter(1..=3)
+                    .map(|n| Message::text(n.to_string()))
+                    .chain(futures_util::stream::pending());
+                stream.forward_from(messages).await.unwrap();
+            })
+        }
+
+        let acceptor = TcpListener::bind("127.0.0.1:0")
+            .into_acceptor()
+            .await
+            .unwrap();
+        let addr = acceptor
+            .local_addr()
+            .remove(0)
+            .as_socket_addr()
+            .cloned()
+            .unwrap();
+
+        let handle = tokio::spawn(async move {
+            let _ = Server::new_with_acceptor(acceptor).run(index).await;
+        });
+
+        let (mut client_stream, _) = tokio_tungstenite::connect_async(format!("ws://{addr}"))
+            .await
+            .unwrap();
+        client_stream
+            .send(tokio_tungstenite::tungstenite::Message::Text(
+                "hello".to_string(),
+            ))
+            .await
+            .unwrap();
+
+        for text in ["hello", "1", "2", "3"] {
+            assert_eq!(
+                client_stream.next().await.unwrap().unwrap(),
+                tokio_tungstenite::tungstenite::Message::Text(text.to_string())
+            );
+        }
+
+        // the client closes the connection while the source stream is pending
+        client_stream.close(None).await.unwrap();
+        assert!(matches!(
+            client_stream.next().await,
+            Some(Ok(tokio_tungstenite::tungstenite::Message::Close(_)))
+        ));
+
+        handle.abort();
+    }
 }
@@ -4,11 +4,22 @@ use std::{
     task::{Context, Poll},
 };
 
-use futures_util::{Sink, SinkExt, Stream, StreamExt};
+use futures_util::{
+    stream::{SplitSink, SplitStream},
+    Sink, SinkExt, Stream, StreamExt,
+};
 
 use super::{utils::tungstenite_error_to_io_error, Message};
 use crate::Upgraded;
 
+/// The sending half of a [`WebSocketStream`], returned by
+/// [`WebSocketStream::split`].
+pub type WebSocketSender = SplitSink<WebSocketStream, Message>;
+
+/// The receiving half of a [`WebSocketStream`], returned by
+/// [`WebSocketStream::split`].
+pub type WebSocketReceiver = SplitStream<WebSocketStream>;
+
 /// A `WebSocket` stream, which implements [`Stream<Message>`] and
 /// [`Sink<Message>`].
 pub struct WebSocketStream {
@@ -19,6 +30,85 @@ impl WebSocketStream {
     pub(crate) fn new(inner: tokio_tungstenite::WebSocketStream<Upgraded>) -> Self {
         Self { inner }
     }
+
+    /// Splits this stream into a sender and a receiver, which can be used
+    /// in different tasks.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use futures_util::{SinkExt, StreamExt};
+    /// use poem::{
+    ///     handler,
+    ///     web::websocket::{Message, WebSocket},
+    ///     IntoResponse,
+    /// };
+    ///
+    /// #[handler]
+    /// async fn index(ws: WebSocket) -> impl IntoResponse {
+    ///     ws.on_upgrade(|socket| async move {
+    ///         let (mut sender, mut receiver) = socket.split();
+    ///         tokio::spawn(async move {
+    ///             while let Some(Ok(msg)) = receiver.next().await {
+    ///                 println!("{msg:?}");
+    ///             }
+    ///         });
+    ///         let _ = sender.send(Message::text("hello")).await;
+    ///     })
+    /// }
+    /// ```
+    pub fn split(self) -> (WebSocketSender, WebSocketReceiver) {
+        StreamExt::split(self)
+    }
+
+    /// Sends all messages of the `stream` to the client, then closes the
+    /// connection.
+    ///
+    /// It returns early if the client closes the connection. The incoming
+    /// messages other than close frames are discarded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use futures_util::stream;
+    /// use poem::{
+    ///     handler,
+    ///     web::websocket::{Message, WebSocket},
+    ///     IntoResponse,
+    /// };
+    ///
+    /// #[handler]
+    /// async fn index(ws: WebSocket) -> impl IntoResponse {
+    ///     ws.on_upgrade(|socket| async move {
+    ///         let messages = stream::iter(vec![Message::text("a"), Message::text("b")]);
+    ///         let _ = socket.forward_from(messages).await;
+    ///     })
+    /// }
+    /// ```
+    pub async fn forward_from<S>(mut self, stream: S) -> IoResult<()>
+    where
+        S: Stream<Item = Message> + Send,
+    {
+        let mut stream = std::pin::pin!(stream);
+
+        loop {
+            tokio::select! {
+                msg = stream.next() => match msg {
+                    Some(msg) => self.send(msg).await?,
+                    None => return self.close().await,
+                },
+                incoming = self.next() => match incoming {
+                    Some(Ok(Message::Close(_))) | None => {
+                        // flush the close frame replied to the client
+                        let _ = self.flush().await;
+                        return Ok(());
+                    }
+                    Some(Ok(_)) => {}
+                    Some(Err(err)) => return Err(err),
+                },
+            }
+        }
+    }
 }
 
 impl Stream for WebSocketStream {